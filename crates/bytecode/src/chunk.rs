@@ -1,6 +1,6 @@
 use crate::InstructionReader;
 use koto_memory::Ptr;
-use koto_parser::{ConstantPool, KString, Span};
+use koto_parser::{ConstantIndex, ConstantPool, KString, Span};
 use std::fmt::{self, Write};
 
 /// Debug information for a Koto program
@@ -9,6 +9,8 @@ pub struct DebugInfo {
     source_map: Vec<(u32, Span)>,
     /// The source of the program that the debug info was derived from
     pub source: String,
+    /// The program's top-level local ids, paired with their assigned registers
+    pub top_level_locals: Vec<(ConstantIndex, u8)>,
 }
 
 impl DebugInfo {
@@ -30,18 +32,28 @@ impl DebugInfo {
 
     /// Returns a source span for a given instruction pointer
     pub fn get_source_span(&self, ip: u32) -> Option<Span> {
-        // Find the last entry with an ip less than or equal to the input
-        // an upper_bound would nice here, but this isn't currently a performance sensitive function
-        // so a scan through the entries will do.
-        let mut result = None;
-        for entry in self.source_map.iter() {
-            if entry.0 <= ip {
-                result = Some(entry.1);
-            } else {
-                break;
-            }
+        // Find the last entry with an ip less than or equal to the input.
+        // Entries are pushed in ip order, so a binary search can be used, which is important when
+        // spans are being looked up for every instruction (e.g. when checking for breakpoints).
+        let end = self
+            .source_map
+            .partition_point(|(entry_ip, _)| *entry_ip <= ip);
+        end.checked_sub(1).map(|index| self.source_map[index].1)
+    }
+
+    /// Returns the entries of the source map
+    ///
+    /// Each entry contains the first instruction ip that the entry's span applies to,
+    /// with the span applying to all following instructions until the next entry.
+    pub fn source_map(&self) -> &[(u32, Span)] {
+        &self.source_map
+    }
+
+    /// Updates the ips in the source map, used when instructions have been removed from a chunk
+    pub(crate) fn remap_ips(&mut self, remap: impl Fn(u32) -> u32) {
+        for (ip, _) in self.source_map.iter_mut() {
+            *ip = remap(*ip);
         }
-        result
     }
 }
 
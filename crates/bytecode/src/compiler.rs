@@ -1,15 +1,17 @@
 use crate::{
-    Chunk, DebugInfo, FunctionFlags, Op, StringFormatFlags,
-    frame::{Arg, AssignedOrReserved, Frame, FrameError},
+    Chunk, DebugInfo, Diagnostic, DiagnosticKind, FunctionFlags, Op, StringFormatFlags,
+    frame::{Arg, AssignedOrReserved, Frame, FrameError, TryBlock},
+    peephole,
 };
 use circular_buffer::CircularBuffer;
 use derive_name::VariantName;
 use koto_parser::{
-    Ast, AstBinaryOp, AstFor, AstIf, AstIndex, AstNode, AstTry, AstUnaryOp, AstVec, ChainNode,
-    ConstantIndex, Function, ImportItem, KString, MetaKeyId, Node, Parser, Span, StringContents,
-    StringFormatOptions, StringNode,
+    Ast, AstBinaryOp, AstFor, AstIf, AstIndex, AstNode, AstString, AstTry, AstUnaryOp, AstVec,
+    ChainNode, ConstantIndex, Function, ImportItem, KString, MetaKeyId, Node, Parser, Span,
+    StringContents, StringFormatOptions, StringNode,
 };
 use smallvec::{SmallVec, smallvec};
+use std::collections::HashSet;
 use thiserror::Error;
 
 /// The different error types that can be thrown by the Koto runtime
@@ -28,8 +30,6 @@ enum ErrorKind {
     InvalidLoopKeyword(String),
     #[error("invalid match pattern (found '{0:?}')")]
     InvalidMatchPattern(Node),
-    #[error("args with ellipses are only allowed in first or last position")]
-    InvalidPositionForArgWithEllipses,
     #[error(
         "the jump offset here is too large. {0} bytes is larger than the maximum of {max}.
              Try breaking up this part of the program a bit",
@@ -58,18 +58,20 @@ enum ErrorKind {
     MissingTypeCheckOnCatchBlock,
     #[error("missing value for Map entry")]
     MissingValueForMapEntry,
+    #[error("only one arg with ellipses is allowed")]
+    MultipleArgsWithEllipses,
     #[error("only one ellipsis is allowed in a match arm")]
     MultipleMatchEllipses,
     #[error("the compiled expression has no output")]
     NoResultInExpressionOutput,
     #[error("child chain node out of position")]
     OutOfPositionChildNodeInChain,
-    #[error("matching with ellipses is only allowed in first or last position")]
-    OutOfPositionMatchEllipsis,
     #[error("root chain node out of position")]
     OutOfPositionRootNodeInChain,
     #[error("the compiled bytecode is larger than the maximum size of 4GB (size: {0} bytes)")]
     ResultingBytecodeIsTooLarge(usize),
+    #[error("`throw` without a value can only be used in a catch block")]
+    RethrowOutsideOfCatchBlock,
     #[error("too many targets in assignment ({0})")]
     TooManyAssignmentTargets(usize),
     #[error(
@@ -87,7 +89,11 @@ enum ErrorKind {
     UnexpectedIgnoredValue,
     #[error("expected {expected} patterns in match arm, found {unexpected}")]
     UnexpectedMatchPatternCount { expected: usize, unexpected: usize },
+    #[error("the '{0}' operator isn't supported by the runtime yet")]
+    UnsupportedBinaryOp(AstBinaryOp),
 
+    #[error("{0}")]
+    Diagnostic(#[from] DiagnosticKind),
     #[error("{0}")]
     Parser(#[from] koto_parser::Error),
     #[error(transparent)]
@@ -125,6 +131,28 @@ impl From<koto_parser::Error> for CompilerError {
     }
 }
 
+impl From<Diagnostic> for CompilerError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Self {
+            error: diagnostic.kind.into(),
+            span: diagnostic.span,
+        }
+    }
+}
+
+impl From<CompilerError> for Diagnostic {
+    fn from(error: CompilerError) -> Self {
+        let kind = match error.error {
+            ErrorKind::Diagnostic(kind) => kind,
+            other => DiagnosticKind::CompileError(other.to_string()),
+        };
+        Self {
+            kind,
+            span: error.span,
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 struct CompileNodeContext<'a> {
     ast: &'a Ast,
@@ -262,6 +290,14 @@ pub struct CompilerSettings {
     ///
     /// Enabled by default.
     pub enable_type_checks: bool,
+
+    /// When enabled, a peephole optimization pass will be run over the compiled bytecode
+    ///
+    /// The pass removes redundant instructions like self-copies and jumps to the following
+    /// instruction, and shortens chains of jumps.
+    ///
+    /// Disabled by default.
+    pub optimize: bool,
 }
 
 impl Default for CompilerSettings {
@@ -269,7 +305,75 @@ impl Default for CompilerSettings {
         Self {
             export_top_level_ids: false,
             enable_type_checks: true,
+            optimize: false,
+        }
+    }
+}
+
+// Information gathered while checking a script, see `Compiler::check`
+#[derive(Default)]
+struct Analysis {
+    diagnostics: Vec<Diagnostic>,
+    // Non-local ids that have been accessed, along with the span of the access
+    non_local_accesses: Vec<(ConstantIndex, Span)>,
+    // Ids that have been exported anywhere in the script
+    exported_ids: HashSet<ConstantIndex>,
+    // Wildcard imports can bring any id into scope, so undefined ids can't be reported
+    contains_wildcard_import: bool,
+    // The bindings that have been made in each frame that's currently being compiled
+    frame_bindings: Vec<Vec<Binding>>,
+    // Bindings that were never read
+    unused_bindings: Vec<(ConstantIndex, Span)>,
+    // Bindings that shadow a binding from an enclosing frame
+    shadowed_bindings: Vec<(ConstantIndex, Span)>,
+}
+
+// A local value or argument in a frame, see `Compiler::add_binding`
+struct Binding {
+    id: ConstantIndex,
+    span: Span,
+    is_read: bool,
+}
+
+impl Analysis {
+    // Produces the list of diagnostics, ordered by their position in the script
+    //
+    // Non-local accesses are reported as undefined if they're neither exported by the script nor
+    // known to the caller, unless the script contains a wildcard import.
+    fn finish(mut self, ast: &Ast, is_defined: impl Fn(&str) -> bool) -> Vec<Diagnostic> {
+        let name = |id| ast.constants().get_str(id).to_string();
+
+        for (id, span) in self.unused_bindings {
+            self.diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::UnusedBinding(name(id)),
+                span,
+            });
+        }
+
+        for (id, span) in self.shadowed_bindings {
+            self.diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::ShadowedBinding(name(id)),
+                span,
+            });
+        }
+
+        for (id, span) in self.non_local_accesses {
+            if self.contains_wildcard_import || self.exported_ids.contains(&id) {
+                continue;
+            }
+
+            let name = ast.constants().get_str(id);
+            if !is_defined(name) {
+                self.diagnostics.push(Diagnostic {
+                    kind: DiagnosticKind::UndefinedId(name.into()),
+                    span,
+                });
+            }
         }
+
+        self.diagnostics
+            .sort_by_key(|diagnostic| diagnostic.span.start);
+        self.diagnostics
     }
 }
 
@@ -281,6 +385,8 @@ pub struct Compiler {
     frame_stack: Vec<Frame>,
     span_stack: Vec<Span>,
     settings: CompilerSettings,
+    // Set when checking a script for potential issues
+    analysis: Option<Analysis>,
 }
 
 impl Compiler {
@@ -307,25 +413,97 @@ impl Compiler {
             ..Default::default()
         };
 
+        compiler.compile_entry_point(&ast)?;
+        Ok(compiler.into_chunk(ast, script_path))
+    }
+
+    /// Compiles a script while checking it for potential issues
+    ///
+    /// The result of compilation is returned along with a list of [Diagnostic]s that describe
+    /// potential issues in the script. Diagnostics are returned even when compilation fails,
+    /// containing any issues that were found before the error was encountered.
+    ///
+    /// Accessed identifiers that aren't local or exported by the script are passed to `is_defined`,
+    /// which should return true if the identifier will be available when the script is run,
+    /// e.g. if it's in the runtime's prelude. Otherwise the identifier will be reported as
+    /// undefined.
+    pub fn compile_with_diagnostics(
+        script: &str,
+        script_path: Option<KString>,
+        settings: CompilerSettings,
+        is_defined: impl Fn(&str) -> bool,
+    ) -> (Result<Chunk>, Vec<Diagnostic>) {
+        let ast = match Parser::parse(script) {
+            Ok(ast) => ast,
+            Err(error) => return (Err(error.into()), Vec::new()),
+        };
+
+        let mut compiler = Compiler {
+            settings,
+            analysis: Some(Analysis::default()),
+            ..Default::default()
+        };
+
+        let compile_result = compiler.compile_entry_point(&ast);
+        let diagnostics = compiler
+            .analysis
+            .take()
+            .unwrap_or_default()
+            .finish(&ast, is_defined);
+
+        let result = compile_result.map(|_| {
+            let mut chunk = compiler.into_chunk(ast, script_path);
+            chunk.debug_info.source = script.to_string();
+            chunk
+        });
+
+        (result, diagnostics)
+    }
+
+    /// Checks a script for potential issues without producing a compiled [Chunk]
+    ///
+    /// The script is compiled as normal, with any compilation errors being returned as an error.
+    /// Along the way, potential issues in the script are gathered as a list of [Diagnostic]s.
+    ///
+    /// See [Compiler::compile_with_diagnostics] for a description of `is_defined`.
+    pub fn check(
+        script: &str,
+        settings: CompilerSettings,
+        is_defined: impl Fn(&str) -> bool,
+    ) -> Result<Vec<Diagnostic>> {
+        let (result, diagnostics) =
+            Self::compile_with_diagnostics(script, None, settings, is_defined);
+        result.map(|_| diagnostics)
+    }
+
+    fn compile_entry_point(&mut self, ast: &Ast) -> Result<()> {
         if let Some(entry_point) = ast.entry_point() {
-            compiler.compile_node(
+            self.compile_node(
                 entry_point,
-                CompileNodeContext::new(&ast, ResultRegister::None),
+                CompileNodeContext::new(ast, ResultRegister::None),
             )?;
         }
 
-        if compiler.bytes.len() > u32::MAX as usize {
-            return compiler.error(ErrorKind::ResultingBytecodeIsTooLarge(compiler.bytes.len()));
+        if self.bytes.len() > u32::MAX as usize {
+            return self.error(ErrorKind::ResultingBytecodeIsTooLarge(self.bytes.len()));
         }
 
-        let result = Chunk {
-            bytes: compiler.bytes,
+        Ok(())
+    }
+
+    fn into_chunk(self, ast: Ast, script_path: Option<KString>) -> Chunk {
+        let mut result = Chunk {
+            bytes: self.bytes,
             constants: ast.consume_constants(),
             path: script_path,
-            debug_info: compiler.debug_info,
+            debug_info: self.debug_info,
         };
 
-        Ok(result)
+        if self.settings.optimize {
+            peephole::optimize(&mut result);
+        }
+
+        result
     }
 
     fn compile_node(
@@ -352,6 +530,7 @@ impl Compiler {
                 result
             }
             Node::Nested(nested) => self.compile_node(*nested, ctx)?,
+            Node::Attributed { expression, .. } => self.compile_node(*expression, ctx)?,
             Node::Id(index, ..) => self.compile_load_id(*index, ctx)?,
             Node::Chain(chain) => self.compile_chain(chain, None, None, None, ctx)?,
             Node::BoolTrue => {
@@ -572,6 +751,7 @@ impl Compiler {
                         (None, None) => {}
                     }
 
+                    self.compile_loop_exit_from_try_expressions(ctx)?;
                     self.push_op(Jump, &[]);
                     self.push_loop_jump_placeholder()?;
 
@@ -587,6 +767,7 @@ impl Compiler {
                     if let Some(result_register) = loop_result_register {
                         self.push_op(SetNull, &[result_register]);
                     }
+                    self.compile_loop_exit_from_try_expressions(ctx)?;
                     self.push_jump_back_op(JumpBack, &[], loop_start_ip);
 
                     CompileNodeOutput::none()
@@ -600,13 +781,22 @@ impl Compiler {
                 // provided with a result register regardless.
                 let result = self.assign_result_register(ctx)?;
 
-                let expression_result = self.compile_node(*expression, ctx.with_any_register())?;
-                let expression_register = expression_result.unwrap(self)?;
+                if let Some(expression) = expression {
+                    let expression_result =
+                        self.compile_node(*expression, ctx.with_any_register())?;
+                    let expression_register = expression_result.unwrap(self)?;
 
-                self.push_op(Throw, &[expression_register]);
+                    self.push_op(Throw, &[expression_register]);
 
-                if expression_result.is_temporary {
-                    self.pop_register()?;
+                    if expression_result.is_temporary {
+                        self.pop_register()?;
+                    }
+                } else {
+                    // Rethrow the error that's being handled by the innermost catch block
+                    match self.frame().catch_registers.last() {
+                        Some(&catch_register) => self.push_op(Rethrow, &[catch_register]),
+                        None => return self.error(ErrorKind::RethrowOutsideOfCatchBlock),
+                    }
                 }
 
                 result
@@ -683,6 +873,23 @@ impl Compiler {
             is_generator,
         ));
 
+        if let Some(analysis) = self.analysis.as_mut() {
+            analysis.frame_bindings.push(Vec::new());
+
+            // Top-level args are added as bindings here,
+            // unpacked args are added when they're assigned in `compile_arg`.
+            for arg in args {
+                let target = match ctx.node(*arg) {
+                    Node::Assign { target, .. } => *target,
+                    _ => *arg,
+                };
+                if let Node::Id(id, ..) = ctx.node(target) {
+                    let span = *ctx.ast.span(ctx.node_with_span(target).span);
+                    self.add_binding(*id, span);
+                }
+            }
+        }
+
         // Check argument types and unpack nested args
         for (arg_index, arg) in args.iter().enumerate() {
             let arg_register = arg_index as u8 + 1; // self is in register 0, args start from 1
@@ -731,6 +938,28 @@ impl Compiler {
         let frame = self.frame_stack.pop().unwrap();
         self.bytes[register_count_byte_index] = frame.registers_used();
 
+        if let Some(analysis) = self.analysis.as_mut() {
+            let bindings = analysis.frame_bindings.pop().unwrap_or_default();
+            // Unused bindings are only reported in functions, top-level values are often
+            // assigned to be made available for inspection.
+            if !self.frame_stack.is_empty() {
+                analysis.unused_bindings.extend(
+                    bindings
+                        .into_iter()
+                        .filter(|binding| {
+                            !(binding.is_read || analysis.exported_ids.contains(&binding.id))
+                        })
+                        .map(|binding| (binding.id, binding.span)),
+                );
+            }
+        }
+
+        if self.frame_stack.is_empty() {
+            // The main block's frame has been compiled,
+            // so its locals can be made available for debugging.
+            self.debug_info.top_level_locals = frame.assigned_locals().collect();
+        }
+
         Ok(())
     }
 
@@ -753,19 +982,19 @@ impl Compiler {
 
             match ctx.result_register {
                 ResultRegister::Any => {
-                    self.push_op(Return, &[expression_register]);
+                    self.compile_return_op(expression_register, ctx)?;
                     expression_result
                 }
                 ResultRegister::Fixed(result) => {
                     self.push_op(Copy, &[result, expression_register]);
-                    self.push_op(Return, &[result]);
+                    self.compile_return_op(result, ctx)?;
                     if expression_result.is_temporary {
                         self.pop_register()?;
                     }
                     CompileNodeOutput::with_assigned(result)
                 }
                 ResultRegister::None => {
-                    self.push_op(Return, &[expression_register]);
+                    self.compile_return_op(expression_register, ctx)?;
                     if expression_result.is_temporary {
                         self.pop_register()?;
                     }
@@ -780,7 +1009,7 @@ impl Compiler {
                     if check_return_type {
                         self.compile_check_output_type(result_register, None, ctx)?;
                     }
-                    self.push_op(Return, &[result_register]);
+                    self.compile_return_op(result_register, ctx)?;
                 }
                 None => {
                     let register = self.push_register()?;
@@ -788,7 +1017,7 @@ impl Compiler {
                     if check_return_type {
                         self.compile_check_output_type(register, None, ctx)?;
                     }
-                    self.push_op(Return, &[register]);
+                    self.compile_return_op(register, ctx)?;
                     self.pop_register()?;
                 }
             }
@@ -798,6 +1027,57 @@ impl Compiler {
         Ok(result)
     }
 
+    // Emits a Return op, preceded by any finally blocks of enclosing try expressions
+    fn compile_return_op(&mut self, register: u8, ctx: CompileNodeContext) -> Result<()> {
+        if self.frame().try_stack.is_empty() {
+            self.push_op(Op::Return, &[register]);
+            return Ok(());
+        }
+
+        // The return value is copied to a temporary register in case it gets modified by a
+        // finally block.
+        let return_register = self.push_register()?;
+        self.push_op(Op::Copy, &[return_register, register]);
+        self.compile_try_exits(0, ctx)?;
+        self.push_op(Op::Return, &[return_register]);
+        self.pop_register()?;
+
+        Ok(())
+    }
+
+    // Emits the ops needed when a `break` or `continue` exits from enclosing try expressions
+    fn compile_loop_exit_from_try_expressions(&mut self, ctx: CompileNodeContext) -> Result<()> {
+        let loop_depth = self.frame().loop_depth();
+        let until = self
+            .frame()
+            .try_stack
+            .iter()
+            .position(|try_block| try_block.loop_depth >= loop_depth)
+            .unwrap_or(self.frame().try_stack.len());
+        self.compile_try_exits(until, ctx)
+    }
+
+    // Clears the catch points and runs the finally blocks of active try expressions
+    //
+    // Try expressions are exited from the innermost outwards, stopping at the `until` index in
+    // the frame's try stack.
+    fn compile_try_exits(&mut self, until: usize, ctx: CompileNodeContext) -> Result<()> {
+        let try_stack = self.frame().try_stack.clone();
+
+        for (i, try_block) in try_stack.iter().enumerate().skip(until).rev() {
+            // Finally blocks are compiled as if they were outside of the try expression
+            self.frame_mut().try_stack.truncate(i);
+
+            self.push_op_without_span(Op::TryEnd, &[0]);
+            if let Some(finally_block) = try_block.finally_block {
+                self.compile_node(finally_block, ctx.with_register(ResultRegister::None))?;
+            }
+        }
+
+        self.frame_mut().try_stack = try_stack;
+        Ok(())
+    }
+
     fn compile_yield(
         &mut self,
         expression: AstIndex,
@@ -993,13 +1273,11 @@ impl Compiler {
 
         let mut index_from_end = false;
 
-        for (arg_index, &arg) in args.iter().enumerate() {
-            let is_first_arg = arg_index == 0;
-            let is_last_arg = arg_index == args.len() - 1;
+        for (arg_position, &arg) in args.iter().enumerate() {
             let arg_index = if index_from_end {
-                -((args.len() - arg_index) as i8) as u8
+                -((args.len() - arg_position) as i8) as u8
             } else {
-                arg_index as u8
+                arg_position as u8
             };
 
             match ctx.node(arg) {
@@ -1016,28 +1294,22 @@ impl Compiler {
                     self.compile_assert_type(temp_register, *type_hint, Some(arg), ctx)?;
                     self.pop_register()?; // temp_register
                 }
-                Node::PackedId(maybe_id) if is_first_arg => {
+                Node::PackedId(maybe_id) if !index_from_end => {
                     if let Some(id) = maybe_id {
-                        // e.g. [first..., x, y]
-                        // We want to assign the slice containing all but the last two items to
-                        // the given id.
                         let id_register = self.assign_local_register(*id)?;
-                        let to_index = -(args.len() as i8 - 1) as u8;
-                        self.push_op(SliceTo, &[id_register, container_register, to_index]);
+                        self.compile_packed_slice(
+                            id_register,
+                            container_register,
+                            arg_position,
+                            args.len(),
+                        );
                     }
 
+                    // Any following args are indexed from the end of the container
                     index_from_end = true;
                 }
-                Node::PackedId(Some(id)) if is_last_arg => {
-                    // e.g. [x, y, z, rest...]
-                    // We want to assign the slice containing all but the first three items
-                    // to the given id.
-                    let id_register = self.assign_local_register(*id)?;
-                    self.push_op(SliceFrom, &[id_register, container_register, arg_index]);
-                }
-                Node::PackedId(None) if is_last_arg => {}
                 Node::PackedId(_) => {
-                    return self.error(ErrorKind::InvalidPositionForArgWithEllipses);
+                    return self.error(ErrorKind::MultipleArgsWithEllipses);
                 }
                 Node::Tuple { .. } | Node::MapPattern { .. } => {
                     let temp_register = self.push_register()?;
@@ -1052,6 +1324,46 @@ impl Compiler {
         Ok(())
     }
 
+    // Assigns the slice of a container that's captured by an ellipsis to the result register
+    //
+    // The container is expected to have already been checked to have at least
+    // `element_count - 1` elements.
+    fn compile_packed_slice(
+        &mut self,
+        result_register: u8,
+        container_register: u8,
+        position: usize,
+        element_count: usize,
+    ) {
+        use Op::*;
+
+        let is_first = position == 0;
+        let is_last = position == element_count - 1;
+        // The number of elements that follow the ellipsis, as a negative index
+        let to_index = -((element_count - position - 1) as i8) as u8;
+
+        if is_last {
+            // e.g. [x, y, z, rest...]
+            // Assign the slice containing all but the first three items.
+            self.push_op(
+                SliceFrom,
+                &[result_register, container_register, position as u8],
+            );
+        } else if is_first {
+            // e.g. [first..., x, y]
+            // Assign the slice containing all but the last two items.
+            self.push_op(SliceTo, &[result_register, container_register, to_index]);
+        } else {
+            // e.g. [x, middle..., y, z]
+            // Assign the slice containing all but the first item and the last two items.
+            self.push_op(
+                SliceFrom,
+                &[result_register, container_register, position as u8],
+            );
+            self.push_op(SliceTo, &[result_register, result_register, to_index]);
+        }
+    }
+
     fn compile_unpack_nested_arg_of_map(
         &mut self,
         container_register: u8,
@@ -1137,6 +1449,10 @@ impl Compiler {
             }
             [expression] => self.compile_node(*expression, ctx)?,
             [expressions @ .., last_expression] => {
+                if self.analysis.is_some() {
+                    self.check_for_unreachable_code(expressions, *last_expression, ctx);
+                }
+
                 for expression in expressions.iter() {
                     self.compile_node(*expression, ctx.compile_for_side_effects())?;
                 }
@@ -1148,6 +1464,30 @@ impl Compiler {
         Ok(result)
     }
 
+    // Reports the first expression in a block that follows an expression that exits the block
+    fn check_for_unreachable_code(
+        &mut self,
+        expressions: &[AstIndex],
+        last_expression: AstIndex,
+        ctx: CompileNodeContext,
+    ) {
+        let exit_position = expressions.iter().position(|expression| {
+            matches!(
+                ctx.node(*expression),
+                Node::Return(_) | Node::Throw(_) | Node::Break(_) | Node::Continue
+            )
+        });
+
+        if let Some(exit_position) = exit_position {
+            let unreachable = expressions
+                .get(exit_position + 1)
+                .copied()
+                .unwrap_or(last_expression);
+            let span = *ctx.ast.span(ctx.node_with_span(unreachable).span);
+            self.add_diagnostic(DiagnosticKind::UnreachableCode, span);
+        }
+    }
+
     fn force_export_assignment(&self) -> bool {
         self.settings.export_top_level_ids && self.frame_stack.len() == 1
     }
@@ -1603,6 +1943,7 @@ impl Compiler {
         ctx: CompileNodeContext,
     ) -> Result<CompileNodeOutput> {
         let result = if let Some(local_register) = self.frame().get_local_assigned_register(id) {
+            self.mark_binding_as_read(id);
             match ctx.result_register {
                 ResultRegister::None => CompileNodeOutput::none(),
                 ResultRegister::Any => CompileNodeOutput::with_assigned(local_register),
@@ -1721,6 +2062,9 @@ impl Compiler {
         self.push_op(Op::ExportValue, &[id_register, value_register]);
         self.pop_register()?;
         self.frame_mut().add_to_exported_ids(id);
+        if let Some(analysis) = self.analysis.as_mut() {
+            analysis.exported_ids.insert(id);
+        }
         Ok(())
     }
 
@@ -1749,6 +2093,11 @@ impl Compiler {
     }
 
     fn compile_load_non_local(&mut self, result_register: u8, id: ConstantIndex) {
+        if let Some(analysis) = self.analysis.as_mut() {
+            let span = *self.span_stack.last().expect("Empty span stack");
+            analysis.non_local_accesses.push((id, span));
+        }
+
         self.compile_constant_op(result_register, id, Op::LoadNonLocal);
     }
 
@@ -1788,6 +2137,10 @@ impl Compiler {
 
         let wildcard_import = items.is_empty();
 
+        if wildcard_import && let Some(analysis) = self.analysis.as_mut() {
+            analysis.contains_wildcard_import = true;
+        }
+
         let mut imported = vec![];
 
         if from.is_empty() {
@@ -2068,6 +2421,7 @@ impl Compiler {
         match ctx.node(item) {
             Node::Id(id, ..) => {
                 if let Some(local_register) = self.frame().get_local_assigned_register(*id) {
+                    self.mark_binding_as_read(*id);
                     // The item to be imported is already locally assigned.
                     if local_register != result_register {
                         if wildcard_import {
@@ -2124,7 +2478,14 @@ impl Compiler {
             _ => ResultRegister::None,
         };
 
+        let try_block_info = TryBlock {
+            finally_block: *finally_block,
+            loop_depth: self.frame().loop_depth(),
+        };
+
+        self.frame_mut().try_stack.push(try_block_info);
         self.compile_node(*try_block, ctx.with_register(try_result_register))?;
+        self.frame_mut().try_stack.pop();
 
         // Clear the catch point at the end of the try block
         // - if the end of the try block has been reached then the catch block is no longer needed.
@@ -2145,6 +2506,20 @@ impl Compiler {
         //   are errors thrown in the catch block.
         self.push_op(TryEnd, &[dummy_byte]);
 
+        // If there's a finally block, then errors thrown in the catch blocks need to be caught so
+        // that the finally block can be run before the error is rethrown.
+        let rethrow = if finally_block.is_some() {
+            let rethrow_register = self.push_register()?;
+            self.push_op_without_span(TryStart, &[rethrow_register]);
+            let rethrow_offset = self.push_offset_placeholder();
+            self.frame_mut().try_stack.push(try_block_info);
+            Some((rethrow_register, rethrow_offset))
+        } else {
+            None
+        };
+
+        let mut catches_end_jump_placeholders = SmallVec::<[usize; 4]>::new();
+
         for (i, catch_block) in catch_blocks.iter().enumerate() {
             let is_last_catch = i == catch_blocks.len() - 1;
 
@@ -2205,12 +2580,14 @@ impl Compiler {
             self.pop_span(); // catch arg
             self.push_span(ctx.node_with_span(catch_block.block), ctx.ast);
 
+            self.frame_mut().catch_registers.push(catch_register);
             self.compile_node(catch_block.block, ctx.with_register(try_result_register))?;
+            self.frame_mut().catch_registers.pop();
 
             if !is_last_catch {
-                // Jump to the finally block at the end of the catch block
+                // Jump to the end of the catch blocks
                 self.push_op_without_span(Jump, &[]);
-                finally_jump_placeholders.push(self.push_offset_placeholder());
+                catches_end_jump_placeholders.push(self.push_offset_placeholder());
             }
 
             for placeholder in type_check_jump_placeholders {
@@ -2220,6 +2597,30 @@ impl Compiler {
             self.pop_span(); // catch block
         }
 
+        for placeholder in catches_end_jump_placeholders {
+            self.update_offset_placeholder(placeholder)?;
+        }
+
+        if let Some((rethrow_register, rethrow_offset)) = rethrow {
+            self.frame_mut().try_stack.pop();
+
+            // The catch blocks haven't thrown, so clear the rethrow catch point and jump to the
+            // finally block.
+            self.push_op_without_span(TryEnd, &[dummy_byte]);
+            self.push_op_without_span(Jump, &[]);
+            finally_jump_placeholders.push(self.push_offset_placeholder());
+
+            // An error was thrown in a catch block, run the finally block then rethrow the error
+            self.update_offset_placeholder(rethrow_offset)?;
+            self.push_op_without_span(TryEnd, &[dummy_byte]);
+            if let Some(finally_block) = finally_block {
+                self.compile_node(*finally_block, ctx.with_register(ResultRegister::None))?;
+            }
+            self.push_op_without_span(Rethrow, &[rethrow_register]);
+
+            self.pop_register()?; // rethrow_register
+        }
+
         self.pop_register()?; // catch_register
 
         // Compile the finally block
@@ -2286,6 +2687,9 @@ impl Compiler {
             }
             And | Or => self.compile_logic_op(op, lhs, rhs, ctx),
             Pipe => self.compile_piped_call(lhs, rhs, ctx),
+            BitwiseAnd | BitwiseOr | BitwiseXor | ShiftLeft | ShiftRight => {
+                self.error(ErrorKind::UnsupportedBinaryOp(op))
+            }
         }
     }
 
@@ -2633,7 +3037,11 @@ impl Compiler {
                                             self.push_var_u32(fill_constant.into());
                                         }
                                         if let Some(style) = format.representation {
-                                            self.bytes.push(style as u8);
+                                            let mut style = style as u8;
+                                            if format.alternate_form {
+                                                style |= StringFormatFlags::ALTERNATE_FORM;
+                                            }
+                                            self.bytes.push(style);
                                         }
 
                                         if expression_result.is_temporary {
@@ -2766,6 +3174,10 @@ impl Compiler {
     ) -> Result<CompileNodeOutput> {
         let result = self.assign_result_register(ctx)?;
 
+        if self.analysis.is_some() {
+            self.check_for_duplicate_map_keys(entries, ctx);
+        }
+
         // Create the map with an appropriate size hint
         if let Some(result_register) = result.register {
             let Ok(size_hint) = u32::try_from(entries.len()) else {
@@ -2804,7 +3216,10 @@ impl Compiler {
                     Node::Id(key, _) => {
                         // An ID key without a value, a value with matching ID should be available
                         let value = match self.frame().get_local_assigned_register(*key) {
-                            Some(register) => CompileNodeOutput::with_assigned(register),
+                            Some(register) => {
+                                self.mark_binding_as_read(*key);
+                                CompileNodeOutput::with_assigned(register)
+                            }
                             None => {
                                 let register = self.push_register()?;
                                 self.compile_load_non_local(register, *key);
@@ -2843,6 +3258,37 @@ impl Compiler {
         Ok(result)
     }
 
+    // Reports map entries that use the same key as a previous entry
+    //
+    // Only keys that are known at compile time (ids and string literals) are checked.
+    fn check_for_duplicate_map_keys(&mut self, entries: &[AstIndex], ctx: CompileNodeContext) {
+        let mut keys = HashSet::with_capacity(entries.len());
+
+        for entry in entries {
+            let key = match ctx.node(*entry) {
+                Node::MapEntry(key, _) => *key,
+                Node::Id(..) => *entry,
+                _ => continue,
+            };
+
+            let key_constant = match ctx.node(key) {
+                Node::Id(id, ..) => *id,
+                Node::Str(AstString {
+                    contents:
+                        StringContents::Literal(constant) | StringContents::Raw { constant, .. },
+                    ..
+                }) => *constant,
+                _ => continue,
+            };
+
+            let key_string = ctx.ast.constants().get_str(key_constant);
+            if !keys.insert(key_string) {
+                let span = *ctx.ast.span(ctx.node_with_span(*entry).span);
+                self.add_diagnostic(DiagnosticKind::DuplicateMapKey(key_string.into()), span);
+            }
+        }
+    }
+
     fn compile_function(
         &mut self,
         function: &Function,
@@ -2892,6 +3338,10 @@ impl Compiler {
         let captures = self
             .frame()
             .captures_for_nested_frame(&function.accessed_non_locals);
+
+        for capture in captures.iter() {
+            self.mark_binding_as_read(*capture);
+        }
         if optional_args.len() + captures.len() > u8::MAX as usize {
             return self.error(ErrorKind::FunctionPropertyLimit {
                 property: "captures".into(),
@@ -3413,6 +3863,9 @@ impl Compiler {
 
                 if export_entry {
                     self.push_op_without_span(ExportValue, &[key_register, value_register]);
+                    if let Some(analysis) = self.analysis.as_mut() {
+                        analysis.exported_ids.insert(*id);
+                    }
                 }
 
                 self.pop_register()?;
@@ -3557,6 +4010,7 @@ impl Compiler {
             Node::Id(id, ..) => {
                 // Compile a call with the piped arg, using the id to access the function
                 if let Some(function_register) = self.frame().get_local_assigned_register(*id) {
+                    self.mark_binding_as_read(*id);
                     self.compile_call(function_register, &[], pipe_register, None, ctx)
                 } else {
                     let call_result_register = if let Some(result_register) = result.register {
@@ -4063,13 +4517,12 @@ impl Compiler {
 
         let mut index_from_end = false;
 
-        for (pattern_index, pattern) in arm_patterns.iter().enumerate() {
-            let is_first_pattern = pattern_index == 0;
-            let is_last_pattern = pattern_index == arm_patterns.len() - 1;
+        for (pattern_position, pattern) in arm_patterns.iter().enumerate() {
+            let is_last_pattern = pattern_position == arm_patterns.len() - 1;
             let pattern_index = if index_from_end {
-                -((arm_patterns.len() - pattern_index) as i8)
+                -((arm_patterns.len() - pattern_position) as i8)
             } else {
-                pattern_index as i8
+                pattern_position as i8
             };
             let pattern_node = ctx.node_with_span(*pattern);
 
@@ -4203,41 +4656,31 @@ impl Compiler {
                         ctx,
                     )?;
                 }
-                Node::PackedId(maybe_id) => {
-                    if is_last_pattern {
-                        if let Some(id) = maybe_id {
-                            // e.g. [x, y, z, rest...]
-                            // We want to assign the slice containing all but the first three items
-                            // to the given id.
-                            let id_register = self.assign_local_register(*id)?;
-                            self.push_op(
-                                SliceFrom,
-                                &[id_register, params.match_register, pattern_index as u8],
-                            );
-                        }
-
-                        if !params.is_last_alternative {
-                            // Ellipses match unconditionally in last position,
-                            // multi-expression pattern, skip over the remaining alternatives
-                            // e.g. (x, 0, rest...) or (x, 1, y) if rest.size() > 0 then
-                            //             ^~~~~~~ We're here, jump to the if condition
-                            self.push_op(Jump, &[]);
-                            params.jumps.match_end.push(self.push_offset_placeholder());
-                        }
-                    } else if is_first_pattern {
-                        if let Some(id) = maybe_id {
-                            // e.g. [first..., x, y]
-                            // We want to assign the slice containing all but the last two items to
-                            // the given id.
-                            let id_register = self.assign_local_register(*id)?;
-                            let to_index = -(arm_patterns.len() as i8 - 1) as u8;
-                            self.push_op(SliceTo, &[id_register, params.match_register, to_index]);
-                        }
+                Node::PackedId(maybe_id) if !index_from_end => {
+                    if let Some(id) = maybe_id {
+                        let id_register = self.assign_local_register(*id)?;
+                        self.compile_packed_slice(
+                            id_register,
+                            params.match_register,
+                            pattern_position,
+                            arm_patterns.len(),
+                        );
+                    }
 
-                        index_from_end = true;
-                    } else {
-                        return self.error(ErrorKind::OutOfPositionMatchEllipsis);
+                    if is_last_pattern && !params.is_last_alternative {
+                        // Ellipses match unconditionally in last position,
+                        // multi-expression pattern, skip over the remaining alternatives
+                        // e.g. (x, 0, rest...) or (x, 1, y) if rest.size() > 0 then
+                        //             ^~~~~~~ We're here, jump to the if condition
+                        self.push_op(Jump, &[]);
+                        params.jumps.match_end.push(self.push_offset_placeholder());
                     }
+
+                    // Any following patterns are indexed from the end of the container
+                    index_from_end = true;
+                }
+                Node::PackedId(_) => {
+                    return self.error(ErrorKind::MultipleMatchEllipses);
                 }
                 Node::MapPattern { entries, type_hint } => {
                     let map_register = if match_is_container {
@@ -4381,18 +4824,13 @@ impl Compiler {
 
         let temp_register = self.push_register()?;
 
-        let first_or_last_pattern_is_ellipsis = {
-            let first_is_ellipsis = nested_patterns
-                .first()
-                .is_some_and(|first| matches!(ctx.node(*first), Node::PackedId(_)));
-            let last_is_ellipsis = nested_patterns
-                .last()
-                .is_some_and(|last| matches!(ctx.node(*last), Node::PackedId(_)));
-            if nested_patterns.len() > 1 && first_is_ellipsis && last_is_ellipsis {
-                return self.error(ErrorKind::MultipleMatchEllipses);
-            }
-            first_is_ellipsis || last_is_ellipsis
-        };
+        let ellipsis_count = nested_patterns
+            .iter()
+            .filter(|pattern| matches!(ctx.node(**pattern), Node::PackedId(_)))
+            .count();
+        if ellipsis_count > 1 {
+            return self.error(ErrorKind::MultipleMatchEllipses);
+        }
 
         // Check that the container has sufficient elements for the match patterns
         if !nested_patterns.is_empty() {
@@ -4401,7 +4839,7 @@ impl Compiler {
 
             let patterns_len = nested_patterns.len() as u8;
 
-            let comparison_op = if first_or_last_pattern_is_ellipsis {
+            let comparison_op = if ellipsis_count == 1 {
                 self.push_op(SetNumberU8, &[expected_register, patterns_len - 1]);
                 GreaterOrEqual
             } else {
@@ -4830,6 +5268,9 @@ impl Compiler {
 
     // Used for values that can be assigned directly to a register
     fn assign_local_register(&mut self, local: ConstantIndex) -> Result<u8> {
+        if self.analysis.is_some() {
+            self.add_binding(local, self.span());
+        }
         self.frame_mut()
             .assign_local_register(local)
             .map_err(|e| self.make_error(e))
@@ -4843,6 +5284,9 @@ impl Compiler {
     // Reserving is necessary to avoid bringing the local's name into scope during the RHS's
     // evaluation before it's been assigned.
     fn reserve_local_register(&mut self, local: ConstantIndex) -> Result<u8> {
+        if self.analysis.is_some() {
+            self.add_binding(local, self.span());
+        }
         self.frame_mut()
             .reserve_local_register(local)
             .map_err(|e| self.make_error(e))
@@ -4872,6 +5316,54 @@ impl Compiler {
         }
     }
 
+    // Adds a binding to the current frame when analyzing the script
+    //
+    // Reassignments of existing bindings in the frame are ignored, and new bindings are checked
+    // for shadowing of bindings in enclosing frames.
+    fn add_binding(&mut self, id: ConstantIndex, span: Span) {
+        let Some(analysis) = self.analysis.as_mut() else {
+            return;
+        };
+        let Some((current, enclosing)) = analysis.frame_bindings.split_last_mut() else {
+            return;
+        };
+
+        if current.iter().any(|binding| binding.id == id) {
+            return;
+        }
+
+        if enclosing
+            .iter()
+            .any(|bindings| bindings.iter().any(|binding| binding.id == id))
+        {
+            analysis.shadowed_bindings.push((id, span));
+        }
+
+        current.push(Binding {
+            id,
+            span,
+            is_read: false,
+        });
+    }
+
+    fn mark_binding_as_read(&mut self, id: ConstantIndex) {
+        if let Some(bindings) = self
+            .analysis
+            .as_mut()
+            .and_then(|analysis| analysis.frame_bindings.last_mut())
+        {
+            for binding in bindings.iter_mut().filter(|binding| binding.id == id) {
+                binding.is_read = true;
+            }
+        }
+    }
+
+    fn add_diagnostic(&mut self, kind: DiagnosticKind, span: Span) {
+        if let Some(analysis) = self.analysis.as_mut() {
+            analysis.diagnostics.push(Diagnostic { kind, span });
+        }
+    }
+
     fn push_span(&mut self, node: &AstNode, ast: &Ast) {
         self.span_stack.push(*ast.span(node.span));
     }
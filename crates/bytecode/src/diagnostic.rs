@@ -0,0 +1,52 @@
+use koto_parser::Span;
+use thiserror::Error;
+
+/// A potential issue in a script that was found while checking or compiling it
+///
+/// See [Compiler::check] and [Compiler::compile_with_diagnostics].
+///
+/// [Compiler::check]: crate::Compiler::check
+/// [Compiler::compile_with_diagnostics]: crate::Compiler::compile_with_diagnostics
+#[derive(Error, Clone, Debug, PartialEq)]
+#[error("{kind}")]
+pub struct Diagnostic {
+    /// The kind of issue that was found
+    pub kind: DiagnosticKind,
+    /// The span in the source where the issue was found
+    pub span: Span,
+}
+
+/// The different kinds of [Diagnostic] that can be reported when checking a script
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum DiagnosticKind {
+    /// An identifier was accessed that isn't defined
+    #[error("'{0}' is not defined")]
+    UndefinedId(String),
+    /// An expression follows a `return`, `throw`, `break`, or `continue` in the same block
+    #[error("unreachable code")]
+    UnreachableCode,
+    /// A key appears more than once in a map
+    #[error("the key '{0}' appears more than once in this map")]
+    DuplicateMapKey(String),
+    /// A function's local value or argument is assigned but never used
+    #[error("'{0}' is assigned but never used")]
+    UnusedBinding(String),
+    /// A function's local value or argument has the same name as a value in an enclosing scope
+    #[error("'{0}' shadows a value with the same name in an enclosing scope")]
+    ShadowedBinding(String),
+    /// The script failed to compile
+    ///
+    /// Only reported by [ModuleLoader::compile_script_with_diagnostics], which returns compilation
+    /// errors alongside other diagnostics.
+    ///
+    /// [ModuleLoader::compile_script_with_diagnostics]: crate::ModuleLoader::compile_script_with_diagnostics
+    #[error("{0}")]
+    CompileError(String),
+}
+
+impl Diagnostic {
+    /// Returns true if the diagnostic describes an error that prevented compilation
+    pub fn is_error(&self) -> bool {
+        matches!(self.kind, DiagnosticKind::CompileError(_))
+    }
+}
@@ -47,6 +47,15 @@ pub(crate) struct Loop {
     pub jump_placeholders: Vec<usize>,
 }
 
+// An active try expression, used when compiling early exits like `return` or `break`
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TryBlock {
+    // The try expression's optional finally block, which needs to be run on early exits
+    pub finally_block: Option<AstIndex>,
+    // The depth of the loop stack when the try expression was entered
+    pub loop_depth: usize,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum LocalRegister {
     // The register is assigned to a specific id.
@@ -69,6 +78,10 @@ pub(crate) enum Arg {
 #[derive(Clone, Debug, Default)]
 pub(crate) struct Frame {
     loop_stack: Vec<Loop>,
+    // The try expressions that are active at the current point of compilation
+    pub try_stack: Vec<TryBlock>,
+    // The registers containing the errors caught by the catch blocks that are being compiled
+    pub catch_registers: Vec<u8>,
     register_stack: Vec<u8>,
     local_registers: Vec<LocalRegister>,
     exported_ids: HashSet<ConstantIndex>,
@@ -143,6 +156,17 @@ impl Frame {
         }
     }
 
+    // Returns the frame's assigned local ids, paired with their registers
+    pub fn assigned_locals(&self) -> impl Iterator<Item = (ConstantIndex, u8)> + '_ {
+        self.local_registers
+            .iter()
+            .enumerate()
+            .filter_map(|(register, local_register)| match local_register {
+                LocalRegister::Assigned(id) => Some((*id, register as u8)),
+                _ => None,
+            })
+    }
+
     pub fn get_local_assigned_register(&self, local_name: ConstantIndex) -> Option<u8> {
         self.local_registers
             .iter()
@@ -350,6 +374,10 @@ impl Frame {
         }
     }
 
+    pub fn loop_depth(&self) -> usize {
+        self.loop_stack.len()
+    }
+
     pub fn current_loop(&self) -> Option<&Loop> {
         self.loop_stack.last()
     }
@@ -263,6 +263,9 @@ pub enum Instruction {
     Throw {
         register: u8,
     },
+    Rethrow {
+        register: u8,
+    },
     Size {
         register: u8,
         value: u8,
@@ -488,6 +491,15 @@ impl StringFormatFlags {
     pub const FILL_CHARACTER: u8 = 1 << 4;
     /// Set to true when a format style is defined
     pub const REPRESENTATION: u8 = 1 << 5;
+    /// Set to true when a sign should be shown for positive numbers
+    pub const SHOW_SIGN: u8 = 1 << 6;
+    /// Set to true when the digits of numbers should be grouped in thousands
+    pub const GROUP_THOUSANDS: u8 = 1 << 7;
+    /// Set in the representation byte when the representation's alternate form should be used
+    ///
+    /// The flags byte has no remaining space, so the alternate form flag is stored alongside the
+    /// representation, which is the only option that it applies to.
+    pub const ALTERNATE_FORM: u8 = 1 << 7;
 
     /// Returns the flag's string alignment
     pub fn alignment(&self) -> StringAlignment {
@@ -523,6 +535,16 @@ impl StringFormatFlags {
     pub fn has_representation(&self) -> bool {
         self.0 & Self::REPRESENTATION != 0
     }
+
+    /// True if a sign should be shown for positive numbers
+    pub fn show_sign(&self) -> bool {
+        self.0 & Self::SHOW_SIGN != 0
+    }
+
+    /// True if the digits of numbers should be grouped in thousands
+    pub fn group_thousands(&self) -> bool {
+        self.0 & Self::GROUP_THOUSANDS != 0
+    }
 }
 
 impl From<StringFormatOptions> for StringFormatFlags {
@@ -541,20 +563,21 @@ impl From<StringFormatOptions> for StringFormatFlags {
         if value.representation.is_some() {
             flags |= Self::REPRESENTATION;
         }
+        if value.show_sign {
+            flags |= Self::SHOW_SIGN;
+        }
+        if value.group_thousands {
+            flags |= Self::GROUP_THOUSANDS;
+        }
 
         Self(flags)
     }
 }
 
-impl TryFrom<u8> for StringFormatFlags {
-    type Error = String;
-
-    fn try_from(byte: u8) -> Result<Self, Self::Error> {
-        if byte <= 0b1111111 {
-            Ok(Self(byte))
-        } else {
-            Err(format!("Invalid string format flags: {byte:#010b}"))
-        }
+impl From<u8> for StringFormatFlags {
+    fn from(byte: u8) -> Self {
+        // All bits of the flags byte are in use, so any byte is valid
+        Self(byte)
     }
 }
 
@@ -834,6 +857,7 @@ impl fmt::Debug for Instruction {
             Return { register } => write!(f, "Return          register: {register}"),
             Yield { register } => write!(f, "Yield           register: {register}"),
             Throw { register } => write!(f, "Throw           register: {register}"),
+            Rethrow { register } => write!(f, "Rethrow         register: {register}"),
             Size { register, value } => {
                 write!(f, "Size            result: {register:<7} value: {value}")
             }
@@ -459,6 +459,7 @@ impl Iterator for InstructionReader {
             Op::Return => Return { register: byte_a },
             Op::Yield => Yield { register: byte_a },
             Op::Throw => Throw { register: byte_a },
+            Op::Rethrow => Rethrow { register: byte_a },
             Op::Size => Size {
                 register: byte_a,
                 value: get_u8!(),
@@ -685,35 +686,38 @@ impl Iterator for InstructionReader {
                 let flags = get_u8!();
 
                 if flags != 0 {
-                    match StringFormatFlags::try_from(flags) {
-                        Ok(flags) => {
-                            let mut options = StringFormatOptions {
-                                alignment: flags.alignment(),
-                                ..Default::default()
-                            };
-                            if flags.has_min_width() {
-                                options.min_width = Some(get_var_u32!());
-                            }
-                            if flags.has_precision() {
-                                options.precision = Some(get_var_u32!());
-                            }
-                            if flags.has_fill_character() {
-                                options.fill_character = Some(get_var_u32!().into());
-                            }
-                            if flags.has_representation() {
-                                match StringFormatRepresentation::try_from(get_u8!()) {
-                                    Ok(representation) => {
-                                        options.representation = Some(representation);
-                                    }
-                                    Err(e) => return Some(Error { message: e }),
-                                }
-                            }
-                            StringPush {
-                                value,
-                                format_options: Some(options),
+                    let flags = StringFormatFlags::from(flags);
+                    let mut options = StringFormatOptions {
+                        alignment: flags.alignment(),
+                        show_sign: flags.show_sign(),
+                        group_thousands: flags.group_thousands(),
+                        ..Default::default()
+                    };
+                    if flags.has_min_width() {
+                        options.min_width = Some(get_var_u32!());
+                    }
+                    if flags.has_precision() {
+                        options.precision = Some(get_var_u32!());
+                    }
+                    if flags.has_fill_character() {
+                        options.fill_character = Some(get_var_u32!().into());
+                    }
+                    if flags.has_representation() {
+                        let representation = get_u8!();
+                        options.alternate_form =
+                            representation & StringFormatFlags::ALTERNATE_FORM != 0;
+                        match StringFormatRepresentation::try_from(
+                            representation & !StringFormatFlags::ALTERNATE_FORM,
+                        ) {
+                            Ok(representation) => {
+                                options.representation = Some(representation);
                             }
+                            Err(e) => return Some(Error { message: e }),
                         }
-                        Err(e) => Error { message: e },
+                    }
+                    StringPush {
+                        value,
+                        format_options: Some(options),
                     }
                 } else {
                     StringPush {
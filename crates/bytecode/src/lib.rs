@@ -4,15 +4,18 @@
 
 mod chunk;
 mod compiler;
+mod diagnostic;
 mod frame;
 mod instruction;
 mod instruction_reader;
 mod module_loader;
 mod op;
+mod peephole;
 
 pub use crate::{
     chunk::{Chunk, DebugInfo},
     compiler::{Compiler, CompilerError, CompilerSettings},
+    diagnostic::{Diagnostic, DiagnosticKind},
     instruction::{FunctionFlags, Instruction, StringFormatFlags},
     instruction_reader::InstructionReader,
     module_loader::{ModuleLoader, ModuleLoaderError, find_module},
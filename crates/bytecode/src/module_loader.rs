@@ -1,4 +1,4 @@
-use crate::{Chunk, Compiler, CompilerError, CompilerSettings};
+use crate::{Chunk, Compiler, CompilerError, CompilerSettings, Diagnostic, DiagnosticKind};
 use dunce::canonicalize;
 use koto_memory::Ptr;
 use koto_parser::{KString, Span, format_source_excerpt};
@@ -121,6 +121,69 @@ impl ModuleLoader {
             .map_err(|e| ModuleLoaderError::from_compiler_error(e, script, script_path))
     }
 
+    /// Compiles a script, returning an error if the script accesses undefined identifiers
+    ///
+    /// See [Compiler::compile_with_diagnostics] for a description of `is_defined`.
+    pub fn compile_script_strict(
+        &mut self,
+        script: &str,
+        script_path: Option<KString>,
+        settings: CompilerSettings,
+        is_defined: impl Fn(&str) -> bool,
+    ) -> Result<Ptr<Chunk>, ModuleLoaderError> {
+        let (result, diagnostics) =
+            Compiler::compile_with_diagnostics(script, script_path.clone(), settings, is_defined);
+
+        let result = match diagnostics
+            .into_iter()
+            .find(|diagnostic| matches!(diagnostic.kind, DiagnosticKind::UndefinedId(_)))
+        {
+            Some(undefined) => Err(undefined.into()),
+            None => result,
+        };
+
+        result
+            .map(Ptr::from)
+            .map_err(|e| ModuleLoaderError::from_compiler_error(e, script, script_path))
+    }
+
+    /// Compiles a script, returning the compiled chunk along with any diagnostics
+    ///
+    /// If compilation fails then no chunk is returned, and the compilation error is included in
+    /// the diagnostics as [DiagnosticKind::CompileError].
+    ///
+    /// See [Compiler::compile_with_diagnostics] for a description of `is_defined`.
+    pub fn compile_script_with_diagnostics(
+        &self,
+        script: &str,
+        script_path: Option<KString>,
+        settings: CompilerSettings,
+        is_defined: impl Fn(&str) -> bool,
+    ) -> (Option<Ptr<Chunk>>, Vec<Diagnostic>) {
+        let (result, mut diagnostics) =
+            Compiler::compile_with_diagnostics(script, script_path, settings, is_defined);
+
+        match result {
+            Ok(chunk) => (Some(chunk.into()), diagnostics),
+            Err(error) => {
+                diagnostics.push(error.into());
+                (None, diagnostics)
+            }
+        }
+    }
+
+    /// Checks a script for potential issues, deferring to [Compiler::check]
+    pub fn check_script(
+        &self,
+        script: &str,
+        script_path: Option<KString>,
+        settings: CompilerSettings,
+        is_defined: impl Fn(&str) -> bool,
+    ) -> Result<Vec<Diagnostic>, ModuleLoaderError> {
+        Compiler::check(script, settings, is_defined)
+            .map_err(|e| ModuleLoaderError::from_compiler_error(e, script, script_path))
+    }
+
     /// Finds a module from its name, and then compiles it
     pub fn compile_module(
         &mut self,
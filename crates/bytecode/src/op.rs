@@ -165,6 +165,8 @@ pub enum Op {
     ///
     /// See [`StringFormatFlags`](crate::StringFormatFlags) for a description of the format flags.
     ///
+    /// The style byte contains the representation, along with the alternate form flag.
+    ///
     /// `[*value, format_flags, ?@min_width, ?@precision, ?@fill_character, ?style]`
     StringPush,
 
@@ -391,6 +393,13 @@ pub enum Op {
     /// `[*error]`
     Throw,
 
+    /// Rethrows the error that was caught by the catch point that uses the given register
+    ///
+    /// If no error is available for the register then its value is thrown as a new error.
+    ///
+    /// `[*error]`
+    Rethrow,
+
     /// Gets the next value from an Iterator
     ///
     /// The output from the iterator is placed in the output register.
@@ -613,7 +622,6 @@ pub enum Op {
     TryAccessString,
 
     // Unused opcodes, allowing for a direct transmutation from a byte to an Op.
-    Unused95,
     Unused96,
     Unused97,
     Unused98,
@@ -0,0 +1,330 @@
+use crate::{Chunk, Instruction, InstructionReader, Op};
+use koto_memory::Ptr;
+use rustc_hash::FxHashSet;
+
+// A decoded instruction's location in the bytecode
+#[derive(Clone, Copy, Debug)]
+struct InstructionInfo {
+    op: Op,
+    // The ip of the instruction's op byte
+    start: usize,
+    // The ip following the instruction's last byte
+    end: usize,
+}
+
+impl InstructionInfo {
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    // Returns the ip that's referred to by the instruction's jump offset, if it has one
+    //
+    // Jump offsets are always placed in the last two bytes of an instruction,
+    // and are relative to the end of the instruction.
+    fn jump_target(&self, bytes: &[u8]) -> Option<usize> {
+        let offset = u16::from_le_bytes([bytes[self.end - 2], bytes[self.end - 1]]) as usize;
+        match jump_direction(self.op)? {
+            JumpDirection::Forward => Some(self.end + offset),
+            JumpDirection::Back => Some(self.end - offset),
+        }
+    }
+}
+
+enum JumpDirection {
+    Forward,
+    Back,
+}
+
+// Returns the direction of the instruction's jump offset, for ops that have one
+fn jump_direction(op: Op) -> Option<JumpDirection> {
+    use Op::*;
+
+    match op {
+        // A function's size is equivalent to a jump over the function's body
+        Function | Jump | JumpIfTrue | JumpIfFalse | JumpIfNull | IterNext | IterNextTemp
+        | IterNextQuiet | TryStart | CheckType | CheckOptionalType | TryAccess
+        | TryAccessString => Some(JumpDirection::Forward),
+        JumpBack => Some(JumpDirection::Back),
+        _ => None,
+    }
+}
+
+/// Runs a peephole optimization pass over a compiled chunk's bytecode
+///
+/// The following optimizations are performed:
+///   - Copies from a register to itself are removed.
+///   - A copy that reverses the immediately preceding copy is removed,
+///     unless it's the target of a jump.
+///   - Jumps to unconditional forward jumps are redirected to the final jump target.
+///   - Jumps to the immediately following instruction are removed.
+///
+/// Jump offsets and the chunk's debug info are updated to account for the removed instructions.
+///
+/// If the bytecode can't be decoded then it will be left unchanged.
+pub(crate) fn optimize(chunk: &mut Chunk) {
+    let Some(instructions) = decode_instructions(&chunk.bytes) else {
+        return;
+    };
+
+    let jump_targets = instructions
+        .iter()
+        .filter_map(|instruction| instruction.jump_target(&chunk.bytes))
+        .collect::<FxHashSet<_>>();
+
+    thread_jumps(&instructions, &mut chunk.bytes);
+
+    let mut removed = Vec::new();
+    let mut previous: Option<&InstructionInfo> = None;
+
+    for instruction in instructions.iter() {
+        let bytes = &chunk.bytes[instruction.start..instruction.end];
+
+        let remove = match instruction.op {
+            Op::Copy if bytes[1] == bytes[2] => true,
+            Op::Copy => match previous {
+                Some(previous)
+                    if previous.op == Op::Copy && !jump_targets.contains(&instruction.start) =>
+                {
+                    let previous_bytes = &chunk.bytes[previous.start..previous.end];
+                    previous_bytes[1] == bytes[2] && previous_bytes[2] == bytes[1]
+                }
+                _ => false,
+            },
+            Op::Jump => instruction.jump_target(&chunk.bytes) == Some(instruction.end),
+            _ => false,
+        };
+
+        if remove {
+            removed.push(*instruction);
+            previous = None;
+        } else {
+            previous = Some(instruction);
+        }
+    }
+
+    if removed.is_empty() {
+        return;
+    }
+
+    let ip_map = IpMap::new(&removed);
+    let mut removed_iter = removed.iter().peekable();
+    let mut bytes = Vec::with_capacity(chunk.bytes.len() - ip_map.total_removed());
+
+    for instruction in instructions.iter() {
+        if let Some(next_removed) = removed_iter.peek()
+            && next_removed.start == instruction.start
+        {
+            removed_iter.next();
+            continue;
+        }
+
+        let new_start = bytes.len();
+        bytes.extend_from_slice(&chunk.bytes[instruction.start..instruction.end]);
+
+        if let Some(target) = instruction.jump_target(&chunk.bytes) {
+            let new_end = new_start + instruction.len();
+            let new_target = ip_map.map(target);
+            let offset = new_target.abs_diff(new_end);
+            // Removing instructions only reduces offsets, so the offset will always fit in a u16.
+            let offset_bytes = (offset as u16).to_le_bytes();
+            bytes[new_end - 2] = offset_bytes[0];
+            bytes[new_end - 1] = offset_bytes[1];
+        }
+    }
+
+    chunk.bytes = bytes;
+    chunk
+        .debug_info
+        .remap_ips(|ip| ip_map.map(ip as usize) as u32);
+}
+
+// Decodes the bytecode into a list of instructions
+//
+// None is returned if an error is encountered while decoding.
+fn decode_instructions(bytes: &[u8]) -> Option<Vec<InstructionInfo>> {
+    let chunk = Ptr::from(Chunk {
+        bytes: bytes.to_vec(),
+        ..Default::default()
+    });
+    let mut reader = InstructionReader::new(chunk);
+    let mut result = Vec::new();
+
+    loop {
+        let start = reader.ip;
+        match reader.next() {
+            Some(Instruction::Error { .. }) => return None,
+            Some(_) => result.push(InstructionInfo {
+                op: Op::from(bytes[start]),
+                start,
+                end: reader.ip,
+            }),
+            None => break,
+        }
+    }
+
+    if result.last().is_none_or(|last| last.end == bytes.len()) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+// Redirects jumps that land on an unconditional forward jump to the final jump target
+fn thread_jumps(instructions: &[InstructionInfo], bytes: &mut [u8]) {
+    let find_instruction = |ip: usize| {
+        instructions
+            .binary_search_by_key(&ip, |instruction| instruction.start)
+            .ok()
+            .map(|index| instructions[index])
+    };
+
+    for instruction in instructions.iter().filter(|i| i.op == Op::Jump) {
+        let Some(mut target) = instruction.jump_target(bytes) else {
+            continue;
+        };
+
+        // Limit the number of jumps that are followed to guard against cycles
+        for _ in 0..8 {
+            match find_instruction(target) {
+                Some(next) if next.op == Op::Jump && next.start != instruction.start => {
+                    match next.jump_target(bytes) {
+                        Some(next_target) if next_target > target => target = next_target,
+                        _ => break,
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        if let Ok(offset) = u16::try_from(target - instruction.end) {
+            let offset_bytes = offset.to_le_bytes();
+            bytes[instruction.end - 2] = offset_bytes[0];
+            bytes[instruction.end - 1] = offset_bytes[1];
+        }
+    }
+}
+
+// Maps instruction pointers from the original bytecode to the optimized bytecode
+struct IpMap {
+    // The start ip of each removed instruction,
+    // along with the total number of bytes removed up to and including the instruction
+    removed: Vec<(usize, usize)>,
+}
+
+impl IpMap {
+    fn new(removed_instructions: &[InstructionInfo]) -> Self {
+        let mut total = 0;
+        let removed = removed_instructions
+            .iter()
+            .map(|instruction| {
+                total += instruction.len();
+                (instruction.start, total)
+            })
+            .collect();
+        Self { removed }
+    }
+
+    fn total_removed(&self) -> usize {
+        self.removed.last().map_or(0, |(_, total)| *total)
+    }
+
+    // Removed instructions that start before the ip shift it back,
+    // an ip that refers to a removed instruction will be mapped to the following instruction.
+    fn map(&self, ip: usize) -> usize {
+        let removed_before = self.removed.partition_point(|(start, _)| *start < ip);
+        match removed_before {
+            0 => ip,
+            n => ip - self.removed[n - 1].1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Compiler, CompilerSettings};
+
+    fn compile(script: &str, optimize: bool) -> Chunk {
+        let settings = CompilerSettings {
+            optimize,
+            ..Default::default()
+        };
+        Compiler::compile(script, None, settings).unwrap()
+    }
+
+    fn instruction_count(chunk: Chunk) -> usize {
+        InstructionReader::new(chunk.into()).count()
+    }
+
+    #[test]
+    fn copy_to_self_is_removed() {
+        let script = "
+x = 1
+x = x
+";
+        let unoptimized = compile(script, false);
+        let optimized = compile(script, true);
+        assert_eq!(
+            instruction_count(optimized),
+            instruction_count(unoptimized) - 1
+        );
+    }
+
+    #[test]
+    fn reversed_copy_is_removed() {
+        let script = "
+x = 1
+y = x
+x = y
+";
+        let unoptimized = compile(script, false);
+        let optimized = compile(script, true);
+        assert_eq!(
+            instruction_count(optimized),
+            instruction_count(unoptimized) - 1
+        );
+    }
+
+    #[test]
+    fn chained_jumps_are_threaded() {
+        let script = "
+x = if true
+  if false then 1 else 2
+else
+  3
+";
+        let jump_offsets = |chunk: Chunk| {
+            InstructionReader::new(chunk.into())
+                .filter_map(|instruction| match instruction {
+                    Instruction::Jump { offset } => Some(offset),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(jump_offsets(compile(script, false)), [3, 3]);
+        assert_eq!(jump_offsets(compile(script, true)), [9, 3]);
+    }
+
+    #[test]
+    fn debug_spans_are_remapped() {
+        let script = "
+x = 1
+x = x
+y = x + 1
+";
+        let unoptimized = compile(script, false);
+        let optimized = compile(script, true);
+
+        let last_span = |chunk: &Chunk| {
+            let mut reader = InstructionReader::new(chunk.clone().into());
+            let mut ip = 0;
+            while reader.next().is_some() && reader.ip < chunk.bytes.len() {
+                ip = reader.ip;
+            }
+            chunk.debug_info.get_source_span(ip as u32).unwrap()
+        };
+
+        assert_eq!(last_span(&optimized), last_span(&unoptimized));
+    }
+}
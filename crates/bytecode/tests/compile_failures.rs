@@ -53,6 +53,36 @@ a...
         mod try_catch {
             use super::*;
 
+            #[test]
+            fn throw_without_value_outside_of_catch_block() {
+                let source = "
+throw
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn throw_without_value_in_try_block() {
+                let source = "
+try
+  throw
+catch _
+  0
+";
+                check_compilation_fails(source);
+            }
+
+            #[test]
+            fn throw_without_value_in_function_in_catch_block() {
+                let source = "
+try
+  f()
+catch _
+  g = || throw
+";
+                check_compilation_fails(source);
+            }
+
             #[test]
             fn missing_type_hint_on_first_catch_block() {
                 let source = "
@@ -128,18 +158,33 @@ match 0
             }
 
             #[test]
-            fn match_ellipsis_out_of_position() {
+            fn match_with_multiple_ellipses() {
                 let source = "
 match [1, 2, 3]
-  (x, ..., y) then 0
+  (x, ..., y, ...) then 0
 ";
                 check_compilation_fails(source);
             }
         }
 
+        #[test]
+        fn bitwise_operators_are_unsupported() {
+            for op in ["&&&", "|||", "^^^", "<<", ">>"] {
+                check_compilation_fails(&format!("1 {op} 2"));
+            }
+        }
+
         mod functions {
             use super::*;
 
+            #[test]
+            fn unpacked_arg_with_multiple_ellipses() {
+                let source = "
+f = |(a, ..., b, rest...)| a
+";
+                check_compilation_fails(source);
+            }
+
             #[test]
             fn error_in_unused_function() {
                 let source = "
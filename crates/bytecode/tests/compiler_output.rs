@@ -0,0 +1,78 @@
+mod bytecode {
+    use koto_bytecode::{Chunk, Compiler, CompilerSettings, Instruction, InstructionReader};
+    use koto_parser::Constant;
+
+    fn compile(source: &str) -> Chunk {
+        match Compiler::compile(source, None, CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => panic!("\nUnexpected error while compiling: {source}\n{error}"),
+        }
+    }
+
+    fn instructions(chunk: Chunk) -> Vec<Instruction> {
+        InstructionReader::new(chunk.into()).collect()
+    }
+
+    mod strings {
+        use super::*;
+
+        #[test]
+        fn literal_string_compiles_to_a_single_constant() {
+            let chunk = compile(r#"x = "hello, \{world}!""#);
+            let constant = chunk
+                .constants
+                .iter()
+                .position(|constant| matches!(constant, Constant::Str("hello, {world}!")))
+                .expect("Missing string constant");
+
+            let instructions = instructions(chunk);
+            assert!(
+                instructions.iter().any(|instruction| matches!(
+                    instruction,
+                    Instruction::LoadString { constant: c, .. } if usize::from(*c) == constant
+                )),
+                "Expected the string to be loaded from a constant"
+            );
+            assert!(
+                !instructions
+                    .iter()
+                    .any(|instruction| matches!(instruction, Instruction::StringStart { .. })),
+                "Unexpected string builder instructions"
+            );
+        }
+
+        #[test]
+        fn interpolated_string_uses_a_string_builder() {
+            let chunk = compile(
+                "
+x = 42
+y = 'x: {x}, {x:.1}!'
+",
+            );
+
+            let string_pushes = instructions(chunk)
+                .iter()
+                .filter(|instruction| matches!(instruction, Instruction::StringPush { .. }))
+                .count();
+            // 'x: ', x, ', ', x, '!'
+            assert_eq!(string_pushes, 5);
+        }
+
+        #[test]
+        fn escaped_characters_dont_split_literals() {
+            let chunk = compile(
+                r#"
+x = 42
+y = "a\n\u{1F44B}\{b} {x} c\td\\e"
+"#,
+            );
+
+            let string_pushes = instructions(chunk)
+                .iter()
+                .filter(|instruction| matches!(instruction, Instruction::StringPush { .. }))
+                .count();
+            // 'a\n👋{b} ', x, ' c\td\e'
+            assert_eq!(string_pushes, 3);
+        }
+    }
+}
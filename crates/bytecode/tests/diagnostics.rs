@@ -0,0 +1,316 @@
+mod diagnostics {
+    use koto_bytecode::{Compiler, CompilerSettings, DiagnosticKind};
+
+    fn check_diagnostics(source: &str, expected: &[(DiagnosticKind, u32)]) {
+        check_diagnostics_with_defined(source, &[], expected);
+    }
+
+    fn check_diagnostics_with_defined(
+        source: &str,
+        defined: &[&str],
+        expected: &[(DiagnosticKind, u32)],
+    ) {
+        let diagnostics = match Compiler::check(source, CompilerSettings::default(), |name| {
+            defined.contains(&name)
+        }) {
+            Ok(diagnostics) => diagnostics,
+            Err(error) => panic!("\nUnexpected error while checking: {error}\n{source}"),
+        };
+
+        let diagnostics = diagnostics
+            .into_iter()
+            .map(|diagnostic| (diagnostic.kind, diagnostic.span.start.line))
+            .collect::<Vec<_>>();
+
+        assert_eq!(diagnostics, expected, "\n{source}");
+    }
+
+    fn undefined(name: &str) -> DiagnosticKind {
+        DiagnosticKind::UndefinedId(name.into())
+    }
+
+    fn duplicate_key(key: &str) -> DiagnosticKind {
+        DiagnosticKind::DuplicateMapKey(key.into())
+    }
+
+    fn unused(name: &str) -> DiagnosticKind {
+        DiagnosticKind::UnusedBinding(name.into())
+    }
+
+    fn shadowed(name: &str) -> DiagnosticKind {
+        DiagnosticKind::ShadowedBinding(name.into())
+    }
+
+    #[test]
+    fn no_diagnostics() {
+        let source = "
+x = 1
+f = |n| n + x
+f 42
+";
+        check_diagnostics(source, &[]);
+    }
+
+    mod undefined_ids {
+        use super::*;
+
+        #[test]
+        fn undefined_id() {
+            let source = "
+x = 1
+y = x + z
+";
+            check_diagnostics(source, &[(undefined("z"), 2)]);
+        }
+
+        #[test]
+        fn access_before_assignment() {
+            let source = "
+a = b
+b = 1
+";
+            check_diagnostics(source, &[(undefined("b"), 1)]);
+        }
+
+        #[test]
+        fn undefined_id_in_function() {
+            let source = "
+f = |x|
+  x + y
+";
+            check_diagnostics(source, &[(undefined("y"), 2)]);
+        }
+
+        #[test]
+        fn recursive_function() {
+            let source = "
+f = |n| if n > 0 then f n - 1 else 0
+";
+            check_diagnostics(source, &[]);
+        }
+
+        #[test]
+        fn forward_reference_to_local() {
+            let source = "
+f = || g()
+g = || 42
+";
+            check_diagnostics(source, &[(undefined("g"), 1)]);
+        }
+
+        #[test]
+        fn exported_top_level_ids() {
+            let source = "
+f = || g()
+g = || 42
+";
+            let diagnostics = Compiler::check(
+                source,
+                CompilerSettings {
+                    export_top_level_ids: true,
+                    ..Default::default()
+                },
+                |_| false,
+            )
+            .unwrap();
+            assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        }
+
+        #[test]
+        fn defined_by_caller() {
+            let source = "
+print size [1, 2, 3]
+";
+            check_diagnostics_with_defined(source, &["print", "size"], &[]);
+        }
+
+        #[test]
+        fn exported_later_in_the_script() {
+            let source = "
+f = || g()
+export g = || 42
+";
+            check_diagnostics(source, &[]);
+        }
+
+        #[test]
+        fn exported_via_map_later_in_the_script() {
+            let source = "
+f = || a + b
+export
+  a: 1
+  b: 2
+";
+            check_diagnostics(source, &[]);
+        }
+
+        #[test]
+        fn after_wildcard_import() {
+            let source = "
+from foo import *
+bar()
+";
+            check_diagnostics_with_defined(source, &[], &[]);
+        }
+    }
+
+    mod unreachable_code {
+        use super::*;
+
+        #[test]
+        fn after_return() {
+            let source = "
+f = ||
+  return 1
+  x = 2
+  x
+";
+            check_diagnostics(source, &[(DiagnosticKind::UnreachableCode, 3)]);
+        }
+
+        #[test]
+        fn after_break() {
+            let source = "
+for x in 0..10
+  break
+  x
+";
+            check_diagnostics(source, &[(DiagnosticKind::UnreachableCode, 3)]);
+        }
+
+        #[test]
+        fn return_as_last_expression() {
+            let source = "
+f = ||
+  x = 1
+  return x
+";
+            check_diagnostics(source, &[]);
+        }
+    }
+
+    mod duplicate_map_keys {
+        use super::*;
+
+        #[test]
+        fn inline_map() {
+            let source = "
+x = {a: 1, b: 2, a: 3}
+";
+            check_diagnostics(source, &[(duplicate_key("a"), 1)]);
+        }
+
+        #[test]
+        fn block_map_with_string_keys() {
+            let source = "
+x =
+  'foo': 1
+  bar: 2
+  foo: 3
+";
+            check_diagnostics(source, &[(duplicate_key("foo"), 4)]);
+        }
+
+        #[test]
+        fn shorthand_id_entry() {
+            let source = "
+a = 1
+x = {a, a: 2}
+";
+            check_diagnostics(source, &[(duplicate_key("a"), 2)]);
+        }
+    }
+
+    mod unused_bindings {
+        use super::*;
+
+        #[test]
+        fn unused_local() {
+            let source = "
+f = |n|
+  x = n * 2
+  n
+";
+            check_diagnostics(source, &[(unused("x"), 2)]);
+        }
+
+        #[test]
+        fn unused_arg() {
+            let source = "
+f = |a, b| a
+";
+            check_diagnostics(source, &[(unused("b"), 1)]);
+        }
+
+        #[test]
+        fn wildcard_ids_are_ignored() {
+            let source = "
+f = |a, _b|
+  _unused = a * 2
+  a
+";
+            check_diagnostics(source, &[]);
+        }
+
+        #[test]
+        fn local_captured_by_nested_function() {
+            let source = "
+f = |n|
+  x = n * 2
+  || x
+";
+            check_diagnostics(source, &[]);
+        }
+
+        #[test]
+        fn unused_top_level_values_are_ignored() {
+            let source = "
+x = 42
+";
+            check_diagnostics(source, &[]);
+        }
+    }
+
+    mod shadowed_bindings {
+        use super::*;
+
+        #[test]
+        fn shadowed_arg() {
+            let source = "
+x = 1
+f = |x| x + 1
+";
+            check_diagnostics(source, &[(shadowed("x"), 2)]);
+        }
+
+        #[test]
+        fn shadowed_local() {
+            let source = "
+x = 1
+f = ||
+  x = 2
+  x
+";
+            check_diagnostics(source, &[(shadowed("x"), 3)]);
+        }
+
+        #[test]
+        fn reassignment_is_not_shadowing() {
+            let source = "
+f = |x|
+  x = x + 1
+  x
+";
+            check_diagnostics(source, &[]);
+        }
+
+        #[test]
+        fn binding_assigned_after_nested_function() {
+            let source = "
+f = |x| x + 1
+x = 1
+";
+            check_diagnostics(source, &[]);
+        }
+    }
+}
@@ -139,6 +139,7 @@ fn main() -> Result<()> {
 
     let koto_settings = KotoSettings {
         run_tests: args.run_tests || args.run_import_tests,
+        optimize: false,
         vm_settings: KotoVmSettings {
             run_import_tests: args.run_import_tests,
             args: args.script_args,
@@ -197,6 +198,7 @@ fn main() -> Result<()> {
                 script: &script,
                 script_path: script_path.map(KString::from),
                 compiler_settings: Default::default(),
+                strict: false,
             }) {
                 Ok(chunk) => {
                     if args.show_bytecode {
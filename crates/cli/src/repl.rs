@@ -68,12 +68,12 @@ impl From<EditMode> for rustyline::EditMode {
 type ReplEditor = Editor<ReplHelper, DefaultHistory>;
 
 pub struct Repl {
-    koto: Koto,
+    // The REPL state, including the buffer of lines for expressions that continue over multiple
+    // lines
+    repl: koto::Repl,
     settings: ReplSettings,
     editor: ReplEditor,
     stdout: Stdout,
-    // A buffer of lines for expressions that continue over multiple lines
-    continued_lines: Vec<String>,
     indent: usize,
     colored_output: bool,
 }
@@ -126,11 +126,10 @@ impl Repl {
         let colored_output = settings.colored_output && stdout.is_tty();
 
         Ok(Self {
-            koto,
+            repl: koto::Repl::with_koto(koto),
             settings,
             editor,
             stdout,
-            continued_lines: Vec::new(),
             indent: 0,
             colored_output,
         })
@@ -147,7 +146,7 @@ Run `help` for more information
         )?;
 
         loop {
-            let result = if self.continued_lines.is_empty() {
+            let result = if !self.repl.is_continuing() {
                 self.editor.readline(PROMPT)
             } else {
                 let indent = " ".repeat(self.indent);
@@ -162,7 +161,7 @@ Run `help` for more information
                 Err(ReadlineError::Interrupted) => {
                     writeln!(self.stdout, "^C")?;
                     self.stdout.flush()?;
-                    self.continued_lines.clear();
+                    self.repl.clear_pending_input();
                     self.indent = 0;
                 }
                 Err(ReadlineError::Eof) => {
@@ -185,107 +184,88 @@ Run `help` for more information
     }
 
     fn on_line(&mut self, line: &str) -> Result<()> {
-        let input_is_whitespace = line.chars().all(|c| c.is_whitespace());
+        let Some(input) = self.repl.push_line(line) else {
+            // We're in a continued expression, so check if the next line should be indented
+            let input = self.repl.pending_lines().join("\n");
+            let indent_next_line = matches!(
+                self.repl.koto_mut().compile(&input),
+                Err(e) if e.is_indentation_error()
+            );
 
-        let mut indent_next_line = false;
+            let current_indent = self
+                .repl
+                .pending_lines()
+                .last()
+                .and_then(|line| line.find(|c: char| !c.is_whitespace()))
+                .unwrap_or(0);
 
-        if self.continued_lines.is_empty() || input_is_whitespace {
-            let mut input = self.continued_lines.join("\n");
+            self.indent = if indent_next_line {
+                current_indent + INDENT_SIZE
+            } else {
+                current_indent
+            };
 
-            if !input_is_whitespace {
-                input += line;
-            }
+            return Ok(());
+        };
 
-            self.editor.add_history_entry(&input)?;
+        self.indent = 0;
 
-            let compile_args = CompileArgs::new(&input).export_top_level_ids(true);
-            match self.koto.compile(compile_args) {
-                Ok(chunk) => {
-                    if self.settings.show_bytecode {
-                        print_wrapped!(self.stdout, "{}\n", &Chunk::bytes_as_string(&chunk))?;
-                    }
-                    if self.settings.show_instructions {
-                        print_wrapped!(self.stdout, "Constants\n---------\n{}\n", chunk.constants)?;
+        if input.is_empty() {
+            return Ok(());
+        }
 
-                        let script_lines = input.lines().collect::<Vec<_>>();
+        self.editor.add_history_entry(&input)?;
 
-                        print_wrapped!(
-                            self.stdout,
-                            "Instructions\n------------\n{}",
-                            Chunk::instructions_as_string(chunk.clone(), &script_lines)
-                        )?;
-                    }
-                    match self.koto.run(chunk) {
-                        Ok(result) => match self.koto.value_to_string(result.clone()) {
-                            Ok(result_string) => {
-                                self.print_result(&result_string)?;
-                            }
-                            Err(e) => {
-                                print_wrapped!(
-                                    self.stdout,
-                                    "Error while getting display string for return value ({})",
-                                    e
-                                )?;
-                            }
-                        },
-                        Err(error) => {
-                            if let Some(help) = self.run_help(&input) {
-                                print_wrapped_indented!(self.stdout, HELP_INDENT, "{help}")?;
-                                writeln!(self.stdout)?;
-                            } else {
-                                self.print_error(&error)?;
-                            }
-                        }
-                    }
-                    self.continued_lines.clear();
+        match self.repl.compile(&input) {
+            Ok(chunk) => {
+                if self.settings.show_bytecode {
+                    print_wrapped!(self.stdout, "{}\n", &Chunk::bytes_as_string(&chunk))?;
+                }
+                if self.settings.show_instructions {
+                    print_wrapped!(self.stdout, "Constants\n---------\n{}\n", chunk.constants)?;
+
+                    let script_lines = input.lines().collect::<Vec<_>>();
+
+                    print_wrapped!(
+                        self.stdout,
+                        "Instructions\n------------\n{}",
+                        Chunk::instructions_as_string(chunk.clone(), &script_lines)
+                    )?;
                 }
-                Err(compile_error) => {
-                    if let Some(help) = self.run_help(&input) {
-                        print_wrapped!(self.stdout, "{}\n", help)?;
-                        self.continued_lines.clear();
-                    } else if compile_error.is_indentation_error()
-                        && self.continued_lines.is_empty()
-                    {
-                        self.continued_lines.push(line.to_string());
-                        indent_next_line = true;
-                    } else {
-                        self.editor.add_history_entry(&input)?;
-
-                        self.print_error(&compile_error.to_string())?;
-                        self.continued_lines.clear();
+
+                let koto = self.repl.koto_mut();
+                match koto.run(chunk) {
+                    Ok(result) => match koto.value_to_string(result.clone()) {
+                        Ok(result_string) => {
+                            self.print_result(&result_string)?;
+                        }
+                        Err(e) => {
+                            print_wrapped!(
+                                self.stdout,
+                                "Error while getting display string for return value ({})",
+                                e
+                            )?;
+                        }
+                    },
+                    Err(error) => {
+                        if let Some(help) = self.run_help(&input) {
+                            print_wrapped_indented!(self.stdout, HELP_INDENT, "{help}")?;
+                            writeln!(self.stdout)?;
+                        } else {
+                            self.print_error(&error)?;
+                        }
                     }
                 }
             }
-        } else {
-            // We're in a continued expression, so cache the input for execution later
-            self.continued_lines.push(line.to_string());
-
-            // Check if we should add indentation on the next line
-            let input = self.continued_lines.join("\n");
-            if let Err(e) = self.koto.compile(&input)
-                && e.is_indentation_error()
-            {
-                indent_next_line = true;
+            Err(compile_error) => {
+                if let Some(help) = self.run_help(&input) {
+                    print_wrapped!(self.stdout, "{}\n", help)?;
+                } else {
+                    self.print_error(&compile_error.to_string())?;
+                }
             }
         }
 
-        if self.continued_lines.is_empty() {
-            self.indent = 0;
-        } else {
-            let current_indent = self
-                .continued_lines
-                .last()
-                .unwrap()
-                .find(|c: char| !c.is_whitespace())
-                .unwrap_or(0);
-
-            self.indent = if indent_next_line {
-                current_indent + INDENT_SIZE
-            } else {
-                current_indent
-            };
-        };
-
         Ok(())
     }
 
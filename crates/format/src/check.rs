@@ -0,0 +1,356 @@
+use crate::{FormatOptions, Result, format};
+use koto_lexer::{Position, Span};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// A difference between a script and its formatted output, produced by [`check`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatDiff {
+    /// The span of the lines in the input that differ from the formatted output
+    ///
+    /// The span starts at the beginning of the first differing line, and ends at the start of the
+    /// line that follows the last differing line (or at the end of the input).
+    pub span: Span,
+    /// The formatted text that should replace the span's contents
+    pub replacement: String,
+}
+
+/// Checks if the input source is formatted according to the provided options
+///
+/// Rather than returning the formatted output, the differences between the input and the
+/// formatted output are returned. An empty list means that the input is already formatted.
+pub fn check(source: &str, options: FormatOptions) -> Result<Vec<FormatDiff>> {
+    let output = format(source, options)?;
+
+    if output == source {
+        return Ok(Vec::new());
+    }
+
+    let input_lines = source.split_inclusive('\n').collect::<Vec<_>>();
+    let output_lines = output.split_inclusive('\n').collect::<Vec<_>>();
+
+    let result = diff_lines(&input_lines, &output_lines)
+        .into_iter()
+        .map(|hunk| FormatDiff {
+            span: lines_span(&input_lines, hunk.input),
+            replacement: output_lines[hunk.output].concat(),
+        })
+        .collect();
+
+    Ok(result)
+}
+
+// A range of differing lines in the input, along with the output lines that replace them
+#[derive(Debug)]
+struct Hunk {
+    input: Range<usize>,
+    output: Range<usize>,
+}
+
+// Produces the list of hunks that transform the input lines into the output lines
+fn diff_lines(input: &[&str], output: &[&str]) -> Vec<Hunk> {
+    // Lines that are common to the start or end of both inputs can be skipped
+    let prefix = input.iter().zip(output).take_while(|(a, b)| a == b).count();
+    let suffix = input[prefix..]
+        .iter()
+        .rev()
+        .zip(output[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let input_end = input.len() - suffix;
+    let output_end = output.len() - suffix;
+    let matches = matching_lines(&input[prefix..input_end], &output[prefix..output_end]);
+
+    let mut result = Vec::new();
+    let mut input_line = prefix;
+    let mut output_line = prefix;
+
+    for (input_match, output_match) in matches
+        .into_iter()
+        .map(|(i, o)| (prefix + i, prefix + o))
+        .chain(std::iter::once((input_end, output_end)))
+    {
+        if input_line < input_match || output_line < output_match {
+            result.push(Hunk {
+                input: input_line..input_match,
+                output: output_line..output_match,
+            });
+        }
+        input_line = input_match + 1;
+        output_line = output_match + 1;
+    }
+
+    result
+}
+
+// Finds the longest sequence of matching lines using Myers' diff algorithm
+//
+// The linear space variant of the algorithm is used, which recursively splits the inputs at the
+// 'middle snake' of the shortest edit script.
+//
+// The indices of the matching lines in the input and output are returned in ascending order.
+fn matching_lines(input: &[&str], output: &[&str]) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    find_matching_lines(input, output, 0, 0, &mut result);
+    result
+}
+
+// Adds the matching lines of the input and output to the result, offset by the given positions
+fn find_matching_lines(
+    input: &[&str],
+    output: &[&str],
+    input_offset: usize,
+    output_offset: usize,
+    result: &mut Vec<(usize, usize)>,
+) {
+    let prefix = input.iter().zip(output).take_while(|(a, b)| a == b).count();
+    let suffix = input[prefix..]
+        .iter()
+        .rev()
+        .zip(output[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    result.extend((0..prefix).map(|i| (input_offset + i, output_offset + i)));
+
+    let input_middle = &input[prefix..input.len() - suffix];
+    let output_middle = &output[prefix..output.len() - suffix];
+
+    // If either side is empty then the remaining lines are all insertions or deletions.
+    // Otherwise, the middle snake is found and the lines on either side of it are searched.
+    if !input_middle.is_empty() && !output_middle.is_empty() {
+        let input_offset = input_offset + prefix;
+        let output_offset = output_offset + prefix;
+        let snake = middle_snake(input_middle, output_middle);
+
+        find_matching_lines(
+            &input_middle[..snake.start.0],
+            &output_middle[..snake.start.1],
+            input_offset,
+            output_offset,
+            result,
+        );
+
+        result.extend((0..snake.end.0 - snake.start.0).map(|i| {
+            (
+                input_offset + snake.start.0 + i,
+                output_offset + snake.start.1 + i,
+            )
+        }));
+
+        find_matching_lines(
+            &input_middle[snake.end.0..],
+            &output_middle[snake.end.1..],
+            input_offset + snake.end.0,
+            output_offset + snake.end.1,
+            result,
+        );
+    }
+
+    let input_suffix_start = input_offset + input.len() - suffix;
+    let output_suffix_start = output_offset + output.len() - suffix;
+    result.extend((0..suffix).map(|i| (input_suffix_start + i, output_suffix_start + i)));
+}
+
+// A sequence of matching lines in the middle of the shortest edit script
+//
+// The start and end positions are (input, output) line indices, with `end` being exclusive.
+struct Snake {
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
+// Finds the middle snake of the shortest edit script between the input and the output
+//
+// The search is performed simultaneously from the start and end of the inputs until the two
+// searches overlap. Only the furthest reaching positions of the current step are kept, so the
+// memory usage is linear in the size of the inputs.
+//
+// The input and output must be non-empty.
+fn middle_snake(input: &[&str], output: &[&str]) -> Snake {
+    let n = input.len() as isize;
+    let m = output.len() as isize;
+    let delta = n - m;
+    let delta_is_odd = delta % 2 != 0;
+    let max = (n + m + 1) / 2;
+    // Diagonals range from -(max + 1) to (max + 1), so they're offset to produce indices
+    let index = |k: isize| (k + max + 1) as usize;
+
+    // The furthest reaching x positions for each diagonal,
+    // with the backward search's positions counted from the end of the inputs.
+    let mut forward = vec![0; 2 * max as usize + 3];
+    let mut backward = vec![0; 2 * max as usize + 3];
+
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && forward[index(k - 1)] < forward[index(k + 1)]) {
+                forward[index(k + 1)]
+            } else {
+                forward[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            let start = (x as usize, y as usize);
+
+            while x < n && y < m && input[x as usize] == output[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            forward[index(k)] = x;
+
+            // When delta is odd, the searches can first overlap during a forward step
+            let c = delta - k;
+            if delta_is_odd && (-(d - 1)..=d - 1).contains(&c) && x + backward[index(c)] >= n {
+                return Snake {
+                    start,
+                    end: (x as usize, y as usize),
+                };
+            }
+        }
+
+        for c in (-d..=d).step_by(2) {
+            let mut x = if c == -d || (c != d && backward[index(c - 1)] < backward[index(c + 1)]) {
+                backward[index(c + 1)]
+            } else {
+                backward[index(c - 1)] + 1
+            };
+            let mut y = x - c;
+            let end = ((n - x) as usize, (m - y) as usize);
+
+            while x < n && y < m && input[(n - x - 1) as usize] == output[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            backward[index(c)] = x;
+
+            // When delta is even, the searches can first overlap during a backward step
+            let k = delta - c;
+            if !delta_is_odd && (-d..=d).contains(&k) && x + forward[index(k)] >= n {
+                return Snake {
+                    start: ((n - x) as usize, (m - y) as usize),
+                    end,
+                };
+            }
+        }
+    }
+
+    unreachable!("the forward and backward searches always overlap")
+}
+
+// Returns the span that covers the given range of lines
+fn lines_span(lines: &[&str], range: Range<usize>) -> Span {
+    let start = Position {
+        line: range.start as u32,
+        column: 0,
+    };
+
+    let end = match lines.get(range.end.saturating_sub(1)) {
+        // A final line without a newline ends at the line's last column
+        Some(last) if range.end == lines.len() && !last.ends_with('\n') && !range.is_empty() => {
+            Position {
+                line: range.end as u32 - 1,
+                column: last.width() as u32,
+            }
+        }
+        _ => Position {
+            line: range.end as u32,
+            column: 0,
+        },
+    };
+
+    Span { start, end }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_diff(input: &[&str], output: &[&str], expected: &[(Range<usize>, Range<usize>)]) {
+        let hunks = diff_lines(input, output)
+            .into_iter()
+            .map(|hunk| (hunk.input, hunk.output))
+            .collect::<Vec<_>>();
+        assert_eq!(hunks, expected);
+    }
+
+    #[test]
+    fn identical() {
+        check_diff(&["a", "b"], &["a", "b"], &[]);
+    }
+
+    #[test]
+    fn changed_line() {
+        check_diff(&["a", "b", "c"], &["a", "x", "c"], &[(1..2, 1..2)]);
+    }
+
+    #[test]
+    fn inserted_and_removed_lines() {
+        check_diff(
+            &["a", "b", "c", "d", "e"],
+            &["a", "c", "d", "x", "e"],
+            &[(1..2, 1..1), (4..4, 3..4)],
+        );
+    }
+
+    #[test]
+    fn completely_different() {
+        check_diff(&["a", "b"], &["x", "y", "z"], &[(0..2, 0..3)]);
+    }
+
+    #[test]
+    fn interleaved_changes() {
+        check_diff(
+            &["a", "b", "c", "a", "b", "b", "a"],
+            &["c", "b", "a", "b", "a", "c"],
+            &[(0..1, 0..1), (2..3, 2..2), (5..6, 4..4), (7..7, 5..6)],
+        );
+    }
+
+    #[test]
+    fn matching_lines_are_a_longest_common_subsequence() {
+        // Compares the number of matches against the LCS length found via dynamic programming
+        fn lcs_length(a: &[&str], b: &[&str]) -> usize {
+            let mut lengths = vec![vec![0; b.len() + 1]; a.len() + 1];
+            for i in 0..a.len() {
+                for j in 0..b.len() {
+                    lengths[i + 1][j + 1] = if a[i] == b[j] {
+                        lengths[i][j] + 1
+                    } else {
+                        lengths[i][j + 1].max(lengths[i + 1][j])
+                    };
+                }
+            }
+            lengths[a.len()][b.len()]
+        }
+
+        let lines = ["a", "b", "c"];
+        // A simple deterministic generator for a variety of line sequences
+        let mut seed = 12345u32;
+        let mut make_lines = |len: usize| {
+            (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                    lines[(seed >> 16) as usize % lines.len()]
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for len_a in 0..12 {
+            for len_b in 0..12 {
+                let a = make_lines(len_a);
+                let b = make_lines(len_b);
+                let matches = matching_lines(&a, &b);
+
+                assert_eq!(matches.len(), lcs_length(&a, &b), "{a:?} {b:?}");
+                assert!(matches.iter().all(|&(i, j)| a[i] == b[j]));
+                assert!(
+                    matches
+                        .windows(2)
+                        .all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1)
+                );
+            }
+        }
+    }
+}
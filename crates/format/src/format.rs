@@ -6,7 +6,7 @@ use koto_lexer::Position;
 use koto_parser::{
     Ast, AstCatch, AstFor, AstIf, AstIndex, AstNode, AstString, AstTry, AstUnaryOp, ChainNode,
     ConstantIndex, ConstantPool, Function, ImportItem, KString, Node, ParserOptions, Span,
-    StringAlignment, StringContents, StringFormatOptions, StringNode,
+    StringAlignment, StringContents, StringFormatOptions, StringFormatRepresentation, StringNode,
 };
 use std::{cell::OnceCell, iter};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
@@ -18,6 +18,7 @@ pub fn format(source: &str, options: FormatOptions) -> Result<String> {
         source,
         ParserOptions {
             process_escape_codes: false,
+            ..Default::default()
         },
     )?;
 
@@ -258,7 +259,8 @@ fn format_node<'source>(
         Node::Map { entries, braces } => {
             if *braces {
                 let span = ctx.span(node);
-                let force_break = span.start.line < span.end.line;
+                // Empty containers are always collapsed onto a single line
+                let force_break = !entries.is_empty() && span.start.line < span.end.line;
 
                 let mut group = GroupBuilder::new(entries.len() * 2 + 4, node, ctx, trivia)
                     .char('{')
@@ -321,7 +323,8 @@ fn format_node<'source>(
             .build(),
         Node::MapPattern { entries, type_hint } => {
             let span = ctx.span(node);
-            let force_break = span.start.line < span.end.line;
+            // Empty containers are always collapsed onto a single line
+            let force_break = !entries.is_empty() && span.start.line < span.end.line;
             let type_hint_capacity = if type_hint.is_some() { 3 } else { 0 };
 
             let mut group = GroupBuilder::new(
@@ -835,11 +838,16 @@ fn format_node<'source>(
 
             group.build_block()
         }
-        Node::Throw(value) => FormatItem::from_keyword_and_value("throw", value, node, ctx, trivia),
+        Node::Throw(value) => match value {
+            Some(value) => FormatItem::from_keyword_and_value("throw", value, node, ctx, trivia),
+            None => "throw".into(),
+        },
         Node::Yield(value) => FormatItem::from_keyword_and_value("yield", value, node, ctx, trivia),
         Node::Debug { expression, .. } => {
             FormatItem::from_keyword_and_value("debug", expression, node, ctx, trivia)
         }
+        // Attributes are captured as trivia, and will have already been added to the output
+        Node::Attributed { expression, .. } => format_node(*expression, ctx, trivia),
         Node::Type {
             type_index,
             allow_null,
@@ -1310,7 +1318,7 @@ impl<'source, 'trivia> GroupBuilder<'source, 'trivia> {
                 self.strip_trailing_breaks();
                 self.items.push(FormatItem::LineBreak);
             }
-            TriviaToken::CommentSingle | TriviaToken::SkipNode => {
+            TriviaToken::CommentSingle | TriviaToken::Attribute | TriviaToken::SkipNode => {
                 if item.token == TriviaToken::SkipNode {
                     self.skip_next_node = true;
                 }
@@ -1349,7 +1357,12 @@ impl<'source, 'trivia> GroupBuilder<'source, 'trivia> {
                     _ => {}
                 }
 
-                self.add_source_region(&item.span);
+                if self.ctx.options.preserve_multiline_comment_bodies {
+                    self.add_source_region(&item.span);
+                } else {
+                    let comment = normalize_comment_spacing(self.ctx.source_slice(&item.span));
+                    self.items.push(FormatItem::KString(comment.into()));
+                }
 
                 match position_info {
                     TriviaPosition::LineStart if item.span.end.line < position.line => {
@@ -1681,6 +1694,31 @@ impl<'source> FormatItem<'source> {
     }
 }
 
+// Collapses runs of spaces and removes trailing whitespace in each line of a multiline comment
+//
+// Each line's indentation is left unchanged.
+fn normalize_comment_spacing(comment: &str) -> String {
+    let mut result = String::with_capacity(comment.len());
+
+    for (i, line) in comment.lines().enumerate() {
+        if i > 0 {
+            result.push('\n');
+        }
+
+        let contents = line.trim_start();
+        result.push_str(&line[..line.len() - contents.len()]);
+
+        for (j, word) in contents.split_whitespace().enumerate() {
+            if j > 0 {
+                result.push(' ');
+            }
+            result.push_str(word);
+        }
+    }
+
+    result
+}
+
 impl<'source> From<&'source str> for FormatItem<'source> {
     fn from(s: &'source str) -> Self {
         Self::Str(s)
@@ -1696,8 +1734,16 @@ impl From<Error> for FormatItem<'_> {
 fn render_format_options(options: &StringFormatOptions, constants: &ConstantPool) -> String {
     let mut result = String::new();
 
-    if let Some(constant_index) = options.fill_character {
-        result.push_str(constants.get_str(constant_index));
+    // A '0' fill character without alignment is written following the sign, e.g. `+08`
+    let fill = options
+        .fill_character
+        .map(|constant_index| constants.get_str(constant_index));
+    let zero_fill = fill == Some("0") && options.alignment == StringAlignment::Default;
+
+    if let Some(fill) = fill
+        && !zero_fill
+    {
+        result.push_str(fill);
     }
 
     match options.alignment {
@@ -1713,12 +1759,36 @@ fn render_format_options(options: &StringFormatOptions, constants: &ConstantPool
         }
     }
 
+    if options.show_sign {
+        result.push('+');
+    }
+    if options.alternate_form {
+        result.push('#');
+    }
+    if zero_fill {
+        result.push('0');
+    }
     if let Some(min_width) = options.min_width {
         result.push_str(&min_width.to_string());
     }
+    if options.group_thousands {
+        result.push(',');
+    }
     if let Some(precision) = options.precision {
         result.push_str(&format!(".{precision}"));
     }
+    if let Some(representation) = options.representation {
+        use StringFormatRepresentation::*;
+        result.push(match representation {
+            Debug => '?',
+            HexLower => 'x',
+            HexUpper => 'X',
+            Binary => 'b',
+            Octal => 'o',
+            ExpLower => 'e',
+            ExpUpper => 'E',
+        });
+    }
 
     result
 }
@@ -1,9 +1,11 @@
+mod check;
 mod error;
 mod format;
 mod options;
 mod trivia;
 
 pub use crate::{
+    check::{FormatDiff, check},
     error::{Error, ErrorKind, Result},
     format::format,
     options::FormatOptions,
@@ -20,6 +20,11 @@ pub struct FormatOptions {
     ///
     /// A value of `0` disables the threshold.
     pub chain_break_threshold: u8,
+    /// Whether or not the contents of multiline comments should be preserved. (default: `true`)
+    ///
+    /// When disabled, runs of spaces in the comment body are collapsed and trailing whitespace is
+    /// removed, while the indentation of each line is kept.
+    pub preserve_multiline_comment_bodies: bool,
 }
 
 impl Default for FormatOptions {
@@ -29,6 +34,7 @@ impl Default for FormatOptions {
             chain_break_threshold: 4,
             indent_width: 2,
             line_length: 100,
+            preserve_multiline_comment_bodies: true,
         }
     }
 }
@@ -23,8 +23,9 @@ impl Trivia {
             }
 
             let maybe_trivia = match token.token {
-                Token::CommentSingle => {
-                    let mut trivia = TriviaToken::CommentSingle;
+                Token::CommentSingle => Some(TriviaToken::CommentSingle),
+                Token::Attribute => {
+                    let mut trivia = TriviaToken::Attribute;
 
                     // Check for #[fmt: skip] commands
                     let slice = token.slice(source).trim();
@@ -87,6 +88,8 @@ pub enum TriviaToken {
     CommentSingle,
     /// A multi-line comment
     CommentMulti,
+    /// An attribute, e.g. `#[foo]`
+    Attribute,
     /// A directive to skip formatting for the following node
     SkipNode,
 }
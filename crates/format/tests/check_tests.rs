@@ -0,0 +1,90 @@
+mod check {
+    use koto_format::{FormatDiff, FormatOptions, check};
+    use koto_lexer::{Position, Span};
+
+    fn span(start: (u32, u32), end: (u32, u32)) -> Span {
+        Span {
+            start: Position {
+                line: start.0,
+                column: start.1,
+            },
+            end: Position {
+                line: end.0,
+                column: end.1,
+            },
+        }
+    }
+
+    fn check_diffs(source: &str, expected: &[FormatDiff]) {
+        match check(source, FormatOptions::default()) {
+            Ok(diffs) => assert_eq!(diffs, expected, "Mismatch in diffs for:\n{source}"),
+            Err(error) => panic!("error while checking: {error}\ninput:\n{source}"),
+        }
+    }
+
+    #[test]
+    fn formatted_source_has_no_diffs() {
+        check_diffs(
+            "\
+x = 1
+f = |a, b|
+  a + b
+print f x, 2
+",
+            &[],
+        );
+    }
+
+    #[test]
+    fn unformatted_lines() {
+        check_diffs(
+            "\
+x   =   1
+y = 2
+f = |a,b|
+  a+b
+print f x, y
+",
+            &[
+                FormatDiff {
+                    span: span((0, 0), (1, 0)),
+                    replacement: "x = 1\n".into(),
+                },
+                FormatDiff {
+                    span: span((2, 0), (4, 0)),
+                    replacement: "f = |a, b|\n  a + b\n".into(),
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn missing_newline_at_end_of_input() {
+        check_diffs(
+            "\
+x = 1
+y = x+1",
+            &[FormatDiff {
+                span: span((1, 0), (1, 7)),
+                replacement: "y = x + 1\n".into(),
+            }],
+        );
+    }
+
+    #[test]
+    fn removed_lines() {
+        check_diffs(
+            "\
+x = 1
+
+
+
+y = 2
+",
+            &[FormatDiff {
+                span: span((2, 0), (4, 0)),
+                replacement: "".into(),
+            }],
+        );
+    }
+}
@@ -168,6 +168,88 @@ x = 1
 #-
   comment
 -#
+",
+            );
+        }
+
+        #[test]
+        fn multiline_comment_with_aligned_columns() {
+            check_format_output(
+                &["
+x = 1
+#-
+  name    | value
+  --------+------
+  foo     | 1
+  bar     | 22   
+-#
+"],
+                "\
+x = 1
+#-
+  name    | value
+  --------+------
+  foo     | 1
+  bar     | 22   
+-#
+",
+            );
+        }
+
+        #[test]
+        fn multiline_comment_with_aligned_columns_not_preserved() {
+            check_format_output_with_options(
+                &["
+f = ||
+  #-
+    name    | value
+    foo     | 1   
+  -#
+  x
+"],
+                "\
+f = ||
+  #-
+    name | value
+    foo | 1
+  -#
+  x
+",
+                FormatOptions {
+                    preserve_multiline_comment_bodies: false,
+                    ..Default::default()
+                },
+            );
+        }
+
+        #[test]
+        fn single_line_comments_with_aligned_columns() {
+            check_format_output(
+                &["
+#   name    | value
+#   foo     | 1
+x = 1 #    aligned
+y = 2 #    comments
+"],
+                "\
+#   name    | value
+#   foo     | 1
+x = 1 #    aligned
+y = 2 #    comments
+",
+            );
+        }
+
+        #[test]
+        fn attributes() {
+            check_format_output(
+                &["
+#[foo]
+x   =   1   #[bar]
+"],
+                "\
+#[foo]
+x = 1 #[bar]
 ",
             );
         }
@@ -513,6 +595,50 @@ x = 1 + #- abc -# x - -3 * 2
     mod containers {
         use super::*;
 
+        #[test]
+        fn empty_containers() {
+            check_format_output(
+                &[
+                    "\
+a = [ ]
+b = ( )
+c = { }
+",
+                    "\
+a = [
+]
+b = (
+)
+c = {
+}
+",
+                ],
+                "\
+a = []
+b = ()
+c = {}
+",
+            );
+        }
+
+        #[test]
+        fn empty_containers_in_expressions() {
+            check_format_output(
+                &["\
+x = [  ], (  ), {  }
+{ } = { }
+f = ||
+  { }
+"],
+                "\
+x = [], (), {}
+{} = {}
+f = ||
+  {}
+",
+            );
+        }
+
         #[test]
         fn tuple_single_line() {
             check_format_output(
@@ -1005,11 +1131,52 @@ finally
                 },
             );
         }
+
+        #[test]
+        fn try_catch_with_rethrow() {
+            check_format_output(
+                &["
+try
+  foo()
+catch error
+  log error
+  throw   # rethrow
+"],
+                "\
+try
+  foo()
+catch error
+  log error
+  throw # rethrow
+",
+            );
+        }
     }
 
     mod chains {
         use super::*;
 
+        #[test]
+        fn call_with_no_args() {
+            check_format_output(
+                &[
+                    "\
+f(  )
+x = foo.bar( ).baz(  )
+",
+                    "\
+f(
+)
+x = foo.bar(	).baz()
+",
+                ],
+                "\
+f()
+x = foo.bar().baz()
+",
+            );
+        }
+
         #[test]
         fn call_without_parens() {
             check_format_output(
@@ -1270,6 +1437,18 @@ foo
 "],
                 "\
 '{foo 42}' # abc
+",
+            );
+        }
+
+        #[test]
+        fn string_format_options() {
+            check_format_output(
+                &["
+'{x:?} {x:08b} {x:_>+10,.2} {x:+06} {x:X}'
+"],
+                "\
+'{x:?} {x:08b} {x:_>+10,.2} {x:+06} {x:X}'
 ",
             );
         }
@@ -63,6 +63,18 @@ pub fn koto_benchmark(c: &mut Criterion) {
             runner.run();
         })
     });
+    c.bench_function("map_access", |b| {
+        let mut runner = BenchmarkRunner::setup("map_access.koto", &["10000", "quiet"]);
+        b.iter(|| {
+            runner.run();
+        })
+    });
+    c.bench_function("range_loop", |b| {
+        let mut runner = BenchmarkRunner::setup("range_loop.koto", &["1000", "quiet"]);
+        b.iter(|| {
+            runner.run();
+        })
+    });
     c.bench_function("string_formatting", |b| {
         let mut runner = BenchmarkRunner::setup("string_formatting.koto", &["70", "quiet"]);
         b.iter(|| {
@@ -130,6 +130,7 @@ fn compile_and_run(koto: &mut Koto, script_path: &KString) -> Result<()> {
             script: &script,
             script_path: Some(script_path.clone()),
             compiler_settings: Default::default(),
+            strict: false,
         })
         .context("Error while compiling script")?;
     koto.run(chunk).context("Error while running script")?;
@@ -8,6 +8,8 @@ pub enum Error {
     StringError(String),
     #[error("no exported function named '{0}' found")]
     MissingFunction(String),
+    #[error("the exported value '{0}' isn't callable")]
+    NotCallable(String),
     #[error("{error}")]
     CompileError {
         error: String,
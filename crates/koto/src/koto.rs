@@ -1,6 +1,8 @@
 use crate::{Error, Ptr, Result, prelude::*};
 use koto_bytecode::CompilerSettings;
-use koto_runtime::{ModuleImportedCallback, SystemStderr, SystemStdin, SystemStdout};
+use koto_runtime::{
+    BreakpointCallback, ModuleImportedCallback, StepMode, SystemStderr, SystemStdin, SystemStdout,
+};
 use std::time::Duration;
 
 /// The main interface for the Koto language.
@@ -28,6 +30,7 @@ use std::time::Duration;
 pub struct Koto {
     runtime: KotoVm,
     run_tests: bool,
+    optimize: bool,
 }
 
 impl Default for Koto {
@@ -47,6 +50,7 @@ impl Koto {
         Self {
             runtime: KotoVm::with_settings(settings.vm_settings),
             run_tests: settings.run_tests,
+            optimize: settings.optimize,
         }
     }
 
@@ -65,6 +69,29 @@ impl Koto {
         self.runtime.exports_mut()
     }
 
+    /// Returns a clone of the exported value with the given name
+    ///
+    /// `None` is returned if the runtime's exports don't contain a value with the given name.
+    pub fn get_exported(&self, name: &str) -> Option<KValue> {
+        self.exports().get(name)
+    }
+
+    /// Makes a copy of the given map available to scripts as `koto.config`
+    ///
+    /// The copy is deeply frozen (see [KValue::deep_freeze]), so the config and any nested
+    /// containers are read-only from scripts, and attempts to modify them will result in an error.
+    /// The provided map is left unchanged.
+    pub fn set_config(&mut self, config: KMap) -> Result<()> {
+        let config = KValue::Map(config).deep_copy()?;
+        config.deep_freeze();
+
+        if let Some(KValue::Map(koto)) = self.prelude().get("koto") {
+            koto.insert("config", config);
+        }
+
+        Ok(())
+    }
+
     /// Compiles and runs a Koto script, and returns the script's result
     ///
     /// This is a convenience function, equivalent to calling [compile](Self::compile) followed by
@@ -85,18 +112,101 @@ impl Koto {
     /// Compilation arguments are provided via [`CompileArgs`].
     /// `Into<CompileArgs>` is implemented for `&str` for convenience when
     /// default settings are appropriate, e.g. `koto.compile("1 + 1")`.
+    ///
+    /// If [`CompileArgs::strict`] is enabled, then accessing an identifier that isn't defined in the
+    /// script, or available in the runtime's prelude or exports, will result in an error.
     pub fn compile<'a>(&mut self, args: impl Into<CompileArgs<'a>>) -> Result<Ptr<Chunk>> {
+        let args = self.apply_settings(args.into());
+        let mut loader = self.runtime.loader().borrow_mut();
+
+        let result = if args.strict {
+            loader.compile_script_strict(
+                args.script,
+                args.script_path,
+                args.compiler_settings,
+                |name| self.is_defined(name),
+            )
+        } else {
+            loader.compile_script(args.script, args.script_path, args.compiler_settings)
+        };
+
+        result.map_err(Error::from)
+    }
+
+    /// Compiles a Koto script, returning the compiled chunk along with any diagnostics
+    ///
+    /// Potential issues in the script (e.g. unreachable code, or unused bindings) are reported as
+    /// [Diagnostic]s, even when compilation succeeds. If compilation fails then no chunk is
+    /// returned, and the compilation error is included in the diagnostics (see
+    /// [Diagnostic::is_error]).
+    ///
+    /// Identifiers that are available in the runtime's prelude or exports aren't reported as
+    /// undefined. If [`CompileArgs::strict`] is enabled, then no chunk will be returned if an
+    /// undefined identifier is accessed.
+    pub fn compile_with_diagnostics<'a>(
+        &self,
+        args: impl Into<CompileArgs<'a>>,
+    ) -> (Option<Ptr<Chunk>>, Vec<Diagnostic>) {
+        let args = self.apply_settings(args.into());
+        let (chunk, diagnostics) = self
+            .runtime
+            .loader()
+            .borrow()
+            .compile_script_with_diagnostics(
+                args.script,
+                args.script_path,
+                args.compiler_settings,
+                |name| self.is_defined(name),
+            );
+
+        let chunk = chunk.filter(|_| {
+            !(args.strict
+                && diagnostics
+                    .iter()
+                    .any(|diagnostic| matches!(diagnostic.kind, DiagnosticKind::UndefinedId(_))))
+        });
+
+        (chunk, diagnostics)
+    }
+
+    /// Checks a Koto script for potential issues without running it
+    ///
+    /// The script is compiled, with any compilation errors being returned as an error, and then
+    /// a list of [Diagnostic]s is returned describing any issues that were found.
+    ///
+    /// Identifiers that are available in the runtime's prelude or exports aren't reported as
+    /// undefined.
+    ///
+    /// Compilation arguments are provided via [`CompileArgs`].
+    pub fn check<'a>(&self, args: impl Into<CompileArgs<'a>>) -> Result<Vec<Diagnostic>> {
         let args = args.into();
         self.runtime
             .loader()
-            .borrow_mut()
-            .compile_script(args.script, args.script_path, args.compiler_settings)
+            .borrow()
+            .check_script(
+                args.script,
+                args.script_path,
+                args.compiler_settings,
+                |name| self.is_defined(name),
+            )
             .map_err(Error::from)
     }
 
+    // Enables the optimization pass for compiled scripts if requested in the Koto settings
+    fn apply_settings<'a>(&self, mut args: CompileArgs<'a>) -> CompileArgs<'a> {
+        args.compiler_settings.optimize |= self.optimize;
+        args
+    }
+
+    // Returns true if the name is available in the runtime's prelude or exports
+    fn is_defined(&self, name: &str) -> bool {
+        self.prelude().get(name).is_some() || self.exports().get(name).is_some()
+    }
+
     /// Runs a compiled script as a [`Chunk`] and returns the script's result
     ///
-    /// 1. The script is run. If a runtime error is encountered it will be returned as an error.
+    /// 1. The script is run. If a runtime error is encountered it will be returned as an error,
+    ///    otherwise the value of the script's final expression is used as the script's result.
     /// 2. If tests are enabled, the script's exported tests will be run.
     ///    The first test failure will be returned as an error.
     /// 3. If a @main function is exported, it will be called as the last step, with its return
@@ -155,7 +265,8 @@ impl Koto {
         args: impl Into<CallArgs<'a>>,
     ) -> Result<KValue> {
         match self.exports().get(function_name) {
-            Some(f) => self.runtime.call_function(f, args).map_err(From::from),
+            Some(f) if f.is_callable() => self.runtime.call_function(f, args).map_err(From::from),
+            Some(_) => Err(Error::NotCallable(function_name.into())),
             None => Err(Error::MissingFunction(function_name.into())),
         }
     }
@@ -165,6 +276,42 @@ impl Koto {
         self.runtime.value_to_string(&value).map_err(From::from)
     }
 
+    /// Returns a read-only snapshot of the runtime's current state
+    ///
+    /// The values of a script's top-level locals are only available after the script has finished
+    /// running if [KotoSettings::retain_top_level_locals] has been enabled.
+    ///
+    /// See [VmSnapshot] for more information.
+    pub fn inspect(&self) -> VmSnapshot {
+        self.runtime.snapshot()
+    }
+
+    /// Returns a report of the script lines that have been executed
+    ///
+    /// The report will be empty unless [KotoSettings::collect_coverage] has been enabled.
+    pub fn coverage(&self) -> CoverageReport {
+        self.runtime.coverage()
+    }
+
+    /// Adds a breakpoint to the given line of a script
+    ///
+    /// See [`KotoVm::add_breakpoint`] and [`KotoSettings::with_breakpoint_callback`].
+    pub fn add_breakpoint(&self, path: Option<KString>, line: u32) {
+        self.runtime.add_breakpoint(path, line);
+    }
+
+    /// Removes all breakpoints that have been added via [Koto::add_breakpoint]
+    pub fn clear_breakpoints(&self) {
+        self.runtime.clear_breakpoints();
+    }
+
+    /// Sets the runtime's step mode
+    ///
+    /// See [`KotoVm::set_step_mode`].
+    pub fn set_step_mode(&self, step_mode: StepMode) {
+        self.runtime.set_step_mode(step_mode);
+    }
+
     /// Clears the loader's cached modules
     ///
     /// This is useful when a script's dependencies may have changed and need to be recompiled.
@@ -185,6 +332,14 @@ impl Koto {
 pub struct KotoSettings {
     /// Whether or not tests should be run when loading a script
     pub run_tests: bool,
+    /// Whether or not compiled scripts should be optimized
+    ///
+    /// When enabled, [`CompilerSettings::optimize`] is set for all scripts compiled with
+    /// [`Koto::compile`]. The pass can also be enabled for individual scripts via
+    /// [`CompileArgs::optimize`].
+    ///
+    /// Default: `false`
+    pub optimize: bool,
     /// Settings that apply to the runtime
     pub vm_settings: KotoVmSettings,
 }
@@ -232,6 +387,101 @@ impl KotoSettings {
         }
     }
 
+    /// Helper for conveniently defining a maximum number of bytes that can be written to the
+    /// runtime's `stdout` and `stderr`
+    ///
+    /// See [`KotoVmSettings::max_output_size`].
+    #[must_use]
+    pub fn with_max_output_size(self, limit: usize) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                max_output_size: Some(limit),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for running scripts in a sandbox, without access to the filesystem or the
+    /// operating system
+    ///
+    /// See [`KotoVmSettings::sandboxed`].
+    #[must_use]
+    pub fn sandbox(self) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                sandboxed: true,
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for restricting the paths that can be accessed by the `io` core library module
+    ///
+    /// See [`KotoVmSettings::path_permissions`].
+    #[must_use]
+    pub fn with_path_permissions(self, permissions: PathPermissions) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                path_permissions: Some(permissions),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for enabling or disabling the retention of a script's top-level locals
+    ///
+    /// See [`KotoVmSettings::retain_top_level_locals`].
+    #[must_use]
+    pub fn retain_top_level_locals(self, enabled: bool) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                retain_top_level_locals: enabled,
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for enabling or disabling the optimization of compiled scripts
+    ///
+    /// See [`KotoSettings::optimize`].
+    #[must_use]
+    pub fn optimize(self, enabled: bool) -> Self {
+        Self {
+            optimize: enabled,
+            ..self
+        }
+    }
+
+    /// Helper for enabling or disabling the collection of coverage information
+    ///
+    /// See [`KotoVmSettings::collect_coverage`] and [`Koto::coverage`].
+    #[must_use]
+    pub fn collect_coverage(self, enabled: bool) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                collect_coverage: enabled,
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently defining a maximum nesting depth when displaying containers
+    #[must_use]
+    pub fn with_max_display_depth(self, max_depth: usize) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                max_display_depth: Some(max_depth),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
     /// Helper for conveniently defining custom args
     #[must_use]
     pub fn with_args(self, args: impl IntoIterator<Item: Into<String>>) -> Self {
@@ -294,12 +544,27 @@ impl KotoSettings {
             ..self
         }
     }
+
+    /// Convenience function for declaring the breakpoint callback
+    ///
+    /// See [`KotoVmSettings::breakpoint_callback`].
+    #[must_use]
+    pub fn with_breakpoint_callback(self, callback: impl BreakpointCallback + 'static) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                breakpoint_callback: Some(Box::new(callback)),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
 }
 
 impl Default for KotoSettings {
     fn default() -> Self {
         Self {
             run_tests: true,
+            optimize: false,
             vm_settings: KotoVmSettings::default(),
         }
     }
@@ -316,6 +581,10 @@ pub struct CompileArgs<'a> {
     pub script_path: Option<KString>,
     /// Settings used during compilation
     pub compiler_settings: CompilerSettings,
+    /// Whether or not accessing undefined identifiers should result in a compilation error
+    ///
+    /// See [`Koto::compile`].
+    pub strict: bool,
 }
 
 impl<'a> CompileArgs<'a> {
@@ -325,6 +594,7 @@ impl<'a> CompileArgs<'a> {
             script,
             script_path: None,
             compiler_settings: CompilerSettings::default(),
+            strict: false,
         }
     }
 
@@ -345,6 +615,18 @@ impl<'a> CompileArgs<'a> {
         self.compiler_settings.export_top_level_ids = enabled;
         self
     }
+
+    /// Sets the [`CompilerSettings::optimize`] flag, disabled by default.
+    pub fn optimize(mut self, enabled: bool) -> Self {
+        self.compiler_settings.optimize = enabled;
+        self
+    }
+
+    /// Sets the [`CompileArgs::strict`] flag, disabled by default.
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
 }
 
 impl<'a> From<&'a str> for CompileArgs<'a> {
@@ -353,6 +635,7 @@ impl<'a> From<&'a str> for CompileArgs<'a> {
             script,
             script_path: None,
             compiler_settings: Default::default(),
+            strict: false,
         }
     }
 }
@@ -363,6 +646,7 @@ impl<'a> From<&'a String> for CompileArgs<'a> {
             script: script.as_str(),
             script_path: None,
             compiler_settings: Default::default(),
+            strict: false,
         }
     }
 }
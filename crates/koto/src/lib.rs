@@ -31,6 +31,7 @@
 mod error;
 mod koto;
 pub mod prelude;
+mod repl;
 
 pub use koto_bytecode as bytecode;
 pub use koto_parser as parser;
@@ -42,3 +43,4 @@ pub use koto_serde as serde;
 
 pub use crate::error::{Error, Result};
 pub use crate::koto::{CompileArgs, Koto, KotoSettings};
+pub use crate::repl::{Repl, ReplOutput};
@@ -1,5 +1,7 @@
 //! A collection of useful items to make it easier to work with `koto`
 
 pub use crate::{CompileArgs, Koto, KotoSettings};
-pub use koto_bytecode::{Chunk, CompilerSettings, ModuleLoader, ModuleLoaderError};
+pub use koto_bytecode::{
+    Chunk, CompilerSettings, Diagnostic, DiagnosticKind, ModuleLoader, ModuleLoaderError,
+};
 pub use koto_runtime::prelude::*;
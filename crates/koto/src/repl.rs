@@ -0,0 +1,207 @@
+use crate::{Ptr, Result, prelude::*};
+use koto_lexer::{Lexer, Token};
+use koto_parser::Parser;
+
+/// A helper for running an interactive Koto session, e.g. a REPL
+///
+/// Lines of input are passed to [Repl::eval_line]. Incomplete expressions (e.g. an unclosed list,
+/// or an `if` expression that's waiting for its body) are buffered until they're complete, at
+/// which point the accumulated input is compiled and run.
+///
+/// Top-level values are exported after each evaluation so that they persist between inputs.
+///
+/// Example:
+///
+/// ```
+/// use koto::{Repl, ReplOutput};
+///
+/// fn main() -> koto::Result<()> {
+///     let mut repl = Repl::default();
+///
+///     assert!(matches!(repl.eval_line("x = [1,")?, ReplOutput::NeedsMoreInput));
+///     assert!(matches!(repl.eval_line("2]")?, ReplOutput::Value(_)));
+///     assert!(matches!(repl.eval_line("size x")?, ReplOutput::Value(_)));
+///
+///     assert_eq!(repl.history(), ["x = [1,\n2]", "size x"]);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct Repl {
+    koto: Koto,
+    // Lines of input for an expression that's yet to be completed
+    pending_lines: Vec<String>,
+    // Previously evaluated inputs, oldest first
+    history: Vec<String>,
+}
+
+/// The output of [Repl::eval_line]
+pub enum ReplOutput {
+    /// The input is incomplete, and further lines are needed before it can be evaluated
+    NeedsMoreInput,
+    /// The input was evaluated, producing the contained value
+    Value(KValue),
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Repl {
+    /// Creates a new REPL with a default Koto runtime
+    pub fn new() -> Self {
+        Self::with_koto(Koto::default())
+    }
+
+    /// Creates a new REPL that evaluates its input with the given Koto runtime
+    pub fn with_koto(koto: Koto) -> Self {
+        Self {
+            koto,
+            pending_lines: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the REPL's Koto runtime
+    pub fn koto(&self) -> &Koto {
+        &self.koto
+    }
+
+    /// Returns a mutable reference to the REPL's Koto runtime
+    pub fn koto_mut(&mut self) -> &mut Koto {
+        &mut self.koto
+    }
+
+    /// Returns the inputs that have been evaluated by the REPL, oldest first
+    ///
+    /// Inputs that were spread over multiple lines are joined with newlines.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Returns true if the REPL is waiting for further input to complete an expression
+    pub fn is_continuing(&self) -> bool {
+        !self.pending_lines.is_empty()
+    }
+
+    /// Returns the lines of input for an expression that's yet to be completed
+    pub fn pending_lines(&self) -> &[String] {
+        &self.pending_lines
+    }
+
+    /// Discards any pending input for an incomplete expression
+    pub fn clear_pending_input(&mut self) {
+        self.pending_lines.clear();
+    }
+
+    /// Evaluates a line of input
+    ///
+    /// If the line starts an incomplete expression, or continues an incomplete expression from
+    /// a previous line, then [ReplOutput::NeedsMoreInput] is returned.
+    ///
+    /// Indented blocks are evaluated once a non-indented line completes the expression, or when an
+    /// empty line is received. An empty line with no pending input evaluates to `null`.
+    pub fn eval_line(&mut self, line: &str) -> Result<ReplOutput> {
+        match self.push_line(line) {
+            None => Ok(ReplOutput::NeedsMoreInput),
+            Some(input) if input.is_empty() => Ok(ReplOutput::Value(KValue::Null)),
+            Some(input) => {
+                let chunk = self.compile(&input)?;
+                self.koto.run(chunk).map(ReplOutput::Value)
+            }
+        }
+    }
+
+    /// Adds a line of input, returning the accumulated input once it's complete
+    ///
+    /// `None` is returned while further lines are needed to complete the input, see
+    /// [Repl::eval_line] for the rules that are used. An empty string is returned for an empty
+    /// line when there's no pending input.
+    ///
+    /// The returned input can then be compiled with [Repl::compile].
+    pub fn push_line(&mut self, line: &str) -> Option<String> {
+        let line_is_empty = line.trim().is_empty();
+
+        if self.pending_lines.is_empty() {
+            if line_is_empty {
+                return Some(String::new());
+            }
+            if Self::needs_more_input(line) {
+                self.pending_lines.push(line.to_string());
+                return None;
+            }
+            return Some(line.to_string());
+        }
+
+        if !line_is_empty {
+            self.pending_lines.push(line.to_string());
+
+            let line_is_indented = line.starts_with(char::is_whitespace);
+            if line_is_indented || Self::needs_more_input(&self.pending_lines.join("\n")) {
+                return None;
+            }
+        }
+
+        let input = self.pending_lines.join("\n");
+        self.pending_lines.clear();
+        Some(input)
+    }
+
+    /// Compiles complete input, adding it to the REPL's history
+    ///
+    /// Top-level values are exported so that they persist between inputs. The resulting chunk can
+    /// be run with the REPL's runtime, see [Repl::koto_mut].
+    pub fn compile(&mut self, input: &str) -> Result<Ptr<Chunk>> {
+        let result = self
+            .koto
+            .compile(CompileArgs::new(input).export_top_level_ids(true));
+
+        self.history.push(input.to_string());
+
+        result
+    }
+
+    /// Returns true if the source is incomplete and further input is needed
+    ///
+    /// Input is considered to be incomplete when it contains unclosed brackets or strings,
+    /// when it ends with `then` or `else`, or when the parser expects an indented block or
+    /// expression to follow (e.g. after `if x` or `f = |x|`).
+    ///
+    /// Input containing other errors is considered to be complete, allowing the error to be
+    /// reported when the input is compiled.
+    pub fn needs_more_input(source: &str) -> bool {
+        let mut bracket_depth = 0i32;
+        let mut string_depth = 0i32;
+        let mut last_token = None;
+
+        for token in Lexer::new(source) {
+            match token.token {
+                Token::Error => return false,
+                Token::RoundOpen | Token::SquareOpen | Token::CurlyOpen => bracket_depth += 1,
+                Token::RoundClose | Token::SquareClose | Token::CurlyClose => bracket_depth -= 1,
+                Token::StringStart(_) => string_depth += 1,
+                Token::StringEnd => string_depth -= 1,
+                _ => {}
+            }
+
+            if !token.token.is_whitespace_including_newline() {
+                last_token = Some(token.token);
+            }
+        }
+
+        if bracket_depth > 0 || string_depth > 0 {
+            return true;
+        }
+
+        if matches!(last_token, Some(Token::Then | Token::Else)) {
+            return true;
+        }
+
+        match Parser::parse(source) {
+            Ok(_) => false,
+            Err(error) => error.is_indentation_error(),
+        }
+    }
+}
@@ -0,0 +1,972 @@
+//! Tests for the embedding API provided by `Koto`
+
+use koto::prelude::*;
+
+mod run {
+    use super::*;
+
+    #[test]
+    fn compile_and_run_returns_the_final_value() {
+        let mut koto = Koto::default();
+        let result = koto.compile_and_run("1 + 2").unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 3));
+    }
+
+    #[test]
+    fn run_returns_the_final_value() {
+        let mut koto = Koto::default();
+        let chunk = koto.compile("x = 1 + 2\nx * 2").unwrap();
+        let result = koto.run(chunk).unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 6));
+    }
+
+    #[test]
+    fn run_returns_the_result_of_main() {
+        let mut koto = Koto::default();
+        let script = "
+export @main = || 'hello from main'
+1 + 2
+";
+        let result = koto.compile_and_run(script).unwrap();
+        assert!(matches!(result, KValue::Str(s) if s == "hello from main"));
+    }
+}
+
+mod call_function {
+    use super::*;
+
+    #[test]
+    fn call_a_callback_returned_by_a_script() {
+        let mut koto = Koto::default();
+        let script = "
+prefix = 'Hello'
+|name| '{prefix}, {name}!'
+";
+        let callback = koto.compile_and_run(script).unwrap();
+        assert!(callback.is_callable());
+
+        let result = koto
+            .call_function(callback.clone(), &["Koto".into()])
+            .unwrap();
+        assert!(matches!(result, KValue::Str(s) if s == "Hello, Koto!"));
+
+        let result = koto.call_function(callback, &["World".into()]).unwrap();
+        assert!(matches!(result, KValue::Str(s) if s == "Hello, World!"));
+    }
+
+    #[test]
+    fn callback_with_shared_state() {
+        let mut koto = Koto::default();
+        let script = "
+counter = [0]
+|n|
+  counter[0] += n
+  counter[0]
+";
+        let callback = koto.compile_and_run(script).unwrap();
+
+        let result = koto.call_function(callback.clone(), &[2.into()]).unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 2));
+
+        let result = koto.call_function(callback, &[3.into()]).unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 5));
+    }
+}
+
+mod exports {
+    use super::*;
+
+    #[test]
+    fn read_exported_values() {
+        let mut koto = Koto::default();
+        let script = "
+export
+  name: 'test'
+  square: |n| n * n
+";
+        koto.compile_and_run(script).unwrap();
+
+        assert_eq!(koto.exports().len(), 2);
+        assert!(matches!(koto.get_exported("name"), Some(KValue::Str(s)) if s == "test"));
+        assert!(koto.get_exported("missing").is_none());
+
+        let square = koto.get_exported("square").unwrap();
+        let result = koto.call_function(square, &[KValue::from(9)]).unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 81));
+    }
+
+    #[test]
+    fn call_exported_function() {
+        let mut koto = Koto::default();
+        let script = "
+export
+  add: |a, b| a + b
+  x: 42
+";
+        koto.compile_and_run(script).unwrap();
+
+        let result = koto
+            .call_exported_function("add", &[2.into(), 3.into()])
+            .unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 5));
+
+        let error = koto.call_exported_function("missing", &[]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no exported function named 'missing' found"
+        );
+
+        let error = koto.call_exported_function("x", &[]).unwrap_err();
+        assert_eq!(error.to_string(), "the exported value 'x' isn't callable");
+    }
+}
+
+mod inspect {
+    use super::*;
+
+    #[test]
+    fn locals_and_exports_after_running() {
+        let mut koto = Koto::with_settings(KotoSettings::default().retain_top_level_locals(true));
+        let script = "
+x = 1 + 2
+y = 'hello'
+export z = x * 2
+";
+        koto.compile_and_run(script).unwrap();
+
+        let snapshot = koto.inspect();
+        assert!(snapshot.call_stack.is_empty());
+
+        let locals = snapshot
+            .locals
+            .iter()
+            .map(|(name, value)| (name.as_str(), koto.value_to_string(value.clone()).unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locals,
+            [("x", "3".into()), ("y", "hello".into()), ("z", "6".into())]
+        );
+
+        assert_eq!(snapshot.exports.len(), 1);
+        assert!(matches!(snapshot.exports.get("z"), Some(KValue::Number(n)) if *n == 6));
+    }
+
+    #[test]
+    fn locals_arent_retained_by_default() {
+        let mut koto = Koto::default();
+        koto.compile_and_run("x = 42").unwrap();
+        assert!(koto.inspect().locals.is_empty());
+    }
+
+    #[test]
+    fn snapshot_during_a_run() {
+        let mut koto = Koto::default();
+        koto.prelude().add_fn("inspect", |ctx| {
+            let snapshot = ctx.vm.snapshot();
+            let frames = snapshot
+                .call_stack
+                .iter()
+                .map(|frame| {
+                    let span = frame.span.unwrap();
+                    KValue::from(span.start.line as i64)
+                })
+                .collect::<Vec<_>>();
+            let locals = snapshot
+                .locals
+                .into_iter()
+                .map(|(name, _)| KValue::from(name))
+                .collect::<Vec<_>>();
+            Ok(KTuple::from(vec![
+                KTuple::from(frames).into(),
+                KTuple::from(locals).into(),
+            ])
+            .into())
+        });
+
+        let script = "
+a = 1
+f = ||
+  inspect()
+b = f()
+";
+        let result = koto.compile_and_run(script).unwrap();
+        assert_eq!(
+            koto.value_to_string(result).unwrap(),
+            "((4, 3), ('a', 'f', 'b'))"
+        );
+    }
+}
+
+mod breakpoints {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn breakpoint_callback_is_called_with_locals() {
+        let paused = Arc::new(Mutex::new(Vec::new()));
+
+        let mut koto = Koto::with_settings(KotoSettings::default().with_breakpoint_callback({
+            let paused = paused.clone();
+            move |vm: &mut KotoVm, span| {
+                let locals = vm
+                    .snapshot()
+                    .locals
+                    .iter()
+                    .map(|(name, value)| format!("{name}: {}", vm.value_to_string(value).unwrap()))
+                    .collect::<Vec<_>>();
+                paused.lock().unwrap().push((span.start.line, locals));
+                StepMode::Continue
+            }
+        }));
+        koto.add_breakpoint(None, 2);
+
+        let script = "
+a = 'hello'
+b = '{a}, world'
+";
+        koto.compile_and_run(script).unwrap();
+
+        assert_eq!(
+            *paused.lock().unwrap(),
+            [(2, vec!["a: hello".to_string(), "b: null".to_string()])]
+        );
+    }
+}
+
+mod coverage {
+    use super::*;
+
+    #[test]
+    fn untaken_branch_is_reported_as_uncovered() {
+        let mut koto = Koto::with_settings(KotoSettings::default().collect_coverage(true));
+
+        let script = "\
+x = 1
+if x > 10
+  y = 'big'
+else
+  y = 'small'
+y.to_uppercase()
+";
+        koto.compile_and_run(script).unwrap();
+
+        let report = koto.coverage();
+        assert_eq!(report.scripts.len(), 1);
+        let coverage = &report.scripts[0];
+        assert_eq!(coverage.uncovered_lines, [2]);
+        for line in [0, 1, 4, 5] {
+            assert!(
+                coverage.covered_lines.contains(&line),
+                "expected line {line} to be covered: {coverage:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn uncalled_function_is_reported_as_uncovered() {
+        let mut koto = Koto::with_settings(KotoSettings::default().collect_coverage(true));
+
+        let script = "\
+f = |n|
+  n * 2
+g = |n|
+  n + 1
+g 42
+";
+        koto.compile_and_run(script).unwrap();
+
+        let coverage = &koto.coverage().scripts[0];
+        assert!(coverage.uncovered_lines.contains(&1));
+        assert!(coverage.covered_lines.contains(&3));
+    }
+
+    #[test]
+    fn coverage_is_empty_when_disabled() {
+        let mut koto = Koto::default();
+        koto.compile_and_run("1 + 1").unwrap();
+        assert!(koto.coverage().scripts.is_empty());
+    }
+}
+
+mod check {
+    use super::*;
+
+    #[test]
+    fn undefined_id_produces_a_diagnostic() {
+        let koto = Koto::default();
+
+        let script = "\
+x = 1
+print x + y
+";
+        let diagnostics = koto.check(script).unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UndefinedId("y".into()));
+        assert_eq!(diagnostics[0].span.start.line, 1);
+    }
+
+    #[test]
+    fn clean_script_produces_no_diagnostics() {
+        let mut koto = Koto::default();
+        koto.exports_mut().insert("answer", 42);
+
+        let script = "\
+x = [1, 2, 3]
+print size x
+io.print '{answer}'
+";
+        assert!(koto.check(script).unwrap().is_empty());
+    }
+
+    #[test]
+    fn script_is_not_run() {
+        let koto = Koto::default();
+
+        koto.check("export x = 42").unwrap();
+
+        assert!(koto.get_exported("x").is_none());
+    }
+
+    #[test]
+    fn compilation_errors_are_returned() {
+        let koto = Koto::default();
+
+        assert!(koto.check("x = (1 + ").is_err());
+    }
+
+    mod strict {
+        use super::*;
+
+        fn compile_strict(koto: &mut Koto, script: &str) -> koto::Result<koto::Ptr<Chunk>> {
+            koto.compile(CompileArgs::new(script).strict(true))
+        }
+
+        #[test]
+        fn undefined_id_is_an_error() {
+            let mut koto = Koto::default();
+
+            let error = compile_strict(&mut koto, "print undefind_var").unwrap_err();
+
+            assert!(
+                error.to_string().contains("'undefind_var' is not defined"),
+                "unexpected error: {error}"
+            );
+        }
+
+        #[test]
+        fn undefined_id_compiles_without_strict_mode() {
+            let mut koto = Koto::default();
+
+            assert!(koto.compile("print undefind_var").is_ok());
+        }
+
+        #[test]
+        fn prelude_and_exported_ids_are_allowed() {
+            let mut koto = Koto::default();
+            koto.exports_mut().insert("answer", 42);
+
+            let script = "\
+print size [1, 2, 3]
+x = string.to_uppercase 'hello'
+answer + 1
+";
+            assert!(compile_strict(&mut koto, script).is_ok());
+        }
+
+        #[test]
+        fn forward_references_to_exported_ids_are_allowed() {
+            let mut koto = Koto::default();
+
+            let script = "\
+is_even = |n| if n == 0 then true else is_odd n - 1
+export is_odd = |n| if n == 0 then false else is_even n - 1
+is_even 10
+";
+            let chunk = compile_strict(&mut koto, script).unwrap();
+            assert!(matches!(koto.run(chunk), Ok(KValue::Bool(true))));
+        }
+
+        #[test]
+        fn forward_references_to_local_ids_are_errors() {
+            let mut koto = Koto::default();
+
+            let script = "\
+f = || g()
+g = || 42
+f()
+";
+            let error = compile_strict(&mut koto, script).unwrap_err();
+
+            assert!(
+                error.to_string().contains("'g' is not defined"),
+                "unexpected error: {error}"
+            );
+        }
+    }
+}
+
+mod compile_with_diagnostics {
+    use super::*;
+
+    #[test]
+    fn script_with_warnings_produces_a_runnable_chunk() {
+        let mut koto = Koto::default();
+
+        let script = "\
+f = |n|
+  unused = n * 2
+  return n + 1
+  n
+{a: 1, a: 2}
+f 41
+";
+        let (chunk, diagnostics) = koto.compile_with_diagnostics(script);
+
+        let kinds = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.kind.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                DiagnosticKind::UnusedBinding("unused".into()),
+                DiagnosticKind::UnreachableCode,
+                DiagnosticKind::DuplicateMapKey("a".into()),
+            ]
+        );
+        assert!(!diagnostics.iter().any(Diagnostic::is_error));
+
+        let result = koto.run(chunk.expect("Expected a compiled chunk")).unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 42));
+    }
+
+    #[test]
+    fn compilation_error_is_returned_as_a_diagnostic() {
+        let koto = Koto::default();
+
+        let (chunk, diagnostics) = koto.compile_with_diagnostics("x = (1 + ");
+
+        assert!(chunk.is_none());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].is_error());
+    }
+
+    #[test]
+    fn undefined_id_in_strict_mode_produces_no_chunk() {
+        let koto = Koto::default();
+
+        let (chunk, diagnostics) =
+            koto.compile_with_diagnostics(CompileArgs::new("print undefined_var").strict(true));
+
+        assert!(chunk.is_none());
+        assert_eq!(
+            diagnostics[0].kind,
+            DiagnosticKind::UndefinedId("undefined_var".into())
+        );
+    }
+}
+
+mod optimize {
+    use super::*;
+
+    fn compiled_size(settings: KotoSettings) -> usize {
+        let script = "
+x = 1
+x = x
+x
+";
+        let mut koto = Koto::with_settings(settings);
+        let chunk = koto.compile(script).unwrap();
+        let result = koto.run(chunk.clone()).unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 1));
+        chunk.bytes.len()
+    }
+
+    #[test]
+    fn optimize_setting_is_passed_to_the_compiler() {
+        let unoptimized = compiled_size(KotoSettings::default());
+        let optimized = compiled_size(KotoSettings::default().optimize(true));
+
+        assert!(optimized < unoptimized);
+    }
+}
+
+mod output_limit {
+    use super::*;
+    use koto_test_utils::OutputCapture;
+
+    #[test]
+    fn printing_in_an_infinite_loop_fails() {
+        let output = OutputCapture::default();
+        let mut koto = Koto::with_settings(
+            KotoSettings::default()
+                .with_stdout(output.clone())
+                .with_max_output_size(1000),
+        );
+
+        let script = "
+while true
+  print 'hello'
+";
+        assert!(koto.compile_and_run(script).is_err());
+        assert!(output.captured_output().len() <= 1000);
+    }
+
+    #[test]
+    fn output_within_the_limit_succeeds() {
+        let output = OutputCapture::default();
+        let mut koto = Koto::with_settings(
+            KotoSettings::default()
+                .with_stdout(output.clone())
+                .with_max_output_size(6),
+        );
+
+        assert!(koto.compile_and_run("print 'hello'").is_ok());
+        assert!(koto.compile_and_run("print 'hello'").is_err());
+        assert_eq!(*output.captured_output(), "hello\n");
+    }
+}
+
+mod sandbox {
+    use super::*;
+
+    #[test]
+    fn filesystem_access_fails() {
+        let mut koto = Koto::with_settings(KotoSettings::default().sandbox());
+
+        let error = koto
+            .compile_and_run("io.read_to_string 'Cargo.toml'")
+            .unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("'io.read_to_string' is unavailable in a sandboxed runtime"),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn os_access_fails() {
+        let mut koto = Koto::with_settings(KotoSettings::default().sandbox());
+
+        assert!(koto.compile_and_run("os.env 'HOME'").is_err());
+        assert!(koto.compile_and_run("os.command 'ls'").is_err());
+    }
+
+    #[test]
+    fn importing_from_disk_fails() {
+        let mut koto = Koto::with_settings(KotoSettings::default().sandbox());
+
+        let error = koto
+            .compile_and_run(CompileArgs::new("import foo").script_path("tests/test.koto"))
+            .unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("'foo' can't be imported from disk in a sandboxed runtime"),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn pure_computation_succeeds() {
+        let mut koto = Koto::with_settings(KotoSettings::default().sandbox());
+
+        let script = "
+from string import to_uppercase
+x = (1..=10)
+  .keep |n| n % 2 == 0
+  .each |n| n * n
+  .sum()
+'{to_uppercase 'sum'}: {x}'
+";
+        match koto.compile_and_run(script).unwrap() {
+            KValue::Str(result) => assert_eq!(result.as_str(), "SUM: 220"),
+            other => panic!(
+                "unexpected result: {}",
+                koto.value_to_string(other).unwrap()
+            ),
+        }
+    }
+
+    #[test]
+    fn filesystem_access_succeeds_without_sandbox() {
+        let mut koto = Koto::default();
+
+        assert!(koto.compile_and_run("io.exists 'Cargo.toml'").is_ok());
+    }
+}
+
+mod path_permissions {
+    use super::*;
+    use std::{fs, path::Path};
+
+    // Creates a temporary directory containing an 'allowed' directory with a file inside it,
+    // along with a 'secret.txt' file outside of the allowed directory
+    fn make_test_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("allowed")).unwrap();
+        fs::write(dir.path().join("allowed").join("data.txt"), "hello").unwrap();
+        fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        dir
+    }
+
+    fn make_koto(allowed: &Path) -> Koto {
+        Koto::with_settings(
+            KotoSettings::default().with_path_permissions(
+                PathPermissions::default()
+                    .allow_read(allowed)
+                    .allow_write(allowed),
+            ),
+        )
+    }
+
+    fn run_with_path(koto: &mut Koto, script: &str, path: &Path) -> koto::Result<KValue> {
+        koto.exports_mut()
+            .insert("path", path.to_string_lossy().as_ref());
+        koto.compile_and_run(script)
+    }
+
+    #[test]
+    fn reading_an_allowed_file() {
+        let dir = make_test_dir();
+        let mut koto = make_koto(&dir.path().join("allowed"));
+
+        let path = dir.path().join("allowed").join("data.txt");
+        match run_with_path(&mut koto, "io.read_to_string path", &path).unwrap() {
+            KValue::Str(contents) => assert_eq!(contents.as_str(), "hello"),
+            other => panic!(
+                "unexpected result: {}",
+                koto.value_to_string(other).unwrap()
+            ),
+        }
+    }
+
+    #[test]
+    fn writing_an_allowed_file() {
+        let dir = make_test_dir();
+        let mut koto = make_koto(&dir.path().join("allowed"));
+
+        let path = dir.path().join("allowed").join("new.txt");
+        let script = "
+f = io.create path
+f.write 'written'
+f.flush()
+";
+        run_with_path(&mut koto, script, &path).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "written");
+    }
+
+    #[test]
+    fn reading_an_outside_path_is_denied() {
+        let dir = make_test_dir();
+        let mut koto = make_koto(&dir.path().join("allowed"));
+
+        let path = dir.path().join("secret.txt");
+        let error = run_with_path(&mut koto, "io.read_to_string path", &path).unwrap_err();
+        assert!(
+            error.to_string().contains("isn't permitted"),
+            "unexpected error: {error}"
+        );
+
+        assert!(run_with_path(&mut koto, "io.open path", &path).is_err());
+        assert!(run_with_path(&mut koto, "io.exists path", &path).is_err());
+    }
+
+    #[test]
+    fn writing_an_outside_path_is_denied() {
+        let dir = make_test_dir();
+        let mut koto = make_koto(&dir.path().join("allowed"));
+
+        let path = dir.path().join("new.txt");
+        assert!(run_with_path(&mut koto, "io.create path", &path).is_err());
+        assert!(!path.exists());
+
+        let path = dir.path().join("secret.txt");
+        assert!(run_with_path(&mut koto, "io.remove_file path", &path).is_err());
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn parent_dir_traversal_is_denied() {
+        let dir = make_test_dir();
+        let mut koto = make_koto(&dir.path().join("allowed"));
+
+        let path = dir.path().join("allowed").join("..").join("secret.txt");
+        assert!(run_with_path(&mut koto, "io.read_to_string path", &path).is_err());
+
+        let path = dir.path().join("allowed").join("..").join("new.txt");
+        assert!(run_with_path(&mut koto, "io.create path", &path).is_err());
+        assert!(!dir.path().join("new.txt").exists());
+    }
+
+    #[test]
+    fn read_permission_does_not_allow_writing() {
+        let dir = make_test_dir();
+        let allowed = dir.path().join("allowed");
+        let mut koto = Koto::with_settings(
+            KotoSettings::default()
+                .with_path_permissions(PathPermissions::default().allow_read(&allowed)),
+        );
+
+        let path = allowed.join("data.txt");
+        assert!(run_with_path(&mut koto, "io.read_to_string path", &path).is_ok());
+        assert!(run_with_path(&mut koto, "io.remove_file path", &path).is_err());
+        assert!(path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn removing_a_symlink_leaves_the_target_unchanged() {
+        let dir = make_test_dir();
+        let allowed = dir.path().join("allowed");
+        let mut koto = make_koto(&allowed);
+
+        let link = allowed.join("link.txt");
+        std::os::unix::fs::symlink(allowed.join("data.txt"), &link).unwrap();
+
+        run_with_path(&mut koto, "io.remove_file path", &link).unwrap();
+        assert!(!link.exists());
+        assert!(allowed.join("data.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_to_outside_paths() {
+        let dir = make_test_dir();
+        let allowed = dir.path().join("allowed");
+        let mut koto = make_koto(&allowed);
+
+        let link = allowed.join("secret_link.txt");
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), &link).unwrap();
+
+        // Reading or writing via the link would access the target, which isn't permitted
+        assert!(run_with_path(&mut koto, "io.read_to_string path", &link).is_err());
+        assert!(run_with_path(&mut koto, "io.create path", &link).is_err());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("secret.txt")).unwrap(),
+            "secret"
+        );
+
+        // Removing the link only affects the allowed directory
+        run_with_path(&mut koto, "io.remove_file path", &link).unwrap();
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(dir.path().join("secret.txt").exists());
+    }
+}
+
+mod repl {
+    use super::*;
+    use koto::{Repl, ReplOutput};
+
+    fn eval_line(repl: &mut Repl, line: &str) -> Option<KValue> {
+        match repl.eval_line(line).unwrap() {
+            ReplOutput::NeedsMoreInput => None,
+            ReplOutput::Value(value) => Some(value),
+        }
+    }
+
+    #[test]
+    fn incomplete_input_needs_more_input() {
+        for source in [
+            "[1,",
+            "if true then",
+            "if true",
+            "x = (1 +",
+            "f = |x|",
+            "{a: 1,",
+            "'{1 + ",
+        ] {
+            assert!(Repl::needs_more_input(source), "{source}");
+        }
+    }
+
+    #[test]
+    fn complete_input_doesnt_need_more_input() {
+        for source in [
+            "1 + 2",
+            "[1, 2]",
+            "if true then 1 else 2",
+            "x = 'hello'",
+            "1 )",
+        ] {
+            assert!(!Repl::needs_more_input(source), "{source}");
+        }
+    }
+
+    #[test]
+    fn values_persist_between_inputs() {
+        let mut repl = Repl::default();
+
+        eval_line(&mut repl, "x = 40").unwrap();
+        let result = eval_line(&mut repl, "x + 2").unwrap();
+
+        assert!(matches!(result, KValue::Number(n) if n == 42));
+    }
+
+    #[test]
+    fn multiline_input_is_evaluated_once_complete() {
+        let mut repl = Repl::default();
+
+        assert!(eval_line(&mut repl, "x = [1,").is_none());
+        assert!(repl.is_continuing());
+        let result = eval_line(&mut repl, "2, 3]").unwrap();
+        assert!(matches!(result, KValue::List(l) if l.len() == 3));
+        assert!(!repl.is_continuing());
+
+        assert!(eval_line(&mut repl, "f = |n|").is_none());
+        assert!(eval_line(&mut repl, "  n * size x").is_none());
+        assert!(eval_line(&mut repl, "").is_some());
+        let result = eval_line(&mut repl, "f 2").unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 6));
+
+        assert_eq!(
+            repl.history(),
+            ["x = [1,\n2, 3]", "f = |n|\n  n * size x", "f 2"]
+        );
+    }
+
+    #[test]
+    fn push_line_returns_complete_input() {
+        let mut repl = Repl::default();
+
+        assert_eq!(repl.push_line("if true"), None);
+        assert_eq!(repl.push_line("  1"), None);
+        assert_eq!(repl.pending_lines(), ["if true", "  1"]);
+        assert_eq!(repl.push_line("else"), None);
+        assert_eq!(repl.push_line("  2"), None);
+        assert_eq!(
+            repl.push_line("").as_deref(),
+            Some("if true\n  1\nelse\n  2")
+        );
+        assert!(!repl.is_continuing());
+
+        assert_eq!(repl.push_line("").as_deref(), Some(""));
+        assert_eq!(repl.push_line("1 + 2").as_deref(), Some("1 + 2"));
+    }
+
+    #[test]
+    fn compiled_input_is_added_to_the_history() {
+        let mut repl = Repl::default();
+
+        let input = repl.push_line("x = 42").unwrap();
+        let chunk = repl.compile(&input).unwrap();
+        repl.koto_mut().run(chunk).unwrap();
+
+        let result = eval_line(&mut repl, "x").unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 42));
+        assert_eq!(repl.history(), ["x = 42", "x"]);
+    }
+
+    #[test]
+    fn errors_are_returned_and_pending_input_is_cleared() {
+        let mut repl = Repl::default();
+
+        assert!(eval_line(&mut repl, "x = [1,").is_none());
+        assert!(repl.eval_line("2 )").is_err());
+        assert!(!repl.is_continuing());
+
+        let result = eval_line(&mut repl, "1 + 2").unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 3));
+    }
+}
+
+mod config {
+    use super::*;
+
+    fn make_config() -> KMap {
+        let server = KMap::new();
+        server.insert("host", "localhost");
+        server.insert("port", 8080);
+
+        let items = KList::from_slice(&[1.into(), KList::from_slice(&[2.into()]).into()]);
+
+        let config = KMap::new();
+        config.insert("name", "test");
+        config.insert("server", server);
+        config.insert("items", items);
+        config
+    }
+
+    fn check_result(koto: &mut Koto, script: &str, expected: &str) {
+        let result = koto.compile_and_run(script).unwrap();
+        assert_eq!(koto.value_to_string(result).unwrap(), expected);
+    }
+
+    #[test]
+    fn read_nested_values() {
+        let mut koto = Koto::default();
+        koto.set_config(make_config()).unwrap();
+
+        check_result(
+            &mut koto,
+            "'{koto.config.name}: {koto.config.server.host}:{koto.config.server.port}'",
+            "test: localhost:8080",
+        );
+        check_result(&mut koto, "koto.config.items[1][0]", "2");
+    }
+
+    #[test]
+    fn map_functions_are_available() {
+        let mut koto = Koto::default();
+        koto.set_config(make_config()).unwrap();
+
+        check_result(
+            &mut koto,
+            "koto.config.keys().to_tuple()",
+            "('name', 'server', 'items')",
+        );
+        check_result(&mut koto, "size koto.config", "3");
+        check_result(
+            &mut koto,
+            "
+result = []
+for key, value in koto.config.server
+  result.push '{key}={value}'
+result
+",
+            "['host=localhost', 'port=8080']",
+        );
+    }
+
+    #[test]
+    fn assignment_is_an_error() {
+        let mut koto = Koto::default();
+        koto.set_config(make_config()).unwrap();
+
+        assert!(koto.compile_and_run("koto.config.name = 'foo'").is_err());
+        assert!(koto.compile_and_run("koto.config.server.port = 0").is_err());
+
+        // The config should be unchanged
+        check_result(&mut koto, "koto.config.server.port", "8080");
+    }
+
+    #[test]
+    fn nested_lists_are_frozen() {
+        let mut koto = Koto::default();
+        koto.set_config(make_config()).unwrap();
+
+        assert!(koto.compile_and_run("koto.config.items.push 3").is_err());
+        assert!(
+            koto.compile_and_run("koto.config.items[1][0] = 99")
+                .is_err()
+        );
+
+        check_result(&mut koto, "koto.config.items", "[1, [2]]");
+    }
+
+    #[test]
+    fn provided_map_is_unchanged() {
+        let config = make_config();
+
+        let mut koto = Koto::default();
+        koto.set_config(config.clone()).unwrap();
+
+        assert!(!config.is_frozen());
+        config.insert("name", "changed");
+        check_result(&mut koto, "koto.config.name", "test");
+    }
+}
@@ -4,12 +4,18 @@ use std::{
     path::{Path, PathBuf},
 };
 
-fn run_script(script: &str, script_path: PathBuf, expected_module_paths: &[PathBuf]) {
+fn run_script(
+    script: &str,
+    script_path: PathBuf,
+    expected_module_paths: &[PathBuf],
+    optimize: bool,
+) {
     let loaded_module_paths = PtrMut::from(vec![]);
 
     let mut koto = Koto::with_settings(
         KotoSettings {
             run_tests: true,
+            optimize,
             vm_settings: KotoVmSettings {
                 run_import_tests: true,
                 ..Default::default()
@@ -21,11 +27,7 @@ fn run_script(script: &str, script_path: PathBuf, expected_module_paths: &[PathB
         }),
     );
 
-    if let Err(error) = koto.compile_and_run(CompileArgs {
-        script,
-        script_path: Some(script_path.into()),
-        compiler_settings: Default::default(),
-    }) {
+    if let Err(error) = koto.compile_and_run(CompileArgs::new(script).script_path(script_path)) {
         panic!("{error}");
     }
 
@@ -75,7 +77,9 @@ fn load_and_run_script(script_file_name: &str, imported_modules: &[&str]) {
         })
         .collect::<Vec<_>>();
 
-    run_script(&script, script_path, &expected_module_paths);
+    // Run the script with and without bytecode optimizations
+    run_script(&script, script_path.clone(), &expected_module_paths, false);
+    run_script(&script, script_path, &expected_module_paths, true);
 }
 
 macro_rules! koto_test {
@@ -31,6 +31,7 @@ fn run_repl_mode_test(inputs_and_expected_outputs: &[(&str, &str)]) {
                 export_top_level_ids: true,
                 ..Default::default()
             },
+            strict: false,
         }) {
             Ok(chunk) => {
                 chunks.push((input, chunk.clone()));
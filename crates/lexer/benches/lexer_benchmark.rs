@@ -0,0 +1,30 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use koto_lexer::Lexer;
+use std::hint::black_box;
+
+// A large ASCII source made up of repeated copies of the Koto benchmark scripts
+fn make_source() -> String {
+    let scripts = [
+        include_str!("../../../koto/benches/fannkuch.koto"),
+        include_str!("../../../koto/benches/n_body.koto"),
+        include_str!("../../../koto/benches/spectral_norm.koto"),
+        include_str!("../../../koto/benches/string_formatting.koto"),
+    ];
+
+    scripts.repeat(50).join("\n")
+}
+
+pub fn lexer_benchmark(c: &mut Criterion) {
+    let source = make_source();
+
+    c.bench_function("lex_ascii_source", |b| {
+        b.iter(|| {
+            for token in Lexer::new(black_box(&source)) {
+                black_box(token);
+            }
+        })
+    });
+}
+
+criterion_group!(benches, lexer_benchmark);
+criterion_main!(benches);
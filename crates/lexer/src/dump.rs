@@ -0,0 +1,67 @@
+use crate::{LexedToken, Lexer};
+use std::fmt::Write;
+
+/// Produces a listing of the tokens in the source, one token per line
+///
+/// Each line contains the token, its slice of the source, its span, and its line's indentation.
+///
+/// e.g. `Id "x" 0:0-0:1 indent: 0`
+///
+/// This is intended as an aid when debugging lexing issues.
+pub fn dump_tokens(source: &str) -> String {
+    let mut result = String::new();
+
+    for token in Lexer::new(source) {
+        dump_token(&mut result, &token, source);
+    }
+
+    result
+}
+
+fn dump_token(output: &mut String, token: &LexedToken, source: &str) {
+    let start = token.span.start;
+    let end = token.span.end;
+    // Writing to a String is infallible
+    let _ = writeln!(
+        output,
+        "{:?} {:?} {}:{}-{}:{} indent: {}",
+        token.token,
+        token.slice(source),
+        start.line,
+        start.column,
+        end.line,
+        end.column,
+        token.indent
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dump_of_a_small_snippet() {
+        let source = "\
+x = 1
+if x
+  'hi'
+";
+        let dump = dump_tokens(source);
+
+        for expected in [
+            r#"Id "x" 0:0-0:1 indent: 0"#,
+            r#"Whitespace " " 0:1-0:2 indent: 0"#,
+            r#"Assign "=" 0:2-0:3 indent: 0"#,
+            r#"Number "1" 0:4-0:5 indent: 0"#,
+            r#"NewLine "\n" 0:5-1:0 indent: 0"#,
+            r#"If "if" 1:0-1:2 indent: 0"#,
+            r#"StringStart(Normal(Single)) "'" 2:2-2:3 indent: 2"#,
+            r#"StringLiteral "hi" 2:3-2:5 indent: 2"#,
+        ] {
+            assert!(
+                dump.lines().any(|line| line == expected),
+                "Missing '{expected}' in dump:\n{dump}"
+            );
+        }
+    }
+}
@@ -1,5 +1,12 @@
 use crate::{Position, Span};
-use std::{collections::VecDeque, iter::Peekable, ops::Range, str::Chars};
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead},
+    iter::Peekable,
+    ops::Range,
+    rc::Rc,
+    str::Chars,
+};
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthChar;
 use unicode_xid::UnicodeXID;
@@ -13,6 +20,7 @@ pub enum Token {
     NewLine,
     CommentSingle,
     CommentMulti,
+    Attribute,
     Number,
     Id,
 
@@ -20,6 +28,11 @@ pub enum Token {
     StringEnd,
     StringLiteral,
 
+    /// A keyword registered with [KotoLexer::with_custom_keywords]
+    ///
+    /// The value is the keyword's index in the list of custom keywords.
+    CustomKeyword(u16),
+
     // Symbols
     At,
     Colon,
@@ -47,6 +60,12 @@ pub enum Token {
     Remainder,
     Power,
 
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
+
     Assign,
     AddAssign,
     SubtractAssign,
@@ -107,7 +126,7 @@ impl Token {
     /// Returns true if the token should be counted as whitespace
     pub fn is_whitespace(&self) -> bool {
         use Token::*;
-        matches!(self, Whitespace | CommentMulti | CommentSingle)
+        matches!(self, Whitespace | CommentMulti | CommentSingle | Attribute)
     }
 
     /// Returns true if the token should be counted as whitespace, including newlines
@@ -201,21 +220,106 @@ struct TokenLexer<'a> {
     indent: usize,
     // A stack of string modes, allowing for nested mode changes while parsing strings
     string_mode_stack: Vec<StringMode>,
+    // The number of columns that a tab character advances by
+    tab_width: usize,
+    // Additional keywords that are emitted as CustomKeyword tokens
+    //
+    // The keywords are shared so that the lexer's state remains cheap to clone,
+    // e.g. `ReaderLexer` clones its state for each line of input.
+    custom_keywords: Rc<[String]>,
 }
 
+// The state of a TokenLexer, separated from its source
+//
+// This allows the source to be modified in between tokens, see ReaderLexer.
+#[derive(Clone)]
+struct TokenLexerState {
+    current_byte: usize,
+    previous_byte: usize,
+    previous_token: Option<Token>,
+    span: Span,
+    indent: usize,
+    string_mode_stack: Vec<StringMode>,
+    tab_width: usize,
+    custom_keywords: Rc<[String]>,
+}
+
+// The UTF-8 byte order mark, skipped if found at the start of the source
+const BYTE_ORDER_MARK: char = '\u{FEFF}';
+
 impl<'a> TokenLexer<'a> {
     fn new(source: &'a str) -> Self {
+        // Skip over a leading byte order mark, positions in the source are unaffected
+        let start_byte = if source.starts_with(BYTE_ORDER_MARK) {
+            BYTE_ORDER_MARK.len_utf8()
+        } else {
+            0
+        };
+
         Self {
             source,
-            previous_byte: 0,
-            current_byte: 0,
+            previous_byte: start_byte,
+            current_byte: start_byte,
             indent: 0,
             previous_token: None,
             span: Span::default(),
             string_mode_stack: vec![],
+            tab_width: 1,
+            custom_keywords: Rc::new([]),
         }
     }
 
+    // Initializes a lexer with a state that was previously taken from a lexer with `into_state`
+    fn with_state(source: &'a str, state: TokenLexerState) -> Self {
+        Self {
+            source,
+            previous_byte: state.previous_byte,
+            current_byte: state.current_byte,
+            indent: state.indent,
+            previous_token: state.previous_token,
+            span: state.span,
+            string_mode_stack: state.string_mode_stack,
+            tab_width: state.tab_width,
+            custom_keywords: state.custom_keywords,
+        }
+    }
+
+    // Consumes the lexer, returning its state without the source
+    fn into_state(self) -> TokenLexerState {
+        TokenLexerState {
+            previous_byte: self.previous_byte,
+            current_byte: self.current_byte,
+            indent: self.indent,
+            previous_token: self.previous_token,
+            span: self.span,
+            string_mode_stack: self.string_mode_stack,
+            tab_width: self.tab_width,
+            custom_keywords: self.custom_keywords,
+        }
+    }
+
+    // Resets the lexer to the start of its source, keeping its configuration
+    fn reset(&mut self) {
+        *self = Self {
+            tab_width: self.tab_width,
+            custom_keywords: self.custom_keywords.clone(),
+            ..Self::new(self.source)
+        };
+    }
+
+    // Resumes lexing after a line break that was produced when lexing the same source
+    //
+    // The line break must be outside of any strings, so that the lexer's string mode stack is
+    // empty when lexing resumes.
+    fn resume_after_line_break(&mut self, line_break: &LexedToken) {
+        self.reset();
+        self.previous_byte = line_break.source_bytes.start;
+        self.current_byte = line_break.source_bytes.end;
+        self.previous_token = Some(Token::NewLine);
+        self.span = line_break.span;
+        self.indent = line_break.indent;
+    }
+
     fn source_bytes(&self) -> Range<usize> {
         self.previous_byte..self.current_byte
     }
@@ -292,6 +396,21 @@ impl<'a> TokenLexer<'a> {
         // The # symbol has already been matched
         chars.next();
 
+        if chars.peek() == Some(&'[') {
+            // An attribute, e.g. `#[fmt: skip]`, which is expected to be closed on the same line
+            let mut attribute_chars = chars.clone();
+            attribute_chars.next();
+            let (attribute_bytes, attribute_width) =
+                consume_and_count_utf8(&mut attribute_chars, self.tab_width, |c| {
+                    !matches!(c, ']' | '\r' | '\n')
+                });
+            if attribute_chars.peek() == Some(&']') {
+                // Include the `#[` and `]` delimiters
+                self.advance_line_utf8(attribute_bytes + 3, attribute_width + 3);
+                return Attribute;
+            }
+        }
+
         if chars.peek() == Some(&'-') {
             // multi-line comment
             let mut char_bytes = 1;
@@ -300,7 +419,7 @@ impl<'a> TokenLexer<'a> {
             let mut end_found = false;
             while let Some(c) = chars.next() {
                 char_bytes += c.len_utf8();
-                position.column += c.width().unwrap_or(0) as u32;
+                position.column += char_width(c, self.tab_width) as u32;
                 match c {
                     '#' if chars.peek() == Some(&'-') => {
                         chars.next();
@@ -336,7 +455,7 @@ impl<'a> TokenLexer<'a> {
         } else {
             // single-line comment
             let (comment_bytes, comment_width) =
-                consume_and_count_utf8(&mut chars, |c| !matches!(c, '\r' | '\n'));
+                consume_and_count_utf8(&mut chars, self.tab_width, |c| !matches!(c, '\r' | '\n'));
             self.advance_line_utf8(comment_bytes + 1, comment_width + 1);
             CommentSingle
         }
@@ -409,7 +528,7 @@ impl<'a> TokenLexer<'a> {
                 _ => {
                     chars.next();
                     string_bytes += c.len_utf8();
-                    position.column += c.width().unwrap_or(0) as u32;
+                    position.column += char_width(c, self.tab_width) as u32;
                 }
             }
         }
@@ -499,7 +618,7 @@ impl<'a> TokenLexer<'a> {
                 }
                 _ => {
                     string_bytes += c.len_utf8();
-                    position.column += c.width().unwrap_or(0) as u32;
+                    position.column += char_width(c, self.tab_width) as u32;
                 }
             }
         }
@@ -614,7 +733,8 @@ impl<'a> TokenLexer<'a> {
         // The first character has already been matched
         let c = chars.next().unwrap();
 
-        let (char_bytes, char_count) = consume_and_count_utf8(&mut chars, is_id_continue);
+        let (char_bytes, char_count) =
+            consume_and_count_utf8(&mut chars, self.tab_width, is_id_continue);
         let char_bytes = c.len_utf8() + char_bytes;
         let char_count = 1 + char_count;
 
@@ -685,6 +805,19 @@ impl<'a> TokenLexer<'a> {
             check_keyword!("while", While);
             check_keyword!("yield", Yield);
             check_keyword!("let", Let);
+
+            if let Some(index) = self
+                .custom_keywords
+                .iter()
+                .position(|keyword| keyword == id)
+            {
+                self.advance_line_utf8(char_bytes, char_count);
+                // Keywords with indices that don't fit in a u16 can't be represented by a token
+                return match u16::try_from(index) {
+                    Ok(index) => CustomKeyword(index),
+                    Err(_) => Error,
+                };
+            }
         }
 
         // If no keyword matched, then consume as an Id
@@ -696,7 +829,8 @@ impl<'a> TokenLexer<'a> {
         // The _ has already been matched
         let c = chars.next().unwrap();
 
-        let (char_bytes, char_count) = consume_and_count_utf8(&mut chars, is_id_continue);
+        let (char_bytes, char_count) =
+            consume_and_count_utf8(&mut chars, self.tab_width, is_id_continue);
         let char_bytes = c.len_utf8() + char_bytes;
         let char_count = 1 + char_count;
 
@@ -723,6 +857,12 @@ impl<'a> TokenLexer<'a> {
 
         check_symbol!("->", Arrow);
 
+        check_symbol!("&&&", BitwiseAnd);
+        check_symbol!("|||", BitwiseOr);
+        check_symbol!("^^^", BitwiseXor);
+        check_symbol!("<<", ShiftLeft);
+        check_symbol!(">>", ShiftRight);
+
         check_symbol!("==", Equal);
         check_symbol!("!=", NotEqual);
         check_symbol!(">=", GreaterOrEqual);
@@ -800,7 +940,14 @@ impl<'a> TokenLexer<'a> {
                     _ => match next_char {
                         c if is_whitespace(c) => {
                             let count = consume_and_count(&mut chars, is_whitespace);
-                            self.advance_line(count);
+                            // The indent is measured in whitespace characters,
+                            // while tabs can advance the column by more than one.
+                            let tab_count =
+                                remaining[..count].bytes().filter(|b| *b == b'\t').count();
+                            self.advance_line_utf8(
+                                count,
+                                count + tab_count * self.tab_width - tab_count,
+                            );
                             if matches!(self.previous_token, Some(Token::NewLine) | None) {
                                 self.indent = count;
                             }
@@ -891,12 +1038,20 @@ fn is_whitespace(c: char) -> bool {
 
 /// Returns true if the character matches the XID_Start Unicode property
 pub fn is_id_start(c: char) -> bool {
-    UnicodeXID::is_xid_start(c)
+    if c.is_ascii() {
+        c.is_ascii_alphabetic()
+    } else {
+        UnicodeXID::is_xid_start(c)
+    }
 }
 
 /// Returns true if the character matches the XID_Continue Unicode property
 pub fn is_id_continue(c: char) -> bool {
-    UnicodeXID::is_xid_continue(c)
+    if c.is_ascii() {
+        c.is_ascii_alphanumeric() || c == '_'
+    } else {
+        UnicodeXID::is_xid_continue(c)
+    }
 }
 
 fn consume_and_count(chars: &mut Peekable<Chars>, predicate: impl Fn(char) -> bool) -> usize {
@@ -913,8 +1068,20 @@ fn consume_and_count(chars: &mut Peekable<Chars>, predicate: impl Fn(char) -> bo
     char_bytes
 }
 
+// Returns the number of columns that the character advances by
+pub(crate) fn char_width(c: char, tab_width: usize) -> usize {
+    match c {
+        '\t' => tab_width,
+        // ASCII characters can be counted without a width lookup,
+        // control characters have no width.
+        _ if c.is_ascii() => usize::from(!c.is_ascii_control()),
+        _ => c.width().unwrap_or(0),
+    }
+}
+
 fn consume_and_count_utf8(
     chars: &mut Peekable<Chars>,
+    tab_width: usize,
     predicate: impl Fn(char) -> bool,
 ) -> (usize, usize) {
     let mut char_bytes = 0;
@@ -925,7 +1092,7 @@ fn consume_and_count_utf8(
             break;
         }
         char_bytes += c.len_utf8();
-        char_count += c.width().unwrap_or(0);
+        char_count += char_width(*c, tab_width);
         chars.next();
     }
 
@@ -986,6 +1153,44 @@ impl<'a> KotoLexer<'a> {
         }
     }
 
+    /// Sets the number of columns that a tab character advances by, the default is 1
+    ///
+    /// This affects the columns reported in token spans, allowing them to match the display of
+    /// the source in an editor. Token indentation is unaffected, with tabs counted as a single
+    /// whitespace character.
+    #[must_use]
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.lexer.tab_width = tab_width;
+        self
+    }
+
+    /// Registers additional keywords that will be emitted as [Token::CustomKeyword] tokens
+    ///
+    /// Each custom keyword is identified by its index in the provided list, allowing a
+    /// downstream parser to handle it without the lexer needing to be modified.
+    ///
+    /// Custom keywords can't shadow Koto's own keywords, which take priority when matching.
+    /// As with Koto's keywords, a custom keyword that follows a `.` is lexed as an [Token::Id].
+    ///
+    /// Custom keyword indices are limited to the range of a `u16`, and keywords registered beyond
+    /// that limit are lexed as [Token::Error].
+    #[must_use]
+    pub fn with_custom_keywords<I>(mut self, keywords: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.lexer.custom_keywords = keywords.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Initializes a lexer that incrementally reads its input from the provided reader
+    ///
+    /// See [ReaderLexer].
+    pub fn from_reader<R: BufRead>(reader: R) -> ReaderLexer<R> {
+        ReaderLexer::new(reader)
+    }
+
     /// Returns the input source
     pub fn source(&self) -> &'a str {
         self.lexer.source
@@ -1010,6 +1215,146 @@ impl<'a> KotoLexer<'a> {
         self.token_queue.get(n)
     }
 
+    /// Lexes the source after an edit, reusing the tokens from a previous version of the source
+    ///
+    /// `previous_tokens` are the tokens that were produced for the source before the edit was
+    /// made. `edit` is the byte range in the previous source that was replaced by `replacement`,
+    /// with the lexer's source being the result of applying the edit.
+    ///
+    /// Lexing starts from the beginning of the line containing the edit, and stops once a line
+    /// break that follows the edit matches a line break in the previous tokens, with the
+    /// remaining tokens being reused. Line breaks inside strings are skipped over, so edits that
+    /// change where strings start or end will cause a wider region to be lexed.
+    ///
+    /// The result matches the output of lexing the full source. If the edit or the previous
+    /// tokens are found to be inconsistent with the source, then the full source is lexed.
+    pub fn relex(
+        mut self,
+        previous_tokens: &[LexedToken],
+        edit: Range<usize>,
+        replacement: &str,
+    ) -> Vec<LexedToken> {
+        let source = self.source();
+
+        self.lexer.reset();
+        self.token_queue.clear();
+
+        if !is_valid_edit(source, previous_tokens, &edit, replacement) {
+            return self.collect();
+        }
+
+        let edit_end = edit.start + replacement.len();
+        let byte_delta = edit_end as isize - edit.end as isize;
+
+        // The number of unfinished strings following each of the previous tokens
+        let string_depths = previous_tokens
+            .iter()
+            .scan(0usize, |depth, token| {
+                match token.token {
+                    Token::StringStart(_) => *depth += 1,
+                    Token::StringEnd => *depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                Some(*depth)
+            })
+            .collect::<Vec<_>>();
+        let is_line_break = |index: usize| {
+            previous_tokens[index].token == Token::NewLine && string_depths[index] == 0
+        };
+
+        // Restart lexing after the last line break that precedes the edit,
+        // the lexer's state matches the state it had when the line break was produced.
+        let restart = (0..previous_tokens.len())
+            .rev()
+            .find(|&i| previous_tokens[i].source_bytes.end <= edit.start && is_line_break(i));
+        let mut result = match restart {
+            Some(index) => {
+                let line_break = &previous_tokens[index];
+                // The source preceding the edit is unchanged, so the line break should be found
+                // at the same position.
+                if !is_line_break_slice(source, &line_break.source_bytes) {
+                    return self.collect();
+                }
+                self.lexer.resume_after_line_break(line_break);
+                previous_tokens[..=index].to_vec()
+            }
+            None => Vec::new(),
+        };
+
+        let mut previous_index = restart.map_or(0, |index| index + 1);
+
+        while let Some(token) = self.next_token() {
+            let line_break = token.token == Token::NewLine
+                && token.source_bytes.start >= edit_end
+                && self.lexer.string_mode_stack.is_empty();
+            let token_start = token.source_bytes.start;
+            let token_line = token.span.end.line;
+            result.push(token);
+
+            if !line_break {
+                continue;
+            }
+
+            // Look for a matching line break in the previous tokens
+            let previous_start = (token_start as isize - byte_delta) as usize;
+            previous_index += previous_tokens[previous_index..]
+                .partition_point(|token| token.source_bytes.start < previous_start);
+
+            if previous_tokens
+                .get(previous_index)
+                .is_some_and(|token| token.source_bytes.start == previous_start)
+                && is_line_break(previous_index)
+            {
+                // The remaining tokens are unaffected by the edit, other than their positions
+                let line_delta =
+                    token_line as i64 - previous_tokens[previous_index].span.end.line as i64;
+                let shift_line = |position: Position| Position {
+                    line: (position.line as i64 + line_delta) as u32,
+                    column: position.column,
+                };
+
+                let reused = previous_tokens[previous_index + 1..]
+                    .iter()
+                    .map(|token| {
+                        let start = (token.source_bytes.start as isize + byte_delta) as usize;
+                        let end = (token.source_bytes.end as isize + byte_delta) as usize;
+                        let source_bytes = start..end;
+
+                        // Check that the reused token is consistent with the source,
+                        // falling back to a full lex if a mismatch is found.
+                        let slice = source.get(source_bytes.clone())?;
+                        if token.token == Token::NewLine && !matches!(slice, "\n" | "\r\n") {
+                            return None;
+                        }
+
+                        Some(LexedToken {
+                            token: token.token,
+                            source_bytes,
+                            span: Span {
+                                start: shift_line(token.span.start),
+                                end: shift_line(token.span.end),
+                            },
+                            indent: token.indent,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>();
+
+                match reused {
+                    Some(reused) => {
+                        result.extend(reused);
+                        break;
+                    }
+                    None => {
+                        self.lexer.reset();
+                        return self.collect();
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
     fn next_token(&mut self) -> Option<LexedToken> {
         self.lexer.next().map(|token| LexedToken {
             token,
@@ -1020,6 +1365,36 @@ impl<'a> KotoLexer<'a> {
     }
 }
 
+// Checks that an edit, and the tokens produced before the edit was made, are consistent with the
+// edited source
+fn is_valid_edit(
+    source: &str,
+    previous_tokens: &[LexedToken],
+    edit: &Range<usize>,
+    replacement: &str,
+) -> bool {
+    let Some(previous_len) = (source.len() + edit.len()).checked_sub(replacement.len()) else {
+        return false;
+    };
+
+    edit.start <= edit.end
+        && edit.end <= previous_len
+        && source.get(edit.start..edit.start + replacement.len()) == Some(replacement)
+        && previous_tokens
+            .iter()
+            .all(|token| token.source_bytes.start <= token.source_bytes.end)
+        && previous_tokens
+            .windows(2)
+            .all(|pair| pair[0].source_bytes.end <= pair[1].source_bytes.start)
+        && previous_tokens
+            .last()
+            .is_none_or(|token| token.source_bytes.end <= previous_len)
+}
+
+fn is_line_break_slice(source: &str, bytes: &Range<usize>) -> bool {
+    matches!(source.get(bytes.clone()), Some("\n" | "\r\n"))
+}
+
 impl Iterator for KotoLexer<'_> {
     type Item = LexedToken;
 
@@ -1032,6 +1407,116 @@ impl Iterator for KotoLexer<'_> {
     }
 }
 
+/// A token produced by a [ReaderLexer], along with the token's source text
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReadToken {
+    /// The lexed token
+    ///
+    /// The token's `source_bytes` refer to byte positions in the reader's overall input.
+    pub token: LexedToken,
+    /// The token's source text
+    pub slice: String,
+}
+
+/// A lexer that incrementally reads its input from a [BufRead] source
+///
+/// Input is read a line at a time, and the source text for each token is provided with the token,
+/// allowing large inputs or streams to be lexed without having to load all of the input up front.
+///
+/// Tokens that span multiple lines (e.g. multi-line strings or comments) will cause additional
+/// lines to be read until the token is complete.
+///
+/// See [KotoLexer::from_reader].
+pub struct ReaderLexer<R> {
+    reader: R,
+    // Input that has been read but not yet fully consumed by the lexer
+    buffer: String,
+    // The position in the overall input of the start of the buffer
+    buffer_offset: usize,
+    // The lexer's state, with byte positions relative to the start of the buffer
+    state: TokenLexerState,
+    // True when the reader has no more input available
+    end_of_input: bool,
+}
+
+impl<R: BufRead> ReaderLexer<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            buffer_offset: 0,
+            state: TokenLexer::new("").into_state(),
+            end_of_input: false,
+        }
+    }
+
+    // Reads the next line of input into the buffer
+    //
+    // Input preceding the most recently emitted token is removed from the buffer.
+    fn read_line(&mut self) -> io::Result<()> {
+        let consumed = self.state.previous_byte;
+        self.buffer.drain(..consumed);
+        self.buffer_offset += consumed;
+        self.state.current_byte -= consumed;
+        self.state.previous_byte = 0;
+
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            self.end_of_input = true;
+        } else if self.buffer_offset == 0
+            && self.state.current_byte == 0
+            && self.buffer.starts_with(BYTE_ORDER_MARK)
+        {
+            // Skip over a leading byte order mark, see TokenLexer::new
+            self.state.current_byte = BYTE_ORDER_MARK.len_utf8();
+            self.state.previous_byte = self.state.current_byte;
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for ReaderLexer<R> {
+    type Item = io::Result<ReadToken>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut lexer = TokenLexer::with_state(&self.buffer, self.state.clone());
+
+            match lexer.next() {
+                // A token that ends before the end of the buffer is complete, otherwise more
+                // input might be needed to complete the token. Errors can also be caused by
+                // incomplete input, e.g. an unterminated string.
+                Some(token)
+                    if (token != Token::Error && lexer.current_byte < self.buffer.len())
+                        || self.end_of_input =>
+                {
+                    let source_bytes = lexer.source_bytes();
+                    let result = ReadToken {
+                        slice: self.buffer[source_bytes.clone()].to_string(),
+                        token: LexedToken {
+                            token,
+                            source_bytes: (source_bytes.start + self.buffer_offset)
+                                ..(source_bytes.end + self.buffer_offset),
+                            span: lexer.span,
+                            indent: lexer.indent,
+                        },
+                    };
+                    self.state = lexer.into_state();
+                    return Some(Ok(result));
+                }
+                None if self.end_of_input => return None,
+                _ => {
+                    // Discard the lexer's progress and try again once more input is available
+                    if let Err(error) = self.read_line() {
+                        self.end_of_input = true;
+                        return Some(Err(error));
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1180,6 +1665,31 @@ false #
             );
         }
 
+        #[test]
+        fn attributes() {
+            let input = "\
+#[fmt: skip]
+x = 1 #[foo] # comment
+#[unclosed
+y";
+            check_lexer_output(
+                input,
+                &[
+                    (Attribute, Some("#[fmt: skip]"), 0),
+                    (NewLine, None, 0),
+                    (Id, Some("x"), 1),
+                    (Assign, None, 1),
+                    (Number, Some("1"), 1),
+                    (Attribute, Some("#[foo]"), 1),
+                    (CommentSingle, Some("# comment"), 1),
+                    (NewLine, None, 1),
+                    (CommentSingle, Some("#[unclosed"), 2),
+                    (NewLine, None, 2),
+                    (Id, Some("y"), 3),
+                ],
+            );
+        }
+
         #[test]
         fn strings() {
             let input = r#"
@@ -1246,6 +1756,41 @@ r#''bar''#
             );
         }
 
+        #[test]
+        fn raw_strings_with_quotes_in_contents() {
+            let input = r####"
+r#'a'b'#
+r###'x'#'##y'###
+"####;
+
+            check_lexer_output(
+                input,
+                &[
+                    (NewLine, None, 0),
+                    (raw_string(StringQuote::Single, 1), Some("r#'"), 1),
+                    (StringLiteral, Some("a'b"), 1),
+                    (StringEnd, Some("'#"), 1),
+                    (NewLine, None, 1),
+                    (raw_string(StringQuote::Single, 3), Some("r###'"), 2),
+                    (StringLiteral, Some("x'#'##y"), 2),
+                    (StringEnd, Some("'###"), 2),
+                    (NewLine, None, 2),
+                ],
+            );
+        }
+
+        #[test]
+        fn unterminated_raw_string() {
+            let input = "r##'abc'#";
+            let mut lex = KotoLexer::new(input);
+
+            assert_eq!(
+                lex.next().map(|token| token.token),
+                Some(raw_string(StringQuote::Single, 2))
+            );
+            assert_eq!(lex.next().map(|token| token.token), Some(Error));
+        }
+
         #[test]
         fn interpolated_string_ids() {
             let input = r#"
@@ -1461,6 +2006,67 @@ c *= 3";
             );
         }
 
+        #[test]
+        fn power_operators() {
+            let input = "\
+2^3
+x ^= 2
+a^b^c";
+            check_lexer_output(
+                input,
+                &[
+                    (Number, Some("2"), 0),
+                    (Power, Some("^"), 0),
+                    (Number, Some("3"), 0),
+                    (NewLine, None, 0),
+                    (Id, Some("x"), 1),
+                    (PowerAssign, Some("^="), 1),
+                    (Number, Some("2"), 1),
+                    (NewLine, None, 1),
+                    (Id, Some("a"), 2),
+                    (Power, Some("^"), 2),
+                    (Id, Some("b"), 2),
+                    (Power, Some("^"), 2),
+                    (Id, Some("c"), 2),
+                ],
+            );
+        }
+
+        #[test]
+        fn bitwise_operators() {
+            let input = "\
+a &&& b ||| c
+x ^^^ 0xff
+1 << 4 >> 2
+f = || 42";
+            check_lexer_output(
+                input,
+                &[
+                    (Id, Some("a"), 0),
+                    (BitwiseAnd, Some("&&&"), 0),
+                    (Id, Some("b"), 0),
+                    (BitwiseOr, Some("|||"), 0),
+                    (Id, Some("c"), 0),
+                    (NewLine, None, 0),
+                    (Id, Some("x"), 1),
+                    (BitwiseXor, Some("^^^"), 1),
+                    (Number, Some("0xff"), 1),
+                    (NewLine, None, 1),
+                    (Number, Some("1"), 2),
+                    (ShiftLeft, Some("<<"), 2),
+                    (Number, Some("4"), 2),
+                    (ShiftRight, Some(">>"), 2),
+                    (Number, Some("2"), 2),
+                    (NewLine, None, 2),
+                    (Id, Some("f"), 3),
+                    (Assign, None, 3),
+                    (Function, None, 3),
+                    (Function, None, 3),
+                    (Number, Some("42"), 3),
+                ],
+            );
+        }
+
         #[test]
         fn let_expression() {
             let input = "let my_var: Number = 42";
@@ -1696,4 +2302,461 @@ x.iter()
             assert_eq!(lex.peek(13), None);
         }
     }
+
+    mod tab_width {
+        use super::*;
+
+        // Returns the spans and indents of the non-whitespace tokens in the lexer's output
+        fn spans_and_indents(lexer: KotoLexer) -> Vec<(Token, Span, usize)> {
+            lexer
+                .filter(|token| token.token != Token::Whitespace)
+                .map(|token| (token.token, token.span, token.indent))
+                .collect()
+        }
+
+        fn span(start: (u32, u32), end: (u32, u32)) -> Span {
+            Span {
+                start: Position {
+                    line: start.0,
+                    column: start.1,
+                },
+                end: Position {
+                    line: end.0,
+                    column: end.1,
+                },
+            }
+        }
+
+        #[test]
+        fn default_tab_width() {
+            let source = "x\t= 'a\tb'\n\ty";
+
+            assert_eq!(
+                spans_and_indents(KotoLexer::new(source)),
+                [
+                    (Token::Id, span((0, 0), (0, 1)), 0),
+                    (Token::Assign, span((0, 2), (0, 3)), 0),
+                    (
+                        Token::StringStart(StringType::Normal(StringQuote::Single)),
+                        span((0, 4), (0, 5)),
+                        0
+                    ),
+                    (Token::StringLiteral, span((0, 5), (0, 8)), 0),
+                    (Token::StringEnd, span((0, 8), (0, 9)), 0),
+                    (Token::NewLine, span((0, 9), (1, 0)), 0),
+                    (Token::Id, span((1, 1), (1, 2)), 1),
+                ]
+            );
+        }
+
+        #[test]
+        fn tab_width_of_4() {
+            let source = "x\t= 'a\tb'\n\ty";
+
+            assert_eq!(
+                spans_and_indents(KotoLexer::new(source).with_tab_width(4)),
+                [
+                    (Token::Id, span((0, 0), (0, 1)), 0),
+                    (Token::Assign, span((0, 5), (0, 6)), 0),
+                    (
+                        Token::StringStart(StringType::Normal(StringQuote::Single)),
+                        span((0, 7), (0, 8)),
+                        0
+                    ),
+                    (Token::StringLiteral, span((0, 8), (0, 14)), 0),
+                    (Token::StringEnd, span((0, 14), (0, 15)), 0),
+                    (Token::NewLine, span((0, 15), (1, 0)), 0),
+                    // The indent is measured in whitespace characters
+                    (Token::Id, span((1, 4), (1, 5)), 1),
+                ]
+            );
+        }
+    }
+
+    mod custom_keywords {
+        use super::*;
+
+        // Returns the non-whitespace tokens in the lexer's output along with their slices
+        fn tokens<'a>(lexer: KotoLexer<'a>, source: &'a str) -> Vec<(Token, &'a str)> {
+            lexer
+                .filter(|token| token.token != Token::Whitespace)
+                .map(|token| (token.token, token.slice(source)))
+                .collect()
+        }
+
+        #[test]
+        fn custom_keyword_is_distinct_from_id() {
+            let source = "match2 x match22";
+            let lexer = KotoLexer::new(source).with_custom_keywords(["unless", "match2"]);
+
+            assert_eq!(
+                tokens(lexer, source),
+                [
+                    (Token::CustomKeyword(1), "match2"),
+                    (Token::Id, "x"),
+                    (Token::Id, "match22"),
+                ]
+            );
+        }
+
+        #[test]
+        fn custom_keyword_after_dot_is_an_id() {
+            let source = "x.match2";
+            let lexer = KotoLexer::new(source).with_custom_keywords(["match2"]);
+
+            assert_eq!(
+                tokens(lexer, source),
+                [(Token::Id, "x"), (Token::Dot, "."), (Token::Id, "match2")]
+            );
+        }
+
+        #[test]
+        fn custom_keyword_with_an_index_beyond_u16_is_an_error() {
+            let source = "last";
+            let keywords = (0..=u16::MAX)
+                .map(|i| format!("keyword{i}"))
+                .chain(Some("last".into()));
+            let lexer = KotoLexer::new(source).with_custom_keywords(keywords);
+
+            assert_eq!(tokens(lexer, source), [(Token::Error, "last")]);
+        }
+
+        #[test]
+        fn custom_keywords_dont_shadow_existing_keywords() {
+            let source = "match x";
+            let lexer = KotoLexer::new(source).with_custom_keywords(["match"]);
+
+            assert_eq!(
+                tokens(lexer, source),
+                [(Token::Match, "match"), (Token::Id, "x")]
+            );
+        }
+    }
+
+    mod relex {
+        use super::*;
+
+        // Applies the edit to the source, and checks that relexing matches a full lex
+        fn check_relex(source: &str, edit: Range<usize>, replacement: &str) {
+            let previous_tokens = KotoLexer::new(source).collect::<Vec<_>>();
+
+            let mut edited = source.to_string();
+            edited.replace_range(edit.clone(), replacement);
+
+            let expected = KotoLexer::new(&edited).collect::<Vec<_>>();
+            let relexed = KotoLexer::new(&edited).relex(&previous_tokens, edit, replacement);
+
+            assert_eq!(relexed, expected, "Mismatch when relexing:\n{edited}");
+        }
+
+        const SOURCE: &str = "\
+x = 1
+f = |y|
+  z = 'hello {y}'
+  z.size()
+# comment
+f x
+";
+
+        #[test]
+        fn edit_inside_a_line() {
+            check_relex(SOURCE, 4..5, "1234");
+        }
+
+        #[test]
+        fn edit_inside_an_indented_line() {
+            check_relex(SOURCE, 36..40, "to_uppercase");
+        }
+
+        #[test]
+        fn edit_inside_a_string() {
+            check_relex(SOURCE, 21..26, "hi");
+        }
+
+        #[test]
+        fn edit_removing_a_template_expression() {
+            check_relex(SOURCE, 24..29, "hi");
+        }
+
+        #[test]
+        fn edit_on_the_first_line() {
+            check_relex(SOURCE, 0..1, "foo");
+        }
+
+        #[test]
+        fn edit_on_the_last_line() {
+            check_relex(SOURCE, 55..56, "42");
+        }
+
+        #[test]
+        fn inserted_lines() {
+            check_relex(SOURCE, 6..6, "a = 2\nb = 3\n");
+        }
+
+        #[test]
+        fn removed_lines() {
+            check_relex(SOURCE, 6..14, "");
+        }
+
+        #[test]
+        fn indentation_change() {
+            check_relex(SOURCE, 32..32, "  ");
+        }
+
+        #[test]
+        fn opening_a_multiline_string() {
+            let source = "\
+a = 1
+b = 2
+c = 3 # '
+";
+            check_relex(source, 4..5, "'1");
+        }
+
+        #[test]
+        fn closing_a_multiline_string() {
+            let source = "\
+a = '1
+b = 2
+c = 3 # '
+";
+            check_relex(source, 4..5, "");
+        }
+
+        #[test]
+        fn opening_a_multiline_comment() {
+            let source = "\
+a = 1
+b = 2 # -#
+c = 3
+";
+            check_relex(source, 0..0, "#- ");
+        }
+
+        #[test]
+        fn edit_with_crlf_line_endings() {
+            let source = SOURCE.replace('\n', "\r\n");
+            check_relex(&source, 7..8, "g");
+        }
+
+        #[test]
+        fn edit_replacing_a_line_break() {
+            // Joining the first two lines
+            check_relex(SOURCE, 5..6, " ");
+            check_relex(SOURCE, 5..6, "");
+        }
+
+        #[test]
+        fn edit_inserting_a_line_break() {
+            check_relex(SOURCE, 5..5, "\n");
+            check_relex(SOURCE, 6..6, "\n");
+        }
+
+        #[test]
+        fn edit_replacing_a_crlf_line_break() {
+            let source = SOURCE.replace('\n', "\r\n");
+            check_relex(&source, 5..7, "\n");
+            check_relex(&source, 5..6, "");
+        }
+
+        #[test]
+        fn edits_without_a_trailing_newline() {
+            let source = SOURCE.trim_end();
+            check_relex(source, 55..56, "42");
+            check_relex(source, 0..1, "foo");
+            check_relex(source, source.len()..source.len(), "\n");
+            check_relex(source, source.len()..source.len(), " + 1");
+            check_relex(source, source.len() - 4..source.len(), "");
+        }
+
+        #[test]
+        fn edit_removing_the_trailing_newline() {
+            check_relex(SOURCE, SOURCE.len() - 1..SOURCE.len(), "");
+        }
+
+        #[test]
+        fn stale_previous_tokens_fall_back_to_a_full_lex() {
+            let previous_tokens = KotoLexer::new(SOURCE).collect::<Vec<_>>();
+
+            // The edit doesn't match the previous tokens, e.g. an edit was missed
+            let edited = SOURCE.replace("x = 1", "xyz = 1");
+            let expected = KotoLexer::new(&edited).collect::<Vec<_>>();
+            let relexed = KotoLexer::new(&edited).relex(&previous_tokens, 55..56, "x");
+            assert_eq!(relexed, expected);
+
+            // The edit's replacement isn't in the source
+            let relexed = KotoLexer::new(&edited).relex(&previous_tokens, 0..1, "foo");
+            assert_eq!(relexed, expected);
+
+            // The edit extends past the end of the previous source
+            let relexed = KotoLexer::new(&edited).relex(&previous_tokens, 0..1000, "xyz");
+            assert_eq!(relexed, expected);
+        }
+
+        #[test]
+        fn relexing_with_a_used_lexer() {
+            let previous_tokens = KotoLexer::new(SOURCE).collect::<Vec<_>>();
+
+            let mut edited = SOURCE.to_string();
+            edited.replace_range(4..5, "99");
+            let expected = KotoLexer::new(&edited).collect::<Vec<_>>();
+
+            let mut lexer = KotoLexer::new(&edited);
+            lexer.next();
+            lexer.peek(0);
+            assert_eq!(lexer.relex(&previous_tokens, 4..5, "99"), expected);
+        }
+    }
+
+    mod byte_order_mark {
+        use super::*;
+
+        // Returns the lexer's output with the token slices in place of byte positions
+        fn lexer_output(source: &str) -> Vec<(Token, &str, Span, usize)> {
+            KotoLexer::new(source)
+                .map(|token| (token.token, token.slice(source), token.span, token.indent))
+                .collect()
+        }
+
+        #[test]
+        fn leading_byte_order_mark_is_skipped() {
+            let source = "\
+x = 'hello'
+f = |y|
+  y + x
+";
+            let with_bom = format!("{BYTE_ORDER_MARK}{source}");
+
+            assert_eq!(lexer_output(source), lexer_output(&with_bom));
+        }
+
+        #[test]
+        fn leading_byte_order_mark_is_skipped_by_reader() {
+            let source = format!("{BYTE_ORDER_MARK}x = 1\ny = 2");
+
+            let expected = KotoLexer::new(&source)
+                .map(|token| ReadToken {
+                    slice: token.slice(&source).to_string(),
+                    token,
+                })
+                .collect::<Vec<_>>();
+            let output = KotoLexer::from_reader(source.as_bytes())
+                .collect::<io::Result<Vec<_>>>()
+                .unwrap();
+
+            assert_eq!(expected, output);
+        }
+
+        #[test]
+        fn byte_order_mark_after_start_is_an_error() {
+            let source = format!("x = 1\n{BYTE_ORDER_MARK}y = 2");
+
+            assert!(
+                KotoLexer::new(&source)
+                    .take(10)
+                    .any(|token| token.token == Token::Error)
+            );
+        }
+    }
+
+    mod reader {
+        use super::*;
+
+        fn check_reader_output(source: &str) {
+            // Lexing stops after the first error, the lexer doesn't make progress after errors
+            fn take_until_error(tokens: impl Iterator<Item = ReadToken>) -> Vec<ReadToken> {
+                let mut result = Vec::new();
+                for token in tokens {
+                    let is_error = token.token.token == Token::Error;
+                    result.push(token);
+                    if is_error {
+                        break;
+                    }
+                }
+                result
+            }
+
+            let expected = take_until_error(KotoLexer::new(source).map(|token| ReadToken {
+                slice: token.slice(source).to_string(),
+                token,
+            }));
+
+            let output = take_until_error(
+                KotoLexer::from_reader(source.as_bytes()).map(|token| token.unwrap()),
+            );
+
+            assert_eq!(expected, output);
+        }
+
+        #[test]
+        fn single_line() {
+            check_reader_output("x = foo(1, 2) + 'hello {bar}'");
+        }
+
+        #[test]
+        fn indented_blocks() {
+            check_reader_output(
+                "\
+f = |x|
+  if x > 0
+    x * 2
+  else
+    -x
+",
+            );
+        }
+
+        #[test]
+        fn multiline_comments_and_strings() {
+            check_reader_output(
+                r#"
+#- a multi-line
+   comment -#
+x = "
+  a multi-line
+  string {1 + 1}
+"
+y = r##'a multi-line
+raw string'##
+"#,
+            );
+        }
+
+        #[test]
+        fn windows_line_endings() {
+            check_reader_output("x = 1\r\ny = 'abc\r\ndef'\r\n");
+        }
+
+        #[test]
+        fn unterminated_string() {
+            check_reader_output("x = 'abc\ndef");
+        }
+    }
+
+    mod ascii_fast_path {
+        use super::*;
+
+        #[test]
+        fn id_predicates_match_unicode_properties() {
+            for c in (0..128u8).map(char::from) {
+                assert_eq!(is_id_start(c), UnicodeXID::is_xid_start(c), "{c:?}");
+                assert_eq!(is_id_continue(c), UnicodeXID::is_xid_continue(c), "{c:?}");
+            }
+        }
+
+        #[test]
+        fn ascii_widths_match_unicode_width() {
+            let source = (0..128u8).map(char::from).collect::<String>();
+            // Tabs have no width according to unicode-width, so a tab width of 0 is used here
+            let (char_bytes, char_count) =
+                consume_and_count_utf8(&mut source.chars().peekable(), 0, |_| true);
+
+            let expected_count = source
+                .chars()
+                .map(|c| c.width().unwrap_or(0))
+                .sum::<usize>();
+            assert_eq!(char_bytes, 128);
+            assert_eq!(char_count, expected_count);
+        }
+    }
 }
@@ -2,13 +2,17 @@
 
 #![warn(missing_docs)]
 
+mod dump;
 mod lexer;
+mod source_map;
 mod span;
 
 pub use crate::{
+    dump::dump_tokens,
     lexer::{
-        KotoLexer as Lexer, LexedToken, RawStringDelimiter, StringQuote, StringType, Token,
-        is_id_continue, is_id_start,
+        KotoLexer as Lexer, LexedToken, RawStringDelimiter, ReadToken, ReaderLexer, StringQuote,
+        StringType, Token, is_id_continue, is_id_start,
     },
+    source_map::SourceMap,
     span::{Position, Span},
 };
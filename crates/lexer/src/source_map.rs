@@ -0,0 +1,166 @@
+use crate::{Position, lexer::char_width};
+
+/// Converts between byte offsets and line/column positions in a script
+///
+/// Columns are counted in the same way as in the spans produced by the lexer, so wide characters
+/// advance by their display width, and control characters have no width. Both `\n` and `\r\n`
+/// line endings are supported.
+pub struct SourceMap<'a> {
+    source: &'a str,
+    // The byte offsets of the start of each line
+    line_starts: Vec<usize>,
+    // The number of columns that a tab character advances by
+    tab_width: usize,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Initializes a source map for the given script
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            source,
+            line_starts,
+            tab_width: 1,
+        }
+    }
+
+    /// Sets the number of columns that a tab character advances by, the default is 1
+    ///
+    /// This should match the tab width that was used by the lexer that produced the spans that
+    /// are being mapped, see [KotoLexer::with_tab_width](crate::Lexer::with_tab_width).
+    #[must_use]
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    /// Returns the line/column position of the given byte offset
+    ///
+    /// Offsets past the end of the source are clamped to the end of the source, and offsets that
+    /// fall within a multi-byte character are treated as referring to the start of the character.
+    pub fn offset_to_position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.source.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+
+        let column = self
+            .line(line)
+            .char_indices()
+            .take_while(|(i, c)| line_start + i + c.len_utf8() <= offset)
+            .map(|(_, c)| char_width(c, self.tab_width))
+            .sum::<usize>();
+
+        Position {
+            line: line as u32,
+            column: column as u32,
+        }
+    }
+
+    /// Returns the byte offset of the given line/column position
+    ///
+    /// Positions past the end of a line are clamped to the end of the line, and positions past the
+    /// last line are clamped to the end of the source. A column that falls within a wide
+    /// character refers to the start of the character.
+    pub fn position_to_offset(&self, position: Position) -> usize {
+        let line = position.line as usize;
+        let Some(&line_start) = self.line_starts.get(line) else {
+            return self.source.len();
+        };
+
+        let line_contents = self.line(line);
+        let mut column = 0;
+
+        for (i, c) in line_contents.char_indices() {
+            column += char_width(c, self.tab_width);
+            if column > position.column as usize {
+                return line_start + i;
+            }
+        }
+
+        line_start + line_contents.len()
+    }
+
+    // Returns the contents of the given line, excluding the line ending
+    fn line(&self, line: usize) -> &'a str {
+        let start = self.line_starts[line];
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.source.len(), |next_start| next_start - 1);
+        let contents = &self.source[start..end];
+        contents.strip_suffix('\r').unwrap_or(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(line: u32, column: u32) -> Position {
+        Position { line, column }
+    }
+
+    #[test]
+    fn multi_line_multi_byte() {
+        // 'é' is 2 bytes wide, '漢' is 3 bytes and 2 columns wide
+        let source = "ab\r\né漢x\n\ny";
+        let map = SourceMap::new(source);
+
+        let cases = [
+            (0, position(0, 0)),
+            (2, position(0, 2)),
+            (4, position(1, 0)),
+            (6, position(1, 1)),
+            (9, position(1, 3)),
+            (10, position(1, 4)),
+            (11, position(2, 0)),
+            (12, position(3, 0)),
+            (13, position(3, 1)),
+        ];
+
+        for (offset, expected) in cases {
+            assert_eq!(map.offset_to_position(offset), expected, "offset {offset}");
+            assert_eq!(map.position_to_offset(expected), offset, "{expected:?}");
+        }
+    }
+
+    #[test]
+    fn offsets_within_characters_and_line_endings() {
+        let source = "ab\r\né漢x";
+        let map = SourceMap::new(source);
+
+        // Within the \r\n line ending
+        assert_eq!(map.offset_to_position(3), position(0, 2));
+        // Within 'é'
+        assert_eq!(map.offset_to_position(5), position(1, 0));
+        // Within '漢'
+        assert_eq!(map.offset_to_position(8), position(1, 1));
+        // Past the end
+        assert_eq!(map.offset_to_position(100), position(1, 4));
+    }
+
+    #[test]
+    fn positions_out_of_range() {
+        let source = "ab\r\né漢x";
+        let map = SourceMap::new(source);
+
+        // Within '漢'
+        assert_eq!(map.position_to_offset(position(1, 2)), 6);
+        // Past the end of a line
+        assert_eq!(map.position_to_offset(position(0, 10)), 2);
+        // Past the last line
+        assert_eq!(map.position_to_offset(position(5, 0)), source.len());
+    }
+
+    #[test]
+    fn tabs() {
+        let source = "\tx";
+        let map = SourceMap::new(source).with_tab_width(4);
+
+        assert_eq!(map.offset_to_position(1), position(0, 4));
+        assert_eq!(map.position_to_offset(position(0, 4)), 1);
+    }
+}
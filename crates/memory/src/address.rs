@@ -4,7 +4,7 @@ use std::{
 };
 
 /// A wrapper for comparing and hashing pointer addresses
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Address(*const u8);
 
 impl<T: ?Sized> From<*const T> for Address {
@@ -65,3 +65,24 @@ where
 {
     BorrowMutImpl::try_map(borrowed, f)
 }
+
+#[inline]
+pub(crate) fn borrowed_map<'a, T: ?Sized, U, F>(borrowed: BorrowImpl<'a, T>, f: F) -> BorrowImpl<'a, U>
+where
+    F: FnOnce(&T) -> &U,
+    U: ?Sized,
+{
+    BorrowImpl::map(borrowed, f)
+}
+
+#[inline]
+pub(crate) fn borrowed_mut_map<'a, T: ?Sized, U, F>(
+    borrowed: BorrowMutImpl<'a, T>,
+    f: F,
+) -> BorrowMutImpl<'a, U>
+where
+    F: FnOnce(&mut T) -> &mut U,
+    U: ?Sized,
+{
+    BorrowMutImpl::map(borrowed, f)
+}
@@ -6,8 +6,8 @@ use std::{
 use crate::{
     Ptr,
     ptr_impl::{
-        BorrowImpl, BorrowMutImpl, CellImpl, borrow, borrow_mut, borrowed_filter_map,
-        borrowed_mut_filter_map, try_borrow, try_borrow_mut,
+        BorrowImpl, BorrowMutImpl, CellImpl, borrow, borrow_mut, borrowed_filter_map, borrowed_map,
+        borrowed_mut_filter_map, borrowed_mut_map, try_borrow, try_borrow_mut,
     },
 };
 
@@ -91,6 +91,15 @@ impl<T: ?Sized> KCell<T> {
 pub struct Borrow<'a, T: ?Sized>(BorrowImpl<'a, T>);
 
 impl<'a, T: ?Sized> Borrow<'a, T> {
+    /// Makes a new Borrow for a component of the borrowed data
+    pub fn map<U, F>(borrowed: Self, f: F) -> Borrow<'a, U>
+    where
+        F: FnOnce(&T) -> &U,
+        U: ?Sized,
+    {
+        Borrow(borrowed_map(borrowed.0, f))
+    }
+
     /// Makes a new Borrow for an optional component of the borrowed data.
     /// If the closure returns None then the original borrow is returned as the error.
     pub fn filter_map<U, F>(borrowed: Self, f: F) -> Result<Borrow<'a, U>, Self>
@@ -123,6 +132,15 @@ impl<T: ?Sized + fmt::Display> fmt::Display for Borrow<'_, T> {
 pub struct BorrowMut<'a, T: ?Sized>(BorrowMutImpl<'a, T>);
 
 impl<'a, T: ?Sized> BorrowMut<'a, T> {
+    /// Makes a new BorrowMut for a component of the borrowed data
+    pub fn map<U, F>(borrowed: Self, f: F) -> BorrowMut<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+        U: ?Sized,
+    {
+        BorrowMut(borrowed_mut_map(borrowed.0, f))
+    }
+
     /// Makes a new BorrowMut for an optional component of the borrowed data.
     /// If the closure returns None then the original borrow is returned as the error.
     pub fn filter_map<U, F>(borrowed: Self, f: F) -> Result<BorrowMut<'a, U>, Self>
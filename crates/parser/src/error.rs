@@ -153,6 +153,8 @@ pub enum SyntaxError {
     ExpectedMatchPattern,
     #[error("expected id after @meta")]
     ExpectedMetaId,
+    #[error("expected a property name after @get or @set")]
+    ExpectedMetaPropertyName,
     #[error("expected a module path after 'from'")]
     ExpectedPathAfterFrom,
     #[error("expected a line break before starting a map block")]
@@ -181,6 +183,8 @@ pub enum SyntaxError {
     MatchEllipsisOutsideOfNestedPatterns,
     #[error("'else' can only be used in the last arm in a match expression")]
     MatchElseNotInLastArm,
+    #[error("the maximum nesting depth of {0} has been exceeded")]
+    MaximumDepthExceeded(usize),
     #[error("Missing 'from' for wildcard import")]
     MissingModuleForWildcardImport,
     #[error("nested types aren't currently supported")]
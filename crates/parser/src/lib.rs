@@ -21,4 +21,4 @@ pub use crate::{
     string_format_options::{StringAlignment, StringFormatOptions, StringFormatRepresentation},
     string_slice::StringSlice,
 };
-pub use koto_lexer::{Position, RawStringDelimiter, Span, StringQuote, StringType};
+pub use koto_lexer::{Position, RawStringDelimiter, SourceMap, Span, StringQuote, StringType};
@@ -335,7 +335,9 @@ pub enum Node {
     Try(AstTry),
 
     /// A throw expression
-    Throw(AstIndex),
+    ///
+    /// A `throw` without a value rethrows the error that's being handled in a `catch` block.
+    Throw(Option<AstIndex>),
 
     /// A yield expression
     Yield(AstIndex),
@@ -348,6 +350,20 @@ pub enum Node {
         expression: AstIndex,
     },
 
+    /// An expression that's preceded by attributes
+    ///
+    /// E.g.
+    /// ```koto
+    /// #[fmt: skip]
+    /// x = 1 +   2
+    /// ```
+    Attributed {
+        /// The contents of the attributes, excluding the `#[` and `]` delimiters
+        attributes: AstVec<ConstantIndex>,
+        /// The expression that the attributes are attached to
+        expression: AstIndex,
+    },
+
     /// A type hint
     ///
     /// E.g. `let x: Number = 0`
@@ -480,6 +496,10 @@ impl fmt::Display for AstUnaryOp {
 }
 
 /// An operation used in BinaryOp expressions
+///
+/// The bitwise operations are reserved: they're parsed, but are currently rejected by the
+/// compiler. Once supported, they'll operate on the integer values of their operands (truncating
+/// towards zero), with non-integral operands resulting in an error.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(missing_docs)]
 pub enum AstBinaryOp {
@@ -489,6 +509,11 @@ pub enum AstBinaryOp {
     Divide,
     Remainder,
     Power,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
     AddAssign,
     SubtractAssign,
     MultiplyAssign,
@@ -516,6 +541,11 @@ impl AstBinaryOp {
             AstBinaryOp::Divide => "/",
             AstBinaryOp::Remainder => "%",
             AstBinaryOp::Power => "^",
+            AstBinaryOp::BitwiseAnd => "&&&",
+            AstBinaryOp::BitwiseOr => "|||",
+            AstBinaryOp::BitwiseXor => "^^^",
+            AstBinaryOp::ShiftLeft => "<<",
+            AstBinaryOp::ShiftRight => ">>",
             AstBinaryOp::AddAssign => "+=",
             AstBinaryOp::SubtractAssign => "-=",
             AstBinaryOp::MultiplyAssign => "*=",
@@ -701,6 +731,11 @@ pub enum MetaKeyId {
     /// @meta name
     Named,
 
+    /// @get name
+    Get,
+    /// @set name
+    Set,
+
     /// Unused
     ///
     /// This entry must be last, see `TryFrom<u7>` for [MetaKeyId]
@@ -708,6 +743,14 @@ pub enum MetaKeyId {
 }
 
 impl MetaKeyId {
+    /// Returns true if the key is followed by a name, e.g. `@test my_test`
+    pub fn is_named(&self) -> bool {
+        matches!(
+            self,
+            MetaKeyId::Named | MetaKeyId::Test | MetaKeyId::Get | MetaKeyId::Set
+        )
+    }
+
     /// Returns the key id as a static str
     pub fn as_str(&self) -> &'static str {
         use MetaKeyId::*;
@@ -755,6 +798,8 @@ impl MetaKeyId {
             PostTest => "@post_test",
             Main => "@main",
             Named => "@meta",
+            Get => "@get",
+            Set => "@set",
             Invalid => unreachable!(),
         }
     }
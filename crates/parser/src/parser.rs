@@ -7,7 +7,7 @@ use crate::{
     error::{Error, ErrorKind, ExpectedIndentation, InternalError, Result, SyntaxError},
     node::*,
 };
-use koto_lexer::{LexedToken, Lexer, Span, StringType, Token};
+use koto_lexer::{LexedToken, Lexer, Span, StringType, Token, is_id_continue};
 use std::{
     borrow::Cow,
     collections::HashSet,
@@ -245,6 +245,7 @@ impl ExpressionContext {
 }
 
 /// Options that are passed into [`Parser::parse`]
+#[derive(Clone)]
 pub struct ParserOptions {
     /// Whether or not string escape codes should be processed. (default: `true`)
     ///
@@ -255,12 +256,22 @@ pub struct ParserOptions {
     /// The runtime expects this to be `true`, while some development tools like `koto_format` will
     /// set this to `false`.
     pub process_escape_codes: bool,
+
+    /// The maximum depth of nested expressions that the parser will accept. (default: `None`)
+    ///
+    /// Each syntactic level of nesting (e.g. a bracketed expression, a container, or an indented
+    /// block) counts as one level of depth.
+    ///
+    /// Parsing is recursive, so deeply nested input could otherwise exhaust the stack.
+    /// An error will be returned if the limit is exceeded.
+    pub max_parse_depth: Option<usize>,
 }
 
 impl Default for ParserOptions {
     fn default() -> Self {
         Self {
             process_escape_codes: true,
+            max_parse_depth: None,
         }
     }
 }
@@ -274,6 +285,14 @@ pub struct Parser<'source> {
     current_token: LexedToken,
     frame_stack: Vec<Frame>,
     options: ParserOptions,
+    // Attribute tokens that have been consumed since the last non-whitespace token
+    pending_attributes: Vec<LexedToken>,
+    // True when a non-whitespace token has been consumed on the current line
+    //
+    // Attributes that follow an expression on the same line aren't attached to anything.
+    line_has_expression: bool,
+    // The current depth of nested terms, checked against `options.max_parse_depth`
+    depth: usize,
 }
 
 impl<'source> Parser<'source> {
@@ -293,6 +312,9 @@ impl<'source> Parser<'source> {
             current_token: LexedToken::default(),
             frame_stack: Vec::new(),
             options,
+            pending_attributes: Vec::new(),
+            line_has_expression: false,
+            depth: 0,
         };
 
         match parser.consume_main_block() {
@@ -316,6 +338,51 @@ impl<'source> Parser<'source> {
         }
     }
 
+    /// Takes in a source script and produces an Ast, recovering from errors where possible
+    ///
+    /// When an error is encountered, parsing resumes with the next top-level expression in the
+    /// script, i.e. the next line following the error that starts without indentation. This
+    /// allows multiple independent errors to be reported, which is useful for editor tooling.
+    ///
+    /// If no errors were encountered then the Ast is returned, otherwise all of the errors that
+    /// were found are returned, in the order that they appear in the script.
+    pub fn parse_with_recovery(
+        source: &'source str,
+        options: ParserOptions,
+    ) -> std::result::Result<Ast, Vec<Error>> {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect::<Vec<_>>();
+        let mut errors = Vec::new();
+        let mut first_line = 0;
+
+        loop {
+            let remaining = &source[line_starts[first_line]..];
+
+            match Self::parse_with_options(remaining, options.clone()) {
+                Ok(ast) if errors.is_empty() => return Ok(ast),
+                Ok(_) => break,
+                Err(mut error) => {
+                    // Make the error's span relative to the start of the full source
+                    error.span.start.line += first_line as u32;
+                    error.span.end.line += first_line as u32;
+                    let error_line = error.span.start.line as usize;
+                    errors.push(error);
+
+                    let next_line = (error_line + 1..line_starts.len())
+                        .find(|&line| is_top_level_expression_start(&source[line_starts[line]..]));
+
+                    match next_line {
+                        Some(line) => first_line = line,
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        Err(errors)
+    }
+
     // Parses the main 'top-level' block
     fn consume_main_block(&mut self) -> Result<AstIndex> {
         self.frame_stack.push(Frame::default());
@@ -487,8 +554,37 @@ impl<'source> Parser<'source> {
     }
 
     // Parses expressions from the start of a line
+    //
+    // Attributes that precede the line are attached to the line's expression.
     fn parse_line(&mut self, context: &ExpressionContext) -> Result<Option<AstIndex>> {
-        self.parse_expressions(context, TempResult::No)
+        let attribute_tokens = std::mem::take(&mut self.pending_attributes);
+
+        let Some(expression) = self.parse_expressions(context, TempResult::No)? else {
+            return Ok(None);
+        };
+
+        if attribute_tokens.is_empty() {
+            return Ok(Some(expression));
+        }
+
+        let attributes = attribute_tokens
+            .iter()
+            .map(|token| {
+                // Strip the `#[` and `]` delimiters
+                let slice = token.slice(self.source);
+                self.add_string_constant(slice[2..slice.len() - 1].trim())
+            })
+            .collect::<Result<_>>()?;
+
+        let span = *self.ast.span(self.ast.node(expression).span);
+        self.push_node_with_span(
+            Node::Attributed {
+                attributes,
+                expression,
+            },
+            span,
+        )
+        .map(Some)
     }
 
     // Parse a comma separated series of expressions
@@ -731,6 +827,12 @@ impl<'source> Parser<'source> {
                 Remainder => AstBinaryOp::Remainder,
                 Power => AstBinaryOp::Power,
 
+                BitwiseAnd => AstBinaryOp::BitwiseAnd,
+                BitwiseOr => AstBinaryOp::BitwiseOr,
+                BitwiseXor => AstBinaryOp::BitwiseXor,
+                ShiftLeft => AstBinaryOp::ShiftLeft,
+                ShiftRight => AstBinaryOp::ShiftRight,
+
                 AddAssign => AstBinaryOp::AddAssign,
                 SubtractAssign => AstBinaryOp::SubtractAssign,
                 MultiplyAssign => AstBinaryOp::MultiplyAssign,
@@ -888,8 +990,25 @@ impl<'source> Parser<'source> {
         Ok(())
     }
 
-    // Peeks the next token and dispatches to the relevant parsing functions
+    // Parses a term, checking that the maximum parse depth hasn't been exceeded
     fn parse_term(&mut self, context: &ExpressionContext) -> Result<Option<AstIndex>> {
+        // Terms at the top level have a depth of zero, so a term is within the limit as long as it
+        // has no more than `max_parse_depth` enclosing terms.
+        if let Some(max_depth) = self.options.max_parse_depth
+            && self.depth > max_depth
+        {
+            self.consume_token_with_context(context);
+            return self.error(SyntaxError::MaximumDepthExceeded(max_depth));
+        }
+
+        self.depth += 1;
+        let result = self.parse_term_inner(context);
+        self.depth -= 1;
+        result
+    }
+
+    // Peeks the next token and dispatches to the relevant parsing functions
+    fn parse_term_inner(&mut self, context: &ExpressionContext) -> Result<Option<AstIndex>> {
         use Node::*;
 
         let start_line = self.current_line();
@@ -901,8 +1020,10 @@ impl<'source> Parser<'source> {
 
         let result = match peeked.token {
             Token::Null => {
-                self.consume_token_with_context(context);
-                self.push_node(Null)
+                let (_, null_context) = self.consume_token_with_context(context).unwrap();
+                let null_node = self.push_node(Null)?;
+                // `null` can be used as the root of a chain, e.g. `null?.foo`
+                self.check_for_chain_after_node(null_node, &null_context)
             }
             Token::True => {
                 self.consume_token_with_context(context);
@@ -2278,11 +2399,8 @@ impl<'source> Parser<'source> {
         self.consume_next_token_on_same_line(); // Token::Throw
         let start_span = self.current_span();
 
-        if let Some(expression) = self.parse_expression(&ExpressionContext::permissive())? {
-            self.push_node_with_start_span(Node::Throw(expression), start_span)
-        } else {
-            self.consume_token_and_error(SyntaxError::ExpectedExpression)
-        }
+        let expression = self.parse_expression(&ExpressionContext::permissive())?;
+        self.push_node_with_start_span(Node::Throw(expression), start_span)
     }
 
     fn consume_debug_expression(&mut self) -> Result<AstIndex> {
@@ -2811,6 +2929,21 @@ impl<'source> Parser<'source> {
                     }
                     _ => return self.error(SyntaxError::ExpectedMetaId),
                 },
+                id @ ("get" | "set") => {
+                    let meta_key_id = if id == "get" {
+                        MetaKeyId::Get
+                    } else {
+                        MetaKeyId::Set
+                    };
+                    match self.consume_next_token_on_same_line() {
+                        Some(Token::Id) => {
+                            let name = self.add_current_slice_as_string_constant()?;
+                            meta_name = Some(name);
+                            meta_key_id
+                        }
+                        _ => return self.error(SyntaxError::ExpectedMetaPropertyName),
+                    }
+                }
                 _ => return self.error(SyntaxError::UnexpectedMetaKey),
             },
             _ => return self.error(SyntaxError::UnexpectedMetaKey),
@@ -3684,6 +3817,9 @@ impl<'source> Parser<'source> {
                     nodes.push(StringNode::Expression { expression, format });
                 }
                 StringEnd => {
+                    // The lexer produces a single literal for each run of text between
+                    // interpolated expressions, so adjacent literal nodes never need to be merged,
+                    // and strings without expressions can be stored as a single constant.
                     let contents = match nodes.as_slice() {
                         [] => StringContents::Literal(self.add_string_constant("")?),
                         [StringNode::Literal(literal)] => StringContents::Literal(*literal),
@@ -3916,6 +4052,19 @@ impl<'source> Parser<'source> {
 
     fn consume_token(&mut self) -> Option<Token> {
         if let Some(next) = self.lexer.next() {
+            match next.token {
+                // Trailing attributes are discarded rather than being attached to the expression
+                // on the following line.
+                Token::Attribute if self.line_has_expression => {}
+                Token::Attribute => self.pending_attributes.push(next.clone()),
+                Token::NewLine => self.line_has_expression = false,
+                // Attributes are only attached to the expression that immediately follows them
+                token if !token.is_whitespace_including_newline() => {
+                    self.pending_attributes.clear();
+                    self.line_has_expression = true;
+                }
+                _ => {}
+            }
             self.current_token = next;
             Some(self.current_token.token)
         } else {
@@ -3999,7 +4148,7 @@ impl<'source> Parser<'source> {
         while let Some(peeked) = self.lexer.peek(peek_count) {
             match peeked.token {
                 NewLine => same_line = false,
-                Whitespace | CommentMulti | CommentSingle => {}
+                Whitespace | CommentMulti | CommentSingle | Attribute => {}
                 token => {
                     let result = Some(PeekInfo {
                         token,
@@ -4193,6 +4342,20 @@ impl<'source> Parser<'source> {
     }
 }
 
+// Used by Parser::parse_with_recovery() to find a line where parsing can be resumed
+fn is_top_level_expression_start(line: &str) -> bool {
+    match line.chars().next() {
+        None => false,
+        Some(c) if c.is_whitespace() => false,
+        Some('#' | ')' | ']' | '}') => false,
+        // Continuations of multi-part expressions can't be used to resume parsing
+        Some(_) => !["else", "catch", "finally", "then"].iter().any(|keyword| {
+            line.strip_prefix(keyword)
+                .is_some_and(|rest| !rest.starts_with(is_id_continue))
+        }),
+    }
+}
+
 // Used by Parser::parse_expressions() to determine if comma-separated values should be stored in a
 // Tuple or a TempTuple.
 enum TempResult {
@@ -4223,10 +4386,15 @@ fn operator_precedence(op: Token) -> Option<(u8, u8)> {
         // Comparisons, right-associative
         Equal | NotEqual => (10, 9),
         Greater | GreaterOrEqual | Less | LessOrEqual => (12, 11),
+        // Bitwise operators, left-associative
+        BitwiseOr => (13, 14),
+        BitwiseXor => (15, 16),
+        BitwiseAnd => (17, 18),
+        ShiftLeft | ShiftRight => (19, 20),
         // Arithmetic operators, left-associative
-        Add | Subtract => (13, 14),
-        Multiply | Divide | Remainder => (15, 16),
-        Power => (17, 18),
+        Add | Subtract => (21, 22),
+        Multiply | Divide | Remainder => (23, 24),
+        Power => (25, 26),
         _ => return None,
     };
     Some(priority)
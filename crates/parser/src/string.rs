@@ -232,3 +232,28 @@ impl fmt::Debug for KString {
         f.write_str(self.as_str())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_bounds_shares_data() {
+        let s = KString::from("hello, world!");
+        let sub = s.with_bounds(7..12).unwrap();
+        assert_eq!(sub, "world");
+        assert_eq!(sub.as_ptr(), s[7..].as_ptr());
+
+        let sub_sub = sub.with_bounds(1..3).unwrap();
+        assert_eq!(sub_sub, "or");
+        assert_eq!(sub_sub.as_ptr(), s[8..].as_ptr());
+    }
+
+    #[test]
+    fn with_bounds_rejects_invalid_ranges() {
+        let s = KString::from("héllo");
+        assert!(s.with_bounds(0..2).is_none());
+        assert!(s.with_bounds(0..10).is_none());
+        assert!(s.with_bounds(0..3).unwrap() == "hé");
+    }
+}
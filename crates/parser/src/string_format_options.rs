@@ -18,6 +18,14 @@ pub struct StringFormatOptions {
     pub fill_character: Option<ConstantIndex>,
     /// An alternative representation that can be applied to the formatted string
     pub representation: Option<StringFormatRepresentation>,
+    /// True if a `+` sign should be displayed for positive numbers
+    pub show_sign: bool,
+    /// True if the digits of numbers should be grouped in thousands, separated by `,`
+    pub group_thousands: bool,
+    /// True if the representation's alternate form should be used
+    ///
+    /// e.g. integers formatted as hexadecimal will be prefixed with `0x`.
+    pub alternate_form: bool,
 }
 
 impl StringFormatOptions {
@@ -57,6 +65,18 @@ impl StringFormatOptions {
                     result.alignment = char_to_alignment(next);
                     position = MinWidth;
                 }
+                ('+', _, Start | MinWidth) if !result.show_sign => {
+                    result.show_sign = true;
+                    position = MinWidth;
+                }
+                ('#', _, Start | MinWidth) if !result.alternate_form => {
+                    result.alternate_form = true;
+                    position = MinWidth;
+                }
+                (',', _, Start | MinWidth | Precision) if !result.group_thousands => {
+                    result.group_thousands = true;
+                    position = Precision;
+                }
                 ('0', Some('0'..='9'), Start | MinWidth) => {
                     result.fill_character = Some(add_string_constant("0")?);
                     position = MinWidth;
@@ -112,6 +132,20 @@ impl StringFormatOptions {
             }
         }
 
+        if result.alternate_form
+            && !matches!(
+                result.representation,
+                Some(
+                    StringFormatRepresentation::Binary
+                        | StringFormatRepresentation::Octal
+                        | StringFormatRepresentation::HexLower
+                        | StringFormatRepresentation::HexUpper
+                )
+            )
+        {
+            return Err(StringFormatError::UnsupportedAlternateForm);
+        }
+
         Ok(result)
     }
 }
@@ -212,6 +246,8 @@ pub enum StringFormatError {
     InternalError,
     #[error("unexpected token '{0}'")]
     UnexpectedToken(char),
+    #[error("'#' can only be used with the 'b', 'o', 'x', and 'X' representations")]
+    UnsupportedAlternateForm,
 }
 
 #[cfg(test)]
@@ -323,6 +359,46 @@ mod tests {
         ])
     }
 
+    #[test]
+    fn sign_and_grouping() {
+        test_parse_format_string(&[
+            (
+                "+",
+                StringFormatOptions {
+                    show_sign: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ",",
+                StringFormatOptions {
+                    group_thousands: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ">+10,.2",
+                StringFormatOptions {
+                    alignment: StringAlignment::Right,
+                    show_sign: true,
+                    min_width: Some(10),
+                    group_thousands: true,
+                    precision: Some(2),
+                    ..Default::default()
+                },
+            ),
+            (
+                "+<5",
+                StringFormatOptions {
+                    alignment: StringAlignment::Left,
+                    fill_character: Some(0.into()),
+                    min_width: Some(5),
+                    ..Default::default()
+                },
+            ),
+        ])
+    }
+
     #[test]
     fn style() {
         test_parse_format_string(&[
@@ -354,6 +430,27 @@ mod tests {
                     ..Default::default()
                 },
             ),
+            (
+                "#010x",
+                StringFormatOptions {
+                    alternate_form: true,
+                    fill_character: Some(0.into()),
+                    min_width: Some(10),
+                    representation: Some(StringFormatRepresentation::HexLower),
+                    ..Default::default()
+                },
+            ),
         ]);
     }
+
+    #[test]
+    fn alternate_form_without_integer_representation() {
+        for options in ["#", "#?", "#e", "#10"] {
+            let mut constants = ConstantPoolBuilder::default();
+            assert!(matches!(
+                StringFormatOptions::parse(options, &mut constants),
+                Err(StringFormatError::UnsupportedAlternateForm)
+            ));
+        }
+    }
 }
@@ -37,11 +37,15 @@ where
     ///
     /// If the bounds aren't valid for the given string data then None is returned.
     pub fn new(string: Ptr<String>, bounds: Range<usize>) -> Option<Self> {
-        try_from_range(&bounds).map(|bounds| Self {
-            data: string,
-            bounds,
-            _niche: false,
-        })
+        if string.get(bounds.clone()).is_some() {
+            try_from_range(&bounds).map(|bounds| Self {
+                data: string,
+                bounds,
+                _niche: false,
+            })
+        } else {
+            None
+        }
     }
 
     /// Initializes a string slice with the given string data and bounds
@@ -219,11 +223,19 @@ mod test {
         assert_eq!(slice.as_str(), "bc");
     }
 
+    #[test]
+    fn new_with_invalid_bounds() {
+        let string: Ptr<String> = Ptr::from("héllo".to_string());
+        assert!(StringSlice::<usize>::new(string.clone(), 0..2).is_none());
+        assert!(StringSlice::<usize>::new(string, 0..10).is_none());
+    }
+
     #[test]
     fn with_bounds() {
         let original = StringSlice::from("0123456789");
         let slice = original.with_bounds(4..8).unwrap();
         assert_eq!(slice.as_str(), "4567");
+        assert!(Ptr::ptr_eq(&original.data, &slice.data));
     }
 
     #[test]
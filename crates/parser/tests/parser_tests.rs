@@ -447,6 +447,7 @@ null"#;
                                     precision: Some(2),
                                     fill_character: Some(2.into()),
                                     representation: Some(StringFormatRepresentation::HexLower),
+                                    ..Default::default()
                                 },
                             },
                             StringNode::Literal(0.into()),
@@ -901,6 +902,34 @@ x =
                 Some(&[Constant::Str("x"), Constant::Str("foo")]),
             )
         }
+
+        #[test]
+        fn map_block_property_getter_and_setter() {
+            let source = r#"
+x =
+  @get foo: 0
+  @set foo: 1
+"#;
+            check_ast(
+                source,
+                &[
+                    id(0), // x
+                    Meta(MetaKeyId::Get, Some(1.into())),
+                    SmallInt(0),
+                    map_entry(1, 2),
+                    Meta(MetaKeyId::Set, Some(1.into())),
+                    SmallInt(1), // 5
+                    map_entry(4, 5),
+                    map_block(&[3, 6]),
+                    assign(0, 7),
+                    MainBlock {
+                        body: nodes(&[8]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("foo")]),
+            )
+        }
     }
 
     mod ranges {
@@ -2447,6 +2476,74 @@ a = (1
         }
     }
 
+    mod bitwise {
+        use super::*;
+
+        #[test]
+        fn and_xor_or() {
+            let source = "1 ||| 2 ^^^ 3 &&& 4"; // 1 ||| (2 ^^^ (3 &&& 4))
+            check_ast(
+                source,
+                &[
+                    SmallInt(1),
+                    SmallInt(2),
+                    SmallInt(3),
+                    SmallInt(4),
+                    binary_op(AstBinaryOp::BitwiseAnd, 2, 3),
+                    binary_op(AstBinaryOp::BitwiseXor, 1, 4), // 5
+                    binary_op(AstBinaryOp::BitwiseOr, 0, 5),
+                    MainBlock {
+                        body: nodes(&[6]),
+                        local_count: 0,
+                    },
+                ],
+                None,
+            )
+        }
+
+        #[test]
+        fn shifts_with_arithmetic() {
+            let source = "1 << 2 + 3 >> 1"; // (1 << (2 + 3)) >> 1
+            check_ast(
+                source,
+                &[
+                    SmallInt(1),
+                    SmallInt(2),
+                    SmallInt(3),
+                    binary_op(AstBinaryOp::Add, 1, 2),
+                    binary_op(AstBinaryOp::ShiftLeft, 0, 3),
+                    SmallInt(1), // 5
+                    binary_op(AstBinaryOp::ShiftRight, 4, 5),
+                    MainBlock {
+                        body: nodes(&[6]),
+                        local_count: 0,
+                    },
+                ],
+                None,
+            )
+        }
+
+        #[test]
+        fn bitwise_and_with_comparison() {
+            let source = "2 &&& 1 == 0"; // (2 &&& 1) == 0
+            check_ast(
+                source,
+                &[
+                    SmallInt(2),
+                    SmallInt(1),
+                    binary_op(AstBinaryOp::BitwiseAnd, 0, 1),
+                    SmallInt(0),
+                    binary_op(AstBinaryOp::Equal, 2, 3),
+                    MainBlock {
+                        body: nodes(&[4]),
+                        local_count: 0,
+                    },
+                ],
+                None,
+            )
+        }
+    }
+
     mod control_flow {
         use super::*;
 
@@ -4225,6 +4322,28 @@ foo? 42
                     Some(&[Constant::Str("foo")]),
                 )
             }
+
+            #[test]
+            fn after_null() {
+                let source = "
+null?.foo.bar
+";
+                check_ast(
+                    source,
+                    &[
+                        Null,
+                        chain_id(1, None),
+                        chain_id(0, Some(1)),
+                        chain_null_check(2),
+                        chain_root(0, Some(3)),
+                        MainBlock {
+                            body: nodes(&[4]),
+                            local_count: 0,
+                        },
+                    ],
+                    Some(&[Constant::Str("foo"), Constant::Str("bar")]),
+                )
+            }
         }
     }
 
@@ -4336,6 +4455,119 @@ loop
         }
     }
 
+    mod attributes {
+        use super::*;
+
+        #[test]
+        fn attribute_preceding_assignment() {
+            let source = "\
+#[foo]
+a = 1";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    SmallInt(1),
+                    assign(0, 1),
+                    Attributed {
+                        attributes: constants(&[1]),
+                        expression: 2.into(),
+                    },
+                    MainBlock {
+                        body: nodes(&[3]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("a"), Constant::Str("foo")]),
+            )
+        }
+
+        #[test]
+        fn multiple_attributes_preceding_assignment() {
+            let source = "\
+#[foo] #[ bar: baz ]
+# A comment
+a = 1
+b = 2";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    SmallInt(1),
+                    assign(0, 1),
+                    Attributed {
+                        attributes: constants(&[1, 2]),
+                        expression: 2.into(),
+                    },
+                    id(3),
+                    SmallInt(2), // 5
+                    assign(4, 5),
+                    MainBlock {
+                        body: nodes(&[3, 6]),
+                        local_count: 2,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("a"),
+                    Constant::Str("foo"),
+                    Constant::Str("bar: baz"),
+                    Constant::Str("b"),
+                ]),
+            )
+        }
+
+        #[test]
+        fn trailing_attribute_isnt_attached_to_the_next_line() {
+            let source = "\
+a = 1 #[foo]
+b = 2";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    SmallInt(1),
+                    assign(0, 1),
+                    id(1),
+                    SmallInt(2),
+                    assign(3, 4), // 5
+                    MainBlock {
+                        body: nodes(&[2, 5]),
+                        local_count: 2,
+                    },
+                ],
+                Some(&[Constant::Str("a"), Constant::Str("b")]),
+            )
+        }
+
+        #[test]
+        fn attribute_following_a_trailing_attribute() {
+            let source = "\
+a = 1 #[foo]
+#[bar]
+b = 2";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    SmallInt(1),
+                    assign(0, 1),
+                    id(1),
+                    SmallInt(2),
+                    assign(3, 4), // 5
+                    Attributed {
+                        attributes: constants(&[2]),
+                        expression: 5.into(),
+                    },
+                    MainBlock {
+                        body: nodes(&[2, 6]),
+                        local_count: 2,
+                    },
+                ],
+                Some(&[Constant::Str("a"), Constant::Str("b"), Constant::Str("bar")]),
+            )
+        }
+    }
+
     mod import {
         use super::*;
 
@@ -4633,7 +4865,7 @@ catch x
                 source,
                 &[
                     id(0),
-                    Throw(0.into()),
+                    Throw(Some(0.into())),
                     MainBlock {
                         body: nodes(&[1]),
                         local_count: 0,
@@ -4643,6 +4875,22 @@ catch x
             )
         }
 
+        #[test]
+        fn throw_without_value() {
+            let source = "throw";
+            check_ast(
+                source,
+                &[
+                    Throw(None),
+                    MainBlock {
+                        body: nodes(&[0]),
+                        local_count: 0,
+                    },
+                ],
+                None,
+            )
+        }
+
         #[test]
         fn throw_string() {
             let source = "throw 'error!'";
@@ -4650,7 +4898,7 @@ catch x
                 source,
                 &[
                     string_literal(0, StringQuote::Single),
-                    Throw(0.into()),
+                    Throw(Some(0.into())),
                     MainBlock {
                         body: nodes(&[1]),
                         local_count: 0,
@@ -4677,7 +4925,7 @@ throw
                     string_literal(3, StringQuote::Double), // error!
                     map_entry(3, 4),                        // 5
                     map_block(&[2, 5]),
-                    Throw(6.into()),
+                    Throw(Some(6.into())),
                     MainBlock {
                         body: nodes(&[7]),
                         local_count: 0,
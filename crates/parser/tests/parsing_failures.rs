@@ -1,5 +1,5 @@
 mod parser {
-    use koto_parser::{Ast, ErrorKind, Parser, Position, Result, Span, SyntaxError};
+    use koto_parser::{Ast, ErrorKind, Parser, ParserOptions, Position, Result, Span, SyntaxError};
 
     fn check_parsing_result(
         result: Result<Ast>,
@@ -683,6 +683,12 @@ switch
                 check_parsing_fails("'{1 + }'");
             }
 
+            #[test]
+            fn alternate_form_without_integer_representation() {
+                check_parsing_fails("'{42:#}'");
+                check_parsing_fails("'{42:#.2e}'");
+            }
+
             #[test]
             fn multiline_template_expression() {
                 let source = "
@@ -746,5 +752,159 @@ switch
                 check_parsing_fails("const = 99");
             }
         }
+
+        // Errors are checked directly, so panicking on errors needs to be disabled
+        #[cfg(not(feature = "panic_on_parser_error"))]
+        mod error_recovery {
+            use super::*;
+
+            fn check_parsing_errors(source: &str, expected_lines: &[u32]) {
+                match Parser::parse_with_recovery(source, ParserOptions::default()) {
+                    Ok(ast) => panic!(
+                        "Unexpected success while parsing:\n{source}\n{:#?}",
+                        ast.nodes()
+                    ),
+                    Err(errors) => {
+                        let error_lines = errors
+                            .iter()
+                            .map(|error| error.span.start.line)
+                            .collect::<Vec<_>>();
+                        assert_eq!(error_lines, expected_lines);
+                    }
+                }
+            }
+
+            #[test]
+            fn two_independent_errors() {
+                let source = "\
+a = 1 + * 2
+b = 42
+c = x.(1)
+d = 99
+";
+                check_parsing_errors(source, &[0, 2]);
+            }
+
+            #[test]
+            fn error_in_indented_block() {
+                let source = "\
+f = |x|
+  y = x +* 1
+  y
+g = 42
+if true
+  x = )
+else
+  99
+";
+                check_parsing_errors(source, &[1, 5]);
+            }
+
+            #[test]
+            fn no_errors() {
+                let source = "\
+a = 1
+b = a + 1
+";
+                assert!(Parser::parse_with_recovery(source, ParserOptions::default()).is_ok());
+            }
+        }
+
+        // Errors are checked directly, so panicking on errors needs to be disabled
+        #[cfg(not(feature = "panic_on_parser_error"))]
+        mod nesting_depth {
+            use super::*;
+
+            fn nested_parens(depth: usize) -> String {
+                format!("{}1{}", "(".repeat(depth), ")".repeat(depth))
+            }
+
+            fn nested_lists(depth: usize) -> String {
+                format!("{}1{}", "[".repeat(depth), "]".repeat(depth))
+            }
+
+            fn nested_ifs(depth: usize) -> String {
+                let mut result = String::new();
+                for i in 0..depth {
+                    result.push_str(&format!("{}if x\n", "  ".repeat(i)));
+                }
+                result.push_str(&format!("{}1\n", "  ".repeat(depth)));
+                result
+            }
+
+            fn options_with_max_depth(max_depth: usize) -> ParserOptions {
+                ParserOptions {
+                    max_parse_depth: Some(max_depth),
+                    ..Default::default()
+                }
+            }
+
+            #[test]
+            fn no_limit_by_default() {
+                assert!(Parser::parse(&nested_parens(200)).is_ok());
+                assert!(Parser::parse(&nested_lists(200)).is_ok());
+                assert!(Parser::parse(&nested_ifs(100)).is_ok());
+            }
+
+            #[test]
+            fn nested_parens_at_max_depth() {
+                let source = nested_parens(10);
+                assert!(Parser::parse_with_options(&source, options_with_max_depth(10)).is_ok());
+            }
+
+            #[test]
+            fn nested_parens_exceeding_max_depth() {
+                let source = nested_parens(11);
+                check_parsing_result(
+                    Parser::parse_with_options(&source, options_with_max_depth(10)),
+                    &source,
+                    Some(SyntaxError::MaximumDepthExceeded(10).into()),
+                    Some(Span {
+                        start: Position {
+                            line: 0,
+                            column: 11,
+                        },
+                        end: Position {
+                            line: 0,
+                            column: 12,
+                        },
+                    }),
+                );
+            }
+
+            #[test]
+            fn nested_lists_at_max_depth() {
+                let source = nested_lists(10);
+                assert!(Parser::parse_with_options(&source, options_with_max_depth(10)).is_ok());
+            }
+
+            #[test]
+            fn nested_lists_exceeding_max_depth() {
+                let source = nested_lists(11);
+                check_parsing_result(
+                    Parser::parse_with_options(&source, options_with_max_depth(10)),
+                    &source,
+                    Some(SyntaxError::MaximumDepthExceeded(10).into()),
+                    None,
+                );
+            }
+
+            #[test]
+            fn nested_ifs_at_max_depth() {
+                let source = nested_ifs(10);
+                assert!(Parser::parse_with_options(&source, options_with_max_depth(10)).is_ok());
+            }
+
+            #[test]
+            fn nested_ifs_exceeding_max_depth() {
+                let source = nested_ifs(11);
+                check_parsing_result(
+                    Parser::parse_with_options(&source, options_with_max_depth(10)),
+                    &source,
+                    Some(SyntaxError::MaximumDepthExceeded(10).into()),
+                    None,
+                );
+            }
+        }
     }
 }
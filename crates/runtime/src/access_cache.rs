@@ -0,0 +1,69 @@
+use crate::{KValue, Ptr, ValueKey, ValueMap};
+use koto_bytecode::Chunk;
+
+// The number of cached access sites, must be a power of two
+const CACHE_SIZE: usize = 64;
+
+// A cached access site, containing the chunk and the ip of the access instruction,
+// along with the slot index of the entry that was found in the accessed map.
+//
+// Holding on to the chunk keeps it alive while it's in the cache, which is limited to
+// `CACHE_SIZE` chunks.
+#[derive(Clone)]
+struct CacheEntry {
+    chunk: Ptr<Chunk>,
+    ip: u32,
+    slot: usize,
+}
+
+/// An inline cache for `.` access operations on maps
+///
+/// Each access site (identified by its chunk and the ip of its instruction) remembers the slot
+/// index of the entry that was found during its most recent lookup. Subsequent accesses at the
+/// same site check the key stored in the cached slot, and if it matches then the hash lookup can
+/// be skipped.
+///
+/// Validating the key on every cache hit means that stale entries are never returned,
+/// e.g. after keys have been added or removed from a map, or when a different map is accessed.
+#[derive(Clone, Default)]
+pub(crate) struct AccessCache {
+    // Direct-mapped cache entries, allocated on first use
+    entries: Vec<Option<CacheEntry>>,
+}
+
+impl AccessCache {
+    /// Looks up a key in the given map, making use of the access site's cached slot if available
+    ///
+    /// The cache entry for the access site will be updated if the key is found in a new slot.
+    pub fn get(
+        &mut self,
+        chunk: &Ptr<Chunk>,
+        ip: u32,
+        map: &ValueMap,
+        key: &ValueKey,
+    ) -> Option<KValue> {
+        let cache_index = ip as usize & (CACHE_SIZE - 1);
+
+        if let Some(Some(entry)) = self.entries.get(cache_index)
+            && entry.ip == ip
+            && Ptr::ptr_eq(&entry.chunk, chunk)
+            && let Some((cached_key, value)) = map.get_index(entry.slot)
+            && cached_key == key
+        {
+            return Some(value.clone());
+        }
+
+        let (slot, _, value) = map.get_full(key)?;
+
+        if self.entries.is_empty() {
+            self.entries.resize(CACHE_SIZE, None);
+        }
+        self.entries[cache_index] = Some(CacheEntry {
+            chunk: chunk.clone(),
+            ip,
+            slot,
+        });
+
+        Some(value.clone())
+    }
+}
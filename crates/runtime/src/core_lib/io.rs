@@ -19,7 +19,7 @@ pub fn make_module() -> KMap {
     result.add_fn("create", {
         move |ctx| match ctx.args() {
             [Str(path)] => {
-                let path = Path::new(path.as_str()).to_path_buf();
+                let path = writable_path(ctx.vm, path)?;
                 match fs::File::create(&path) {
                     Ok(file) => Ok(File::system_file(file, path)),
                     Err(error) => runtime_error!("error while creating file: {error}"),
@@ -41,7 +41,10 @@ pub fn make_module() -> KMap {
     });
 
     result.add_fn("exists", |ctx| match ctx.args() {
-        [Str(path)] => Ok(Bool(fs::canonicalize(path.as_str()).is_ok())),
+        [Str(path)] => {
+            let path = readable_path(ctx.vm, path)?;
+            Ok(Bool(fs::canonicalize(path).is_ok()))
+        }
         unexpected => unexpected_args("|String|", unexpected),
     });
 
@@ -66,7 +69,7 @@ pub fn make_module() -> KMap {
 
     result.add_fn("open", {
         |ctx| match ctx.args() {
-            [Str(path)] => match fs::canonicalize(path.as_str()) {
+            [Str(path)] => match fs::canonicalize(readable_path(ctx.vm, path)?) {
                 Ok(path) => match fs::File::open(&path) {
                     Ok(file) => Ok(File::system_file(file, path)),
                     Err(error) => runtime_error!("error while opening path: {error}"),
@@ -104,7 +107,7 @@ pub fn make_module() -> KMap {
     });
 
     result.add_fn("read_to_string", |ctx| match ctx.args() {
-        [Str(path)] => match fs::read_to_string(Path::new(path.as_str())) {
+        [Str(path)] => match fs::read_to_string(readable_path(ctx.vm, path)?) {
             Ok(result) => Ok(result.into()),
             Err(error) => {
                 runtime_error!("io.read_to_string: Unable to read file '{path}': {error}")
@@ -116,8 +119,8 @@ pub fn make_module() -> KMap {
     result.add_fn("remove_file", {
         |ctx| match ctx.args() {
             [Str(path)] => {
-                let path = Path::new(path.as_str());
-                match fs::remove_file(path) {
+                let path = removable_path(ctx.vm, path)?;
+                match fs::remove_file(&path) {
                     Ok(_) => Ok(KValue::Null),
                     Err(error) => runtime_error!(
                         "io.remove_file: Error while removing file '{}': {error}",
@@ -141,6 +144,35 @@ pub fn make_module() -> KMap {
     result
 }
 
+// Returns the path to use when reading, checking the path against the VM's path permissions
+//
+// The path is returned as provided, with the canonicalized path only being used for the check.
+fn readable_path(vm: &KotoVm, path: &KString) -> Result<PathBuf> {
+    let path = Path::new(path.as_str());
+    if let Some(permissions) = vm.path_permissions() {
+        permissions.check_read(path)?;
+    }
+    Ok(path.to_path_buf())
+}
+
+// Returns the path to use when writing, checking the path against the VM's path permissions
+fn writable_path(vm: &KotoVm, path: &KString) -> Result<PathBuf> {
+    let path = Path::new(path.as_str());
+    if let Some(permissions) = vm.path_permissions() {
+        permissions.check_write(path)?;
+    }
+    Ok(path.to_path_buf())
+}
+
+// Returns the path to use when removing a file, checking the path against the VM's path permissions
+fn removable_path(vm: &KotoVm, path: &KString) -> Result<PathBuf> {
+    let path = Path::new(path.as_str());
+    if let Some(permissions) = vm.path_permissions() {
+        permissions.check_remove(path)?;
+    }
+    Ok(path.to_path_buf())
+}
+
 /// The File type used in the io module
 #[derive(Clone, KotoCopy, KotoType)]
 #[koto(runtime = crate)]
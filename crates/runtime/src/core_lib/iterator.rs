@@ -181,7 +181,7 @@ pub fn make_module() -> KMap {
     });
 
     result.add_fn("count", |ctx| {
-        let expected_error = "|Iterable|";
+        let expected_error = "|Iterable|, or |Iterable, |Any| -> Bool|";
 
         match ctx.instance_and_args(KValue::is_iterable, expected_error)? {
             (iterable, []) => {
@@ -195,6 +195,35 @@ pub fn make_module() -> KMap {
                 }
                 Ok(KValue::Number(result.into()))
             }
+            (iterable, [predicate]) if predicate.is_callable() => {
+                let iterable = iterable.clone();
+                let predicate = predicate.clone();
+                let mut result = 0;
+
+                for output in ctx.vm.make_iterator(iterable)? {
+                    let predicate_result = match output {
+                        Output::Value(value) => ctx.vm.call_function(predicate.clone(), value),
+                        Output::ValuePair(a, b) => ctx
+                            .vm
+                            .call_function(predicate.clone(), CallArgs::AsTuple(&[a, b])),
+                        Output::Error(error) => return Err(error),
+                    };
+
+                    match predicate_result {
+                        Ok(KValue::Bool(true)) => result += 1,
+                        Ok(KValue::Bool(false)) => {}
+                        Ok(unexpected) => {
+                            return unexpected_type(
+                                "a Bool to be returned from the predicate",
+                                &unexpected,
+                            );
+                        }
+                        Err(error) => return Err(error),
+                    }
+                }
+
+                Ok(KValue::Number(result.into()))
+            }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
         }
     });
@@ -213,6 +242,20 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("dedup", |ctx| {
+        let expected_error = "|Iterable|";
+
+        match ctx.instance_and_args(KValue::is_iterable, expected_error)? {
+            (iterable, []) => {
+                let iterable = iterable.clone();
+                let result = adaptors::Dedup::new(ctx.vm.make_iterator(iterable)?, ctx.vm);
+
+                Ok(KIterator::new(result).into())
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("each", |ctx| {
         let expected_error = "|Iterable, |Any| -> Any|";
 
@@ -300,33 +343,7 @@ pub fn make_module() -> KMap {
                 let iterable = iterable.clone();
                 let result = result.clone();
                 let f = f.clone();
-                let mut iter = ctx.vm.make_iterator(iterable)?;
-
-                match iter
-                    .borrow_internals(|iterator| {
-                        let mut fold_result = result.clone();
-                        for value in iterator.map(collect_pair) {
-                            match value {
-                                Output::Value(value) => {
-                                    match ctx.vm.call_function(f.clone(), &[fold_result, value]) {
-                                        Ok(result) => fold_result = result,
-                                        Err(error) => return Some(Output::Error(error)),
-                                    }
-                                }
-                                Output::Error(error) => return Some(Output::Error(error)),
-                                _ => unreachable!(),
-                            }
-                        }
-
-                        Some(Output::Value(fold_result))
-                    })
-                    // None is never returned from the closure
-                    .unwrap()
-                {
-                    Output::Value(result) => Ok(result),
-                    Output::Error(error) => Err(error),
-                    _ => unreachable!(),
-                }
+                fold(ctx.vm, iterable, result, f)
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
         }
@@ -354,6 +371,21 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("inspect", |ctx| {
+        let expected_error = "|Iterable, |Any| -> Any|";
+
+        match ctx.instance_and_args(KValue::is_iterable, expected_error)? {
+            (iterable, [f]) if f.is_callable() => {
+                let iterable = iterable.clone();
+                let f = f.clone();
+                let result = adaptors::Inspect::new(ctx.vm.make_iterator(iterable)?, f, ctx.vm);
+
+                Ok(KIterator::new(result).into())
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("intersperse", |ctx| {
         let expected_error = "|Iterable, Value|";
 
@@ -564,6 +596,10 @@ pub fn make_module() -> KMap {
             }
         };
 
+        if !iter.is_bidirectional() {
+            return runtime_error!("next_back can only be used with bidirectional iterators");
+        }
+
         let output = match iter_output_to_result(iter.next_back())? {
             None => KValue::Null,
             Some(output) => IteratorOutput::from(output).into(),
@@ -764,19 +800,7 @@ pub fn make_module() -> KMap {
         match ctx.instance_and_args(KValue::is_iterable, expected_error)? {
             (iterable, []) => {
                 let iterable = iterable.clone();
-                let iterator = ctx.vm.make_iterator(iterable)?;
-                let (size_hint, _) = iterator.size_hint();
-                let mut result = ValueVec::with_capacity(size_hint);
-
-                for output in iterator.map(collect_pair) {
-                    match output {
-                        Output::Value(value) => result.push(value),
-                        Output::Error(error) => return Err(error),
-                        _ => unreachable!(),
-                    }
-                }
-
-                Ok(KValue::List(KList::with_data(result)))
+                collect_list(make_collectable_iterator(ctx.vm, iterable)?)
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
         }
@@ -788,9 +812,8 @@ pub fn make_module() -> KMap {
         match ctx.instance_and_args(KValue::is_iterable, expected_error)? {
             (iterable, []) => {
                 let iterable = iterable.clone();
-                let iterator = ctx.vm.make_iterator(iterable)?;
-                let (size_hint, _) = iterator.size_hint();
-                let mut result = ValueMap::with_capacity(size_hint);
+                let iterator = make_collectable_iterator(ctx.vm, iterable)?;
+                let mut result = ValueMap::with_capacity(capacity_hint(&iterator));
 
                 for output in iterator {
                     let (key, value) = match output {
@@ -819,9 +842,9 @@ pub fn make_module() -> KMap {
         match ctx.instance_and_args(KValue::is_iterable, expected_error)? {
             (iterable, []) => {
                 let iterable = iterable.clone();
-                let iterator = ctx.vm.make_iterator(iterable)?;
-                let (size_hint, _) = iterator.size_hint();
-                let mut display_context = DisplayContext::with_vm_and_capacity(ctx.vm, size_hint);
+                let iterator = make_collectable_iterator(ctx.vm, iterable)?;
+                let capacity = capacity_hint(&iterator);
+                let mut display_context = DisplayContext::with_vm_and_capacity(ctx.vm, capacity);
                 for output in iterator.map(collect_pair) {
                     match output {
                         Output::Value(KValue::Str(s)) => display_context.append(s),
@@ -843,9 +866,8 @@ pub fn make_module() -> KMap {
         match ctx.instance_and_args(KValue::is_iterable, expected_error)? {
             (iterable, []) => {
                 let iterable = iterable.clone();
-                let iterator = ctx.vm.make_iterator(iterable)?;
-                let (size_hint, _) = iterator.size_hint();
-                let mut result = Vec::with_capacity(size_hint);
+                let iterator = make_collectable_iterator(ctx.vm, iterable)?;
+                let mut result = Vec::with_capacity(capacity_hint(&iterator));
 
                 for output in iterator.map(collect_pair) {
                     match output {
@@ -897,6 +919,75 @@ pub fn make_module() -> KMap {
     result
 }
 
+// Collects the iterator's output into a list
+pub(crate) fn collect_list(iterator: KIterator) -> Result<KValue> {
+    let mut result = ValueVec::with_capacity(capacity_hint(&iterator));
+
+    for output in iterator.map(collect_pair) {
+        match output {
+            Output::Value(value) => result.push(value),
+            Output::Error(error) => return Err(error),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(KValue::List(KList::with_data(result)))
+}
+
+// The maximum capacity that will be reserved up front when collecting an iterator's output
+const MAX_CAPACITY_HINT: usize = 1 << 16;
+
+// Returns the capacity to reserve when collecting the iterator's output
+//
+// The iterator's lower bound is clamped to avoid reserving excessive amounts of memory for
+// iterators that report large size hints.
+pub(crate) fn capacity_hint(iterator: &KIterator) -> usize {
+    iterator.size_hint().0.min(MAX_CAPACITY_HINT)
+}
+
+// Makes an iterator whose output will be collected into a container
+//
+// Ranges without an end would produce values until memory runs out, so they're rejected.
+pub(crate) fn make_collectable_iterator(vm: &mut KotoVm, iterable: KValue) -> Result<KIterator> {
+    match &iterable {
+        KValue::Range(r) if r.end().is_none() => {
+            runtime_error!("unbounded ranges can't be collected (range: {r})")
+        }
+        _ => vm.make_iterator(iterable),
+    }
+}
+
+// Folds the iterable's output into a single value, see `iterator.fold`
+pub(crate) fn fold(vm: &mut KotoVm, iterable: KValue, result: KValue, f: KValue) -> Result<KValue> {
+    let mut iter = vm.make_iterator(iterable)?;
+
+    match iter
+        .borrow_internals(|iterator| {
+            let mut fold_result = result.clone();
+            for value in iterator.map(collect_pair) {
+                match value {
+                    Output::Value(value) => {
+                        match vm.call_function(f.clone(), &[fold_result, value]) {
+                            Ok(result) => fold_result = result,
+                            Err(error) => return Some(Output::Error(error)),
+                        }
+                    }
+                    Output::Error(error) => return Some(Output::Error(error)),
+                    _ => unreachable!(),
+                }
+            }
+
+            Some(Output::Value(fold_result))
+        })
+        // None is never returned from the closure
+        .unwrap()
+    {
+        Output::Value(result) => Ok(result),
+        Output::Error(error) => Err(error),
+        _ => unreachable!(),
+    }
+}
+
 pub(crate) fn collect_pair(iterator_output: Output) -> Output {
     match iterator_output {
         Output::ValuePair(first, second) => {
@@ -220,6 +220,88 @@ impl Iterator for Cycle {
     }
 }
 
+/// An iterator that skips over output values that are equal to the previous output value
+pub struct Dedup {
+    iter: KIterator,
+    previous: Option<KValue>,
+    vm: KotoVm,
+    error_frame: InstructionFrame,
+}
+
+impl Dedup {
+    /// Creates a new [Dedup] adaptor
+    pub fn new(iter: KIterator, vm: &KotoVm) -> Self {
+        Self {
+            iter,
+            previous: None,
+            vm: vm.spawn_shared_vm(),
+            error_frame: vm.instruction_frame(),
+        }
+    }
+}
+
+impl KotoIterator for Dedup {
+    fn make_copy(&self) -> Result<KIterator> {
+        let result = Self {
+            iter: self.iter.make_copy()?,
+            previous: self.previous.clone(),
+            vm: self.vm.spawn_shared_vm(),
+            error_frame: self.error_frame.clone(),
+        };
+        Ok(KIterator::new(result))
+    }
+}
+
+impl Iterator for Dedup {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for output in &mut self.iter {
+            let value = match collect_pair(output.clone()) {
+                Output::Value(value) => value,
+                error => return Some(error),
+            };
+
+            if let Some(previous) = self.previous.take() {
+                match self
+                    .vm
+                    .run_binary_op(BinaryOp::Equal, previous.clone(), value.clone())
+                {
+                    Ok(KValue::Bool(true)) => {
+                        self.previous = Some(previous);
+                        continue;
+                    }
+                    Ok(KValue::Bool(false)) => {}
+                    Ok(unexpected) => {
+                        let error = Error::with_error_frame(
+                            ErrorKind::UnexpectedType {
+                                expected: "Bool from the comparison".into(),
+                                unexpected,
+                            },
+                            self.error_frame.clone(),
+                        );
+                        return Some(Output::Error(error));
+                    }
+                    Err(mut error) => {
+                        error.extend_trace(self.error_frame.clone());
+                        return Some(Output::Error(error));
+                    }
+                }
+            }
+
+            self.previous = Some(value);
+            return Some(output);
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (lower.min(1), upper)
+    }
+}
+
 /// An iterator that runs a function on each output value from the adapted iterator
 pub struct Each {
     iter: KIterator,
@@ -398,6 +480,79 @@ impl Iterator for Flatten {
     }
 }
 
+/// An iterator that calls a function with each output value from the adapted iterator,
+/// and then passes the value on unchanged
+pub struct Inspect {
+    iter: KIterator,
+    function: KValue,
+    vm: KotoVm,
+    error_frame: InstructionFrame,
+}
+
+impl Inspect {
+    /// Creates a new [Inspect] adaptor
+    pub fn new(iter: KIterator, function: KValue, vm: &KotoVm) -> Self {
+        Self {
+            iter,
+            function,
+            vm: vm.spawn_shared_vm(),
+            error_frame: vm.instruction_frame(),
+        }
+    }
+
+    fn inspect_output(&mut self, output: Output) -> Output {
+        let function = self.function.clone();
+        let functor_result = match &output {
+            Output::Value(value) => self.vm.call_function(function, value.clone()),
+            Output::ValuePair(a, b) => self
+                .vm
+                .call_function(function, CallArgs::AsTuple(&[a.clone(), b.clone()])),
+            Output::Error(_) => return output,
+        };
+        match functor_result {
+            Ok(_) => output,
+            Err(mut error) => {
+                error.extend_trace(self.error_frame.clone());
+                Output::Error(error)
+            }
+        }
+    }
+}
+
+impl KotoIterator for Inspect {
+    fn make_copy(&self) -> Result<KIterator> {
+        let result = Self {
+            iter: self.iter.make_copy()?,
+            function: self.function.clone(),
+            vm: self.vm.spawn_shared_vm(),
+            error_frame: self.error_frame.clone(),
+        };
+        Ok(KIterator::new(result))
+    }
+
+    fn is_bidirectional(&self) -> bool {
+        self.iter.is_bidirectional()
+    }
+
+    fn next_back(&mut self) -> Option<Output> {
+        self.iter
+            .next_back()
+            .map(|output| self.inspect_output(output))
+    }
+}
+
+impl Iterator for Inspect {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|output| self.inspect_output(output))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
 /// An iterator that inserts a separator value between each output value from the adapted iterator
 pub struct Intersperse {
     iter: KIterator,
@@ -1,11 +1,14 @@
 //! The `koto` core library module
 
+use super::value_sort::compare_values_across_types;
 use crate::Result;
 use crate::prelude::*;
+use crate::types::meta_key_from_str;
 use koto_bytecode::CompilerSettings;
 use koto_derive::{KotoCopy, KotoType};
 use koto_memory::Ptr;
 use std::{
+    cmp::Ordering,
     hash::{Hash, Hasher},
     path::Path,
 };
@@ -14,9 +17,22 @@ use std::{
 pub fn make_module() -> KMap {
     let result = KMap::with_type("core.koto");
 
+    result.add_fn("compare", |ctx| match ctx.args() {
+        [a, b] => {
+            let (a, b) = (a.clone(), b.clone());
+            let result = match compare_values_across_types(ctx.vm, &a, &b)? {
+                Ordering::Less => -1,
+                Ordering::Equal => 0,
+                Ordering::Greater => 1,
+            };
+            Ok(result.into())
+        }
+        unexpected => unexpected_args("|Any, Any|", unexpected),
+    });
+
     result.add_fn("copy", |ctx| match ctx.args() {
         [KValue::Iterator(iter)] => Ok(iter.make_copy()?.into()),
-        [KValue::List(l)] => Ok(KList::with_data(l.data().clone()).into()),
+        [KValue::List(l)] => Ok(KList::from_slice(&l.data()).into()),
         [KValue::Map(m)] => {
             let result = KMap::with_contents(
                 m.data().clone(),
@@ -34,6 +50,42 @@ pub fn make_module() -> KMap {
         unexpected => unexpected_args("|Any|", unexpected),
     });
 
+    result.add_fn("deep_freeze", |ctx| match ctx.args() {
+        [value @ (KValue::List(_) | KValue::Map(_))] => Ok(value.deep_frozen_copy()),
+        unexpected => unexpected_args("|List|, or |Map|", unexpected),
+    });
+
+    result.add_fn("error", |ctx| {
+        let (message, data) = match ctx.args() {
+            [KValue::Str(message)] => (message, KValue::Null),
+            [KValue::Str(message), data] => (message, data.clone()),
+            unexpected => return unexpected_args("|String|, or |String, Any|", unexpected),
+        };
+
+        let error = KMap::with_type("Error");
+        error.insert("message", message.clone());
+        error.insert("data", data);
+
+        Ok(error.into())
+    });
+
+    result.add_fn("freeze", |ctx| match ctx.args() {
+        [KValue::List(l)] => {
+            let result = KList::from_slice(&l.data());
+            result.freeze();
+            Ok(result.into())
+        }
+        [KValue::Map(m)] => {
+            let result = KMap::with_contents(
+                m.data().clone(),
+                m.meta_map().map(|meta| meta.borrow().clone()),
+            );
+            result.freeze();
+            Ok(result.into())
+        }
+        unexpected => unexpected_args("|List|, or |Map|", unexpected),
+    });
+
     result.add_fn("hash", |ctx| match ctx.args() {
         [value] => match ValueKey::try_from(value.clone()) {
             Ok(key) => {
@@ -46,6 +98,62 @@ pub fn make_module() -> KMap {
         unexpected => unexpected_args("|Any|", unexpected),
     });
 
+    result.add_fn("is_empty", |ctx| match ctx.args() {
+        [KValue::Null] => Ok(false.into()),
+        [value] => match ctx.vm.run_unary_op(UnaryOp::Size, value.clone())? {
+            KValue::Number(size) => Ok((size == 0).into()),
+            unexpected => unexpected_type("Number as @size result", &unexpected),
+        },
+        unexpected => unexpected_args("|Any|", unexpected),
+    });
+
+    result.add_fn("is_frozen", |ctx| match ctx.args() {
+        [KValue::List(l)] => Ok(l.is_frozen().into()),
+        [KValue::Map(m)] => Ok(m.is_frozen().into()),
+        [_] => Ok(false.into()),
+        unexpected => unexpected_args("|Any|", unexpected),
+    });
+
+    result.add_fn("is_null", |ctx| match ctx.args() {
+        [value] => Ok(matches!(value, KValue::Null).into()),
+        unexpected => unexpected_args("|Any|", unexpected),
+    });
+
+    result.add_fn("meta", |ctx| match ctx.args() {
+        [KValue::Map(m), KValue::Str(key)] => {
+            let key = meta_key_from_str(key)?;
+            Ok(m.get_meta_value(&key).unwrap_or_default())
+        }
+        unexpected => unexpected_args("|Map, String|", unexpected),
+    });
+
+    result.add_fn("pretty", |ctx| {
+        let (value, indent) = match ctx.args() {
+            [value] => (value, 2),
+            [value, KValue::Number(n)] if *n >= 0.0 => (value, usize::from(n)),
+            unexpected => return unexpected_args("|Any|, or |Any, Number|", unexpected),
+        };
+
+        let mut display_context = DisplayContext::with_vm(ctx.vm).with_pretty_indent(indent);
+        value.display(&mut display_context)?;
+        Ok(display_context.result().into())
+    });
+
+    result.add_fn("range", |ctx| {
+        let expected_error = "|Number?, Number?|, or |Number?, Number?, Bool|";
+
+        let (start, end, inclusive) = match ctx.args() {
+            [start, end] => (start, end, false),
+            [start, end, KValue::Bool(inclusive)] => (start, end, *inclusive),
+            unexpected => return unexpected_args(expected_error, unexpected),
+        };
+
+        let start = range_bound(start, "start")?;
+        let end = range_bound(end, "end")?.map(|end| (end, inclusive));
+
+        Ok(KRange::new(start, end).into())
+    });
+
     result.add_fn("script_dir", |ctx| {
         let result = match &ctx.vm.chunk().path {
             Some(script_path) => Path::new(script_path.as_str())
@@ -71,13 +179,58 @@ pub fn make_module() -> KMap {
         unexpected => unexpected_args("|Any|", unexpected),
     });
 
+    result.add_fn("tap", |ctx| {
+        let (value, label) = match ctx.args() {
+            [value] => (value.clone(), None),
+            [value, KValue::Str(label)] => (value.clone(), Some(label.clone())),
+            unexpected => return unexpected_args("|Any|, or |Any, String|", unexpected),
+        };
+
+        let value_string = match ctx.vm.run_unary_op(UnaryOp::Debug, value.clone())? {
+            KValue::Str(s) => s,
+            unexpected => return unexpected_type("a displayable value", &unexpected),
+        };
+
+        let output = match label {
+            Some(label) => format!("{label}: {value_string}"),
+            None => value_string.to_string(),
+        };
+        ctx.vm.stdout().write_line(&output)?;
+
+        Ok(value)
+    });
+
+    result.add_fn("to_string", |ctx| match ctx.args() {
+        [value] => match ctx.vm.run_unary_op(UnaryOp::Display, value.clone())? {
+            result @ KValue::Str(_) => Ok(result),
+            unexpected => unexpected_type("String as @display result", &unexpected),
+        },
+        unexpected => unexpected_args("|Any|", unexpected),
+    });
+
     result.add_fn("type", |ctx| match ctx.args() {
-        [value] => Ok(value.type_as_string().into()),
+        [value] => Ok(ctx.vm.type_name(value).into()),
         unexpected => unexpected_args("|Any|", unexpected),
     });
 
     result.insert("unimplemented", KObject::from(Unimplemented));
 
+    result.add_fn("with_meta_key", |ctx| match ctx.args() {
+        [KValue::Map(m), KValue::Str(key), value] => {
+            let key = meta_key_from_str(key)?;
+            let mut meta = m
+                .meta_map()
+                .map(|meta| meta.borrow().clone())
+                .unwrap_or_default();
+            meta.insert(key, value.clone());
+
+            let mut result = m.clone();
+            result.set_meta_map(Some(meta.into()));
+            Ok(result.into())
+        }
+        unexpected => unexpected_args("|Map, String, Any|", unexpected),
+    });
+
     result.add_fn("load", |ctx| match ctx.args() {
         [KValue::Str(s)] => Ok(try_load_koto_script(ctx, s)?.into()),
         unexpected => unexpected_args("|String|", unexpected),
@@ -97,6 +250,18 @@ pub fn make_module() -> KMap {
     result
 }
 
+// Converts a value into an optional integer range bound, with `null` producing an unbounded range
+fn range_bound(value: &KValue, bound: &str) -> Result<Option<i64>> {
+    match value {
+        KValue::Null => Ok(None),
+        KValue::Number(n) if n.is_i64() || f64::from(n).fract() == 0.0 => Ok(Some(n.into())),
+        KValue::Number(n) => {
+            runtime_error!("expected an integer for the range's {bound}, found {n}")
+        }
+        unexpected => unexpected_type(&format!("an integer for the range's {bound}"), unexpected),
+    }
+}
+
 fn try_load_koto_script(ctx: &CallContext<'_>, script: &str) -> Result<Chunk> {
     let chunk =
         ctx.vm
@@ -1,10 +1,12 @@
 //! The `list` core library module
 
 use super::{
-    iterator::collect_pair,
+    iterator::{
+        adaptors, capacity_hint, collect_list, collect_pair, fold, make_collectable_iterator,
+    },
     value_sort::{sort_by_key, sort_values},
 };
-use crate::prelude::*;
+use crate::{Result, prelude::*};
 use std::{cmp::Ordering, ops::DerefMut};
 
 /// Initializes the `list` core library module
@@ -16,7 +18,7 @@ pub fn make_module() -> KMap {
 
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(l), []) => {
-                l.data_mut().clear();
+                l.data_mut_checked()?.clear();
                 Ok(KValue::List(l.clone()))
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
@@ -52,27 +54,50 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("count", |ctx| {
+        let expected_error = "|List|, or |List, |Any| -> Bool|";
+
+        match ctx.instance_and_args(is_list, expected_error)? {
+            (KValue::List(l), []) => Ok(l.len().into()),
+            (KValue::List(l), [predicate]) if predicate.is_callable() => {
+                let l = l.clone();
+                let predicate = predicate.clone();
+
+                let mut result = 0;
+                let mut index = 0;
+                while let Some(value) = l.data().get(index).cloned() {
+                    if call_predicate(ctx.vm, &predicate, value)? {
+                        result += 1;
+                    }
+                    index += 1;
+                }
+
+                Ok(result.into())
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("extend", |ctx| {
         let expected_error = "|List, Iterable|";
 
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(l), [KValue::List(other)]) => {
-                l.data_mut().extend(other.data().iter().cloned());
+                l.data_mut_checked()?.extend(other.data().iter().cloned());
                 Ok(KValue::List(l.clone()))
             }
             (KValue::List(l), [KValue::Tuple(other)]) => {
-                l.data_mut().extend(other.iter().cloned());
+                l.data_mut_checked()?.extend(other.iter().cloned());
                 Ok(KValue::List(l.clone()))
             }
             (KValue::List(l), [iterable]) if iterable.is_iterable() => {
                 let l = l.clone();
                 let iterable = iterable.clone();
-                let iterator = ctx.vm.make_iterator(iterable)?;
+                let iterator = make_collectable_iterator(ctx.vm, iterable)?;
 
                 {
-                    let mut list_data = l.data_mut();
-                    let (size_hint, _) = iterator.size_hint();
-                    list_data.reserve(size_hint);
+                    let mut list_data = l.data_mut_checked()?;
+                    list_data.reserve(capacity_hint(&iterator));
 
                     for value in iterator.map(collect_pair) {
                         match value {
@@ -94,7 +119,7 @@ pub fn make_module() -> KMap {
 
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(l), [value]) => {
-                for v in l.data_mut().iter_mut() {
+                for v in l.data_mut_checked()?.iter_mut() {
                     *v = value.clone();
                 }
                 Ok(KValue::List(l.clone()))
@@ -103,6 +128,43 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("filter", |ctx| {
+        let expected_error = "|List, |Any| -> Bool|";
+
+        match ctx.instance_and_args(is_list, expected_error)? {
+            (KValue::List(l), [predicate]) if predicate.is_callable() => {
+                let l = l.clone();
+                let predicate = predicate.clone();
+                let iterator =
+                    adaptors::Keep::new(ctx.vm.make_iterator(l.into())?, predicate, ctx.vm);
+                collect_list(KIterator::new(iterator))
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
+    result.add_fn("find_index", |ctx| {
+        let expected_error = "|List, |Any| -> Bool|";
+
+        match ctx.instance_and_args(is_list, expected_error)? {
+            (KValue::List(l), [predicate]) if predicate.is_callable() => {
+                let l = l.clone();
+                let predicate = predicate.clone();
+
+                let mut index = 0;
+                while let Some(value) = l.data().get(index).cloned() {
+                    if call_predicate(ctx.vm, &predicate, value)? {
+                        return Ok(index.into());
+                    }
+                    index += 1;
+                }
+
+                Ok(KValue::Null)
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("first", |ctx| {
         let expected_error = "|List|";
 
@@ -115,6 +177,41 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("fold", |ctx| {
+        let expected_error = "|List, Any, |Any, Any| -> Any|";
+
+        match ctx.instance_and_args(is_list, expected_error)? {
+            (KValue::List(l), [result, f]) if f.is_callable() => {
+                let l = l.clone();
+                let result = result.clone();
+                let f = f.clone();
+                fold(ctx.vm, l.into(), result, f)
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
+    result.add_fn("for_each", |ctx| {
+        let expected_error = "|List, |Any| -> Any|";
+
+        match ctx.instance_and_args(is_list, expected_error)? {
+            (KValue::List(l), [f]) if f.is_callable() => {
+                let l = l.clone();
+                let f = f.clone();
+
+                // The list is indexed on each step in case it gets modified by the function
+                let mut index = 0;
+                while let Some(value) = l.data().get(index).cloned() {
+                    ctx.vm.call_function(f.clone(), value)?;
+                    index += 1;
+                }
+
+                Ok(KValue::List(l))
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("get", |ctx| {
         let (list, index, default) = {
             use KValue::{List, Null, Number};
@@ -129,13 +226,12 @@ pub fn make_module() -> KMap {
             }
         };
 
-        if *index >= 0 {
-            match list.data().get(usize::from(index)) {
-                Some(value) => Ok(value.clone()),
-                None => Ok(default),
-            }
-        } else {
-            Ok(default)
+        match index
+            .resolve_index(list.len())
+            .and_then(|index| list.data().get(index).cloned())
+        {
+            Some(value) => Ok(value),
+            None => Ok(default),
         }
     });
 
@@ -149,7 +245,7 @@ pub fn make_module() -> KMap {
                     return runtime_error!("index out of bounds");
                 }
 
-                l.data_mut().insert(index, value.clone());
+                l.data_mut_checked()?.insert(index, value.clone());
                 Ok(KValue::List(l.clone()))
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
@@ -177,11 +273,25 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("map", |ctx| {
+        let expected_error = "|List, |Any| -> Any|";
+
+        match ctx.instance_and_args(is_list, expected_error)? {
+            (KValue::List(l), [f]) if f.is_callable() => {
+                let l = l.clone();
+                let f = f.clone();
+                let iterator = adaptors::Each::new(ctx.vm.make_iterator(l.into())?, f, ctx.vm);
+                collect_list(KIterator::new(iterator))
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("pop", |ctx| {
         let expected_error = "|List|";
 
         match ctx.instance_and_args(is_list, expected_error)? {
-            (KValue::List(l), []) => match l.data_mut().pop() {
+            (KValue::List(l), []) => match l.data_mut_checked()?.pop() {
                 Some(value) => Ok(value),
                 None => Ok(KValue::Null),
             },
@@ -194,7 +304,7 @@ pub fn make_module() -> KMap {
 
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(l), [value]) => {
-                l.data_mut().push(value.clone());
+                l.data_mut_checked()?.push(value.clone());
                 Ok(KValue::List(l.clone()))
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
@@ -211,7 +321,7 @@ pub fn make_module() -> KMap {
                     return runtime_error!("index out of bounds");
                 }
 
-                Ok(l.data_mut().remove(index))
+                Ok(l.data_mut_checked()?.remove(index))
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
         }
@@ -225,11 +335,11 @@ pub fn make_module() -> KMap {
                 runtime_error!("expected a non-negative size")
             }
             (KValue::List(l), [KValue::Number(n)]) => {
-                l.data_mut().resize(n.into(), KValue::Null);
+                l.data_mut_checked()?.resize(n.into(), KValue::Null);
                 Ok(KValue::List(l.clone()))
             }
             (KValue::List(l), [KValue::Number(n), value]) => {
-                l.data_mut().resize(n.into(), value.clone());
+                l.data_mut_checked()?.resize(n.into(), value.clone());
                 Ok(KValue::List(l.clone()))
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
@@ -251,12 +361,12 @@ pub fn make_module() -> KMap {
                 let f = f.clone();
 
                 match len.cmp(&new_size) {
-                    Ordering::Greater => l.data_mut().truncate(new_size),
+                    Ordering::Greater => l.data_mut_checked()?.truncate(new_size),
                     Ordering::Less => {
-                        l.data_mut().reserve(new_size);
+                        l.data_mut_checked()?.reserve(new_size);
                         for _ in 0..new_size - len {
                             let new_value = ctx.vm.call_function(f.clone(), &[])?;
-                            l.data_mut().push(new_value);
+                            l.data_mut_checked()?.push(new_value);
                         }
                     }
                     Ordering::Equal => {}
@@ -283,7 +393,7 @@ pub fn make_module() -> KMap {
                         match ctx.vm.call_function(f.clone(), value.clone()) {
                             Ok(KValue::Bool(result)) => {
                                 if result {
-                                    l.data_mut()[write_index] = value;
+                                    l.data_mut_checked()?[write_index] = value;
                                     write_index += 1;
                                 }
                             }
@@ -296,7 +406,7 @@ pub fn make_module() -> KMap {
                             Err(error) => return Err(error),
                         }
                     }
-                    l.data_mut().resize(write_index, KValue::Null);
+                    l.data_mut_checked()?.resize(write_index, KValue::Null);
                     l
                 }
                 (KValue::List(l), [value]) => {
@@ -304,7 +414,7 @@ pub fn make_module() -> KMap {
                     let value = value.clone();
 
                     let mut error = None;
-                    l.data_mut().retain(|x| {
+                    l.data_mut_checked()?.retain(|x| {
                         if error.is_some() {
                             return true;
                         }
@@ -346,7 +456,7 @@ pub fn make_module() -> KMap {
 
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(l), []) => {
-                l.data_mut().reverse();
+                l.data_mut_checked()?.reverse();
                 Ok(KValue::List(l.clone()))
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
@@ -359,7 +469,7 @@ pub fn make_module() -> KMap {
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(l), []) => {
                 let l = l.clone();
-                let mut data = l.data_mut();
+                let mut data = l.data_mut_checked()?;
                 sort_values(ctx.vm, &mut data)?;
                 Ok(KValue::List(l.clone()))
             }
@@ -368,7 +478,9 @@ pub fn make_module() -> KMap {
 
                 let sorted = sort_by_key(ctx.vm, l.data().as_ref(), f.clone())?;
 
-                for (target_value, (_key, source_value)) in l.data_mut().iter_mut().zip(sorted) {
+                for (target_value, (_key, source_value)) in
+                    l.data_mut_checked()?.iter_mut().zip(sorted)
+                {
                     *target_value = source_value;
                 }
 
@@ -383,7 +495,10 @@ pub fn make_module() -> KMap {
 
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(a), [KValue::List(b)]) => {
-                std::mem::swap(a.data_mut().deref_mut(), b.data_mut().deref_mut());
+                std::mem::swap(
+                    a.data_mut_checked()?.deref_mut(),
+                    b.data_mut_checked()?.deref_mut(),
+                );
                 Ok(KValue::Null)
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
@@ -394,7 +509,7 @@ pub fn make_module() -> KMap {
         let expected_error = "|List|";
 
         match ctx.instance_and_args(is_list, expected_error)? {
-            (KValue::List(l), []) => Ok(KValue::Tuple(l.data().as_slice().into())),
+            (KValue::List(l), []) => Ok(KValue::Tuple(KTuple::from(&*l.data()))),
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
         }
     });
@@ -407,7 +522,7 @@ pub fn make_module() -> KMap {
                 let l = l.clone();
                 let f = f.clone();
 
-                for value in l.data_mut().iter_mut() {
+                for value in l.data_mut_checked()?.iter_mut() {
                     *value = match ctx.vm.call_function(f.clone(), value.clone()) {
                         Ok(result) => result,
                         Err(error) => return Err(error),
@@ -426,3 +541,11 @@ pub fn make_module() -> KMap {
 fn is_list(value: &KValue) -> bool {
     matches!(value, KValue::List(_))
 }
+
+// Calls the predicate with the value, checking that a Bool is returned
+fn call_predicate(vm: &mut KotoVm, predicate: &KValue, value: KValue) -> Result<bool> {
+    match vm.call_function(predicate.clone(), value)? {
+        KValue::Bool(result) => Ok(result),
+        unexpected => unexpected_type("a Bool to be returned from the predicate", &unexpected),
+    }
+}
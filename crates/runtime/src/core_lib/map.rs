@@ -1,6 +1,9 @@
 //! The `map` core library module
 
-use super::{iterator::adaptors, value_sort::compare_values};
+use super::{
+    iterator::{adaptors, capacity_hint, make_collectable_iterator},
+    value_sort::compare_values,
+};
 use crate::{Result, prelude::*};
 use std::cmp::Ordering;
 
@@ -13,7 +16,7 @@ pub fn make_module() -> KMap {
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), []) => {
-                m.data_mut().clear();
+                m.data_mut_checked()?.clear();
                 Ok(KValue::Map(m.clone()))
             }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
@@ -32,12 +35,30 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("entries", |ctx| {
+        let expected_error = "|Map|";
+
+        match map_instance_and_args(ctx, expected_error)? {
+            (KValue::Map(m), []) => {
+                let result = m
+                    .data()
+                    .iter()
+                    .map(|(key, value)| {
+                        KValue::Tuple(vec![key.value().clone(), value.clone()].into())
+                    })
+                    .collect::<ValueVec>();
+                Ok(KValue::List(KList::with_data(result)))
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("extend", |ctx| {
         let expected_error = "|Map, Iterable|";
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), [KValue::Map(other)]) => {
-                m.data_mut().extend(
+                m.data_mut_checked()?.extend(
                     other
                         .data()
                         .iter()
@@ -48,12 +69,11 @@ pub fn make_module() -> KMap {
             (KValue::Map(m), [iterable]) if iterable.is_iterable() => {
                 let m = m.clone();
                 let iterable = iterable.clone();
-                let iterator = ctx.vm.make_iterator(iterable)?;
+                let iterator = make_collectable_iterator(ctx.vm, iterable)?;
 
                 {
-                    let mut map_data = m.data_mut();
-                    let (size_hint, _) = iterator.size_hint();
-                    map_data.reserve(size_hint);
+                    let mut map_data = m.data_mut_checked()?;
+                    map_data.reserve(capacity_hint(&iterator));
 
                     for output in iterator {
                         use KIteratorOutput as Output;
@@ -78,6 +98,48 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("filter", |ctx| {
+        let expected_error = "|Map, |Any, Any| -> Bool|";
+
+        match map_instance_and_args(ctx, expected_error)? {
+            (KValue::Map(m), [predicate]) if predicate.is_callable() => {
+                let m = m.clone();
+                let predicate = predicate.clone();
+
+                // The entries are cloned to allow the map to be accessed by the predicate
+                let entries = m
+                    .data()
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<Vec<_>>();
+
+                let mut data = ValueMap::default();
+                for (key, value) in entries {
+                    match ctx
+                        .vm
+                        .call_function(predicate.clone(), &[key.value().clone(), value.clone()])?
+                    {
+                        KValue::Bool(true) => {
+                            data.insert(key, value);
+                        }
+                        KValue::Bool(false) => {}
+                        unexpected => {
+                            return unexpected_type(
+                                "a Bool to be returned from the predicate",
+                                &unexpected,
+                            );
+                        }
+                    }
+                }
+
+                let mut result = KMap::with_data(data);
+                result.set_meta_map(m.meta_map().cloned());
+                Ok(result.into())
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("get", |ctx| {
         let (map, key, default) = {
             let expected_error = "|Map, Any|, or |Map, Any, Any|";
@@ -145,7 +207,7 @@ pub fn make_module() -> KMap {
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), [key]) => match m
-                .data_mut()
+                .data_mut_checked()?
                 .insert(ValueKey::try_from(key.clone())?, KValue::Null)
             {
                 Some(old_value) => Ok(old_value),
@@ -153,7 +215,7 @@ pub fn make_module() -> KMap {
             },
             (KValue::Map(m), [key, value]) => {
                 match m
-                    .data_mut()
+                    .data_mut_checked()?
                     .insert(ValueKey::try_from(key.clone())?, value.clone())
                 {
                     Some(old_value) => Ok(old_value),
@@ -185,12 +247,52 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("map_values", |ctx| {
+        let expected_error = "|Map, |Any| -> Any|, or |Map, |Any, Any| -> Any|";
+
+        match map_instance_and_args(ctx, expected_error)? {
+            (KValue::Map(m), [f]) if f.is_callable() => {
+                let m = m.clone();
+                let f = f.clone();
+                // Functions that expect two arguments are called with the entry's key and value
+                let with_key =
+                    matches!(&f, KValue::Function(function) if function.expected_arg_count() == 2);
+
+                // The entries are cloned to allow the map to be accessed by the function
+                let entries = m
+                    .data()
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<Vec<_>>();
+
+                let mut data = ValueMap::with_capacity(entries.len());
+                for (key, value) in entries {
+                    let new_value = if with_key {
+                        ctx.vm
+                            .call_function(f.clone(), &[key.value().clone(), value])?
+                    } else {
+                        ctx.vm.call_function(f.clone(), value)?
+                    };
+                    data.insert(key, new_value);
+                }
+
+                let mut result = KMap::with_data(data);
+                result.set_meta_map(m.meta_map().cloned());
+                Ok(result.into())
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("remove", |ctx| {
         let expected_error = "|Map, Any|";
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), [key]) => {
-                match m.data_mut().shift_remove(&ValueKey::try_from(key.clone())?) {
+                match m
+                    .data_mut_checked()?
+                    .shift_remove(&ValueKey::try_from(key.clone())?)
+                {
                     Some(old_value) => Ok(old_value),
                     None => Ok(KValue::Null),
                 }
@@ -199,13 +301,22 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("size", |ctx| {
+        let expected_error = "|Map|";
+
+        match map_instance_and_args(ctx, expected_error)? {
+            (KValue::Map(m), []) => Ok(m.len().into()),
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("sort", |ctx| {
         let expected_error = "|Map|, or |Map, |Any, Any| -> Any|";
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), []) => {
                 let mut error = None;
-                m.data_mut().sort_by(|key_a, _, key_b, _| {
+                m.data_mut_checked()?.sort_by(|key_a, _, key_b, _| {
                     if error.is_some() {
                         return Ordering::Equal;
                     }
@@ -243,40 +354,41 @@ pub fn make_module() -> KMap {
                 };
 
                 let mut cache = ValueMap::with_capacity(m.len());
-                m.data_mut().sort_by(|key_a, value_a, key_b, value_b| {
-                    if error.is_some() {
-                        return Ordering::Equal;
-                    }
+                m.data_mut_checked()?
+                    .sort_by(|key_a, value_a, key_b, value_b| {
+                        if error.is_some() {
+                            return Ordering::Equal;
+                        }
 
-                    let value_a = match cache.get(key_a) {
-                        Some(value) => value.clone(),
-                        None => match get_sort_key(ctx.vm, &mut cache, key_a, value_a) {
-                            Ok(val) => val,
-                            Err(e) => {
-                                error.get_or_insert(Err(e));
-                                KValue::Null
-                            }
-                        },
-                    };
-                    let value_b = match cache.get(key_b) {
-                        Some(value) => value.clone(),
-                        None => match get_sort_key(ctx.vm, &mut cache, key_b, value_b) {
-                            Ok(val) => val,
+                        let value_a = match cache.get(key_a) {
+                            Some(value) => value.clone(),
+                            None => match get_sort_key(ctx.vm, &mut cache, key_a, value_a) {
+                                Ok(val) => val,
+                                Err(e) => {
+                                    error.get_or_insert(Err(e));
+                                    KValue::Null
+                                }
+                            },
+                        };
+                        let value_b = match cache.get(key_b) {
+                            Some(value) => value.clone(),
+                            None => match get_sort_key(ctx.vm, &mut cache, key_b, value_b) {
+                                Ok(val) => val,
+                                Err(e) => {
+                                    error.get_or_insert(Err(e));
+                                    KValue::Null
+                                }
+                            },
+                        };
+
+                        match compare_values(ctx.vm, &value_a, &value_b) {
+                            Ok(ordering) => ordering,
                             Err(e) => {
                                 error.get_or_insert(Err(e));
-                                KValue::Null
+                                Ordering::Equal
                             }
-                        },
-                    };
-
-                    match compare_values(ctx.vm, &value_a, &value_b) {
-                        Ok(ordering) => ordering,
-                        Err(e) => {
-                            error.get_or_insert(Err(e));
-                            Ordering::Equal
                         }
-                    }
-                });
+                    });
 
                 if let Some(error) = error {
                     error
@@ -346,12 +458,12 @@ fn do_map_update(
     vm: &mut KotoVm,
 ) -> Result<KValue> {
     if !map.data().contains_key(&key) {
-        map.data_mut().insert(key.clone(), default);
+        map.data_mut_checked()?.insert(key.clone(), default);
     }
     let value = map.get(&key).unwrap();
     match vm.call_function(f, value) {
         Ok(new_value) => {
-            map.data_mut().insert(key, new_value.clone());
+            map.data_mut_checked()?.insert(key, new_value.clone());
             Ok(new_value)
         }
         Err(error) => Err(error),
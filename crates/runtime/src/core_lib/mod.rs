@@ -13,7 +13,7 @@ pub mod test;
 pub mod tuple;
 mod value_sort;
 
-use crate::KMap;
+use crate::{KMap, runtime_error};
 
 #[derive(Clone)]
 #[allow(missing_docs)]
@@ -46,6 +46,7 @@ impl CoreLib {
         default_import!("assert_eq", test);
         default_import!("assert_ne", test);
         default_import!("assert_near", test);
+        default_import!("assert_throws", test);
         default_import!("print", io);
         default_import!("copy", koto);
         default_import!("size", koto);
@@ -67,6 +68,37 @@ impl CoreLib {
     }
 }
 
+impl CoreLib {
+    /// Replaces the functions that access the filesystem or the operating system with functions
+    /// that throw an error when called
+    ///
+    /// See [KotoVmSettings::sandboxed](crate::KotoVmSettings::sandboxed).
+    pub fn sandbox(&self) {
+        fn disable(module: &KMap, module_name: &'static str, functions: &[&'static str]) {
+            for &name in functions {
+                module.add_fn(name, move |_| {
+                    runtime_error!("'{module_name}.{name}' is unavailable in a sandboxed runtime")
+                });
+            }
+        }
+
+        disable(
+            &self.io,
+            "io",
+            &[
+                "create",
+                "current_dir",
+                "exists",
+                "open",
+                "read_to_string",
+                "remove_file",
+                "temp_dir",
+            ],
+        );
+        disable(&self.os, "os", &["command", "env", "process_id"]);
+    }
+}
+
 impl Default for CoreLib {
     fn default() -> Self {
         Self {
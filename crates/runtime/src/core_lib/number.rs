@@ -2,7 +2,7 @@
 
 mod step_to;
 
-use crate::prelude::*;
+use crate::{Result, prelude::*};
 
 /// Initializes the `number` core library module
 pub fn make_module() -> KMap {
@@ -46,6 +46,36 @@ pub fn make_module() -> KMap {
         };
     }
 
+    macro_rules! number_fn_2 {
+        ($fn:ident) => {
+            result.add_fn(stringify!($fn), |ctx| {
+                let expected_error = "|Number, Number|";
+
+                match ctx.instance_and_args(is_number, expected_error)? {
+                    (Number(a), [Number(b)]) => Ok(Number(a.$fn(*b))),
+                    (instance, args) => {
+                        unexpected_args_after_instance(expected_error, instance, args)
+                    }
+                }
+            });
+        };
+    }
+
+    macro_rules! number_f64_fn_2 {
+        ($fn:ident) => {
+            result.add_fn(stringify!($fn), |ctx| {
+                let expected_error = "|Number, Number|";
+
+                match ctx.instance_and_args(is_number, expected_error)? {
+                    (Number(a), [Number(b)]) => Ok(f64::from(a).$fn(f64::from(b)).into()),
+                    (instance, args) => {
+                        unexpected_args_after_instance(expected_error, instance, args)
+                    }
+                }
+            });
+        };
+    }
+
     macro_rules! bitwise_fn {
         ($name:ident, $op:tt) => {
             result.add_fn(stringify!($name), |ctx| {
@@ -87,14 +117,7 @@ pub fn make_module() -> KMap {
     number_f64_fn!(atan);
     number_f64_fn!(atanh);
 
-    result.add_fn("atan2", |ctx| {
-        let expected_error = "|Number, Number|";
-
-        match ctx.instance_and_args(is_number, expected_error)? {
-            (Number(y), [Number(x)]) => Ok(f64::from(y).atan2(f64::from(x)).into()),
-            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
-        }
-    });
+    number_f64_fn_2!(atan2);
 
     number_fn!(ceil);
 
@@ -112,10 +135,26 @@ pub fn make_module() -> KMap {
     number_f64_fn!("degrees", to_degrees);
 
     result.insert("e", std::f64::consts::E);
+    result.insert("epsilon", f64::EPSILON);
 
     number_f64_fn!(exp);
     number_f64_fn!(exp2);
 
+    result.add_fn("factorial", |ctx| {
+        let expected_error = "|Number|";
+
+        match ctx.instance_and_args(is_number, expected_error)? {
+            (Number(n), []) => {
+                let n = non_negative_integer(n)?;
+                match (2..=n).try_fold(1_i64, |result, i| result.checked_mul(i)) {
+                    Some(result) => Ok(result.into()),
+                    None => runtime_error!("the factorial of {n} is too large to be represented"),
+                }
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("flip_bits", |ctx| {
         let expected_error = "|Number|";
 
@@ -127,6 +166,21 @@ pub fn make_module() -> KMap {
 
     number_fn!(floor);
 
+    result.add_fn("gcd", |ctx| {
+        let expected_error = "|Number, Number|";
+
+        match ctx.instance_and_args(is_number, expected_error)? {
+            (Number(a), [Number(b)]) => {
+                let a = non_negative_integer(a)?;
+                let b = non_negative_integer(b)?;
+                Ok(gcd(a, b).into())
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
+    number_f64_fn_2!(hypot);
+
     result.insert("infinity", Number(f64::INFINITY.into()));
 
     result.add_fn("is_nan", |ctx| {
@@ -138,6 +192,27 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("lcm", |ctx| {
+        let expected_error = "|Number, Number|";
+
+        match ctx.instance_and_args(is_number, expected_error)? {
+            (Number(a), [Number(b)]) => {
+                let a = non_negative_integer(a)?;
+                let b = non_negative_integer(b)?;
+                if a == 0 || b == 0 {
+                    return Ok(0.into());
+                }
+                match (a / gcd(a, b)).checked_mul(b) {
+                    Some(result) => Ok(result.into()),
+                    None => runtime_error!(
+                        "the least common multiple of {a} and {b} is too large to be represented"
+                    ),
+                }
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("lerp", |ctx| {
         let expected_error = "|Number, Number, Number|";
 
@@ -177,13 +252,53 @@ pub fn make_module() -> KMap {
 
     bitwise_fn!(or, |);
 
+    result.add_fn("parse_float", |ctx| match ctx.args() {
+        [KValue::Str(s)] => parse_float(s).map(KValue::from),
+        unexpected => unexpected_args("|String|", unexpected),
+    });
+
+    result.add_fn("parse_int", |ctx| match ctx.args() {
+        [KValue::Str(s)] => parse_int(s, 10).map(KValue::from),
+        [KValue::Str(s), Number(radix)] => {
+            let radix = match u32::try_from(i64::from(radix)) {
+                Ok(radix) if (2..=36).contains(&radix) => radix,
+                _ => return runtime_error!("the radix must be within 2..=36, found {radix}"),
+            };
+            parse_int(s, radix).map(KValue::from)
+        }
+        unexpected => unexpected_args("|String|, or |String, radix: Number|", unexpected),
+    });
+
     result.insert("pi", std::f64::consts::PI);
     result.insert("pi_2", std::f64::consts::FRAC_PI_2);
     result.insert("pi_4", std::f64::consts::FRAC_PI_4);
 
+    number_fn_2!(pow);
+
     number_f64_fn!("radians", to_radians);
     number_f64_fn!(recip);
-    number_fn!(round);
+
+    result.add_fn("round", |ctx| {
+        let expected_error = "|Number|, or |Number, digits: Number|";
+
+        match ctx.instance_and_args(is_number, expected_error)? {
+            (Number(n), []) => Ok(n.round().into()),
+            (Number(n), [Number(digits)]) => {
+                Ok(round(*n, digits.into(), f64::round_ties_even).into())
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
+    result.add_fn("round_half_up", |ctx| {
+        let expected_error = "|Number|, or |Number, digits: Number|";
+
+        match ctx.instance_and_args(is_number, expected_error)? {
+            (Number(n), []) => Ok(round(*n, 0, round_half_up).into()),
+            (Number(n), [Number(digits)]) => Ok(round(*n, digits.into(), round_half_up).into()),
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
 
     bitwise_fn_positive_arg!(shift_left, <<);
     bitwise_fn_positive_arg!(shift_right, >>);
@@ -257,6 +372,95 @@ pub fn make_module() -> KMap {
     result
 }
 
+// Rounds a number to the given number of decimal places using the provided rounding function
+//
+// Integers are produced when rounding to zero or fewer decimal places.
+// NaN and infinite values are returned unchanged.
+fn round(n: KNumber, digits: i64, round_fn: fn(f64) -> f64) -> KNumber {
+    match n {
+        KNumber::I64(_) if digits >= 0 => n,
+        KNumber::F64(f) if !f.is_finite() => n,
+        _ if digits == 0 => KNumber::I64(round_fn(f64::from(n)) as i64),
+        _ => {
+            let scale = 10.0_f64.powi(digits.clamp(-308, 308) as i32);
+            let result = round_fn(f64::from(n) * scale) / scale;
+            if !result.is_finite() {
+                // Scaling overflowed, so the number has no digits that need rounding
+                n
+            } else if digits < 0 {
+                KNumber::I64(result as i64)
+            } else {
+                KNumber::F64(result)
+            }
+        }
+    }
+}
+
+// Rounds to the nearest integer, with half-way values rounded towards positive infinity
+fn round_half_up(n: f64) -> f64 {
+    let floor = n.floor();
+    if n - floor >= 0.5 { floor + 1.0 } else { floor }
+}
+
+fn non_negative_integer(n: &KNumber) -> Result<i64> {
+    match n {
+        KNumber::I64(n) if *n >= 0 => Ok(*n),
+        _ => runtime_error!("expected a non-negative integer, found {n}"),
+    }
+}
+
+fn gcd(mut a: i64, mut b: i64) -> i64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// Parses an integer, reporting the position of the first invalid digit if parsing fails
+fn parse_int(s: &str, radix: u32) -> Result<i64> {
+    let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let digits_start = s.chars().count() - digits.chars().count();
+
+    if digits.is_empty() {
+        return runtime_error!("expected digits in '{s}'");
+    }
+
+    if let Some((position, c)) = digits.chars().enumerate().find(|(_, c)| !c.is_digit(radix)) {
+        return runtime_error!(
+            "invalid digit '{c}' at position {} in '{s}' (radix {radix})",
+            digits_start + position
+        );
+    }
+
+    match i64::from_str_radix(s, radix) {
+        Ok(n) => Ok(n),
+        Err(_) => runtime_error!("'{s}' is out of range for a 64-bit integer"),
+    }
+}
+
+// Parses a float, reporting the position of the first unexpected character if parsing fails
+fn parse_float(s: &str) -> Result<f64> {
+    if let Ok(n) = s.parse::<f64>() {
+        return Ok(n);
+    }
+
+    if s.is_empty() {
+        return runtime_error!("expected a number, found an empty string");
+    }
+
+    // Find the longest valid prefix, the character that follows it is where parsing failed
+    let position = s
+        .char_indices()
+        .rev()
+        .map(|(i, _)| i)
+        .filter(|&i| i > 0)
+        .find(|&i| s[..i].parse::<f64>().is_ok())
+        .map_or(0, |i| s[..i].chars().count());
+    let c = s.chars().nth(position).unwrap_or_default();
+
+    runtime_error!("unexpected '{c}' at position {position} in '{s}'")
+}
+
 fn is_number(value: &KValue) -> bool {
     matches!(value, KValue::Number(_))
 }
@@ -6,6 +6,7 @@ use self::command::Command;
 use crate::{Result, derive::*, prelude::*};
 use chrono::prelude::*;
 use instant::Instant;
+use std::{result::Result as StdResult, time::SystemTime};
 
 /// Initializes the `os` core library module
 pub fn make_module() -> KMap {
@@ -15,6 +16,11 @@ pub fn make_module() -> KMap {
 
     result.insert("args", KValue::Tuple(KTuple::default()));
 
+    result.add_fn("duration", |ctx| match ctx.args() {
+        [Number(seconds)] => Duration::from_seconds(seconds.into()),
+        unexpected => unexpected_args("|Number|", unexpected),
+    });
+
     result.add_fn("env", |ctx| match ctx.args() {
         [KValue::Str(key)] => Ok(std::env::var(key.as_str()).ok().into()),
         unexpected => unexpected_args("|String|", unexpected),
@@ -157,6 +163,143 @@ impl KotoObject for DateTime {
     }
 }
 
+impl From<SystemTime> for KValue {
+    fn from(time: SystemTime) -> Self {
+        let local: chrono::DateTime<Local> = time.into();
+        DateTime::with_chrono_datetime(local.fixed_offset())
+    }
+}
+
+/// If conversion fails then the input value will be returned.
+impl TryFrom<KValue> for SystemTime {
+    type Error = KValue;
+
+    fn try_from(value: KValue) -> StdResult<Self, KValue> {
+        if let KValue::Object(o) = &value
+            && let Ok(time) = o.cast::<DateTime>()
+        {
+            Ok(time.0.into())
+        } else {
+            Err(value)
+        }
+    }
+}
+
+/// The underlying data type returned by `os.duration()`
+#[derive(Clone, Copy, Debug, KotoCopy, KotoType)]
+#[koto(runtime = crate)]
+pub struct Duration(std::time::Duration);
+
+#[koto_impl(runtime = crate)]
+impl Duration {
+    fn from_seconds(seconds: f64) -> Result<KValue> {
+        match std::time::Duration::try_from_secs_f64(seconds) {
+            Ok(duration) => Ok(duration.into()),
+            Err(_) => runtime_error!("invalid duration in seconds: {seconds}"),
+        }
+    }
+
+    fn from_value(value: &KValue) -> Option<std::time::Duration> {
+        match value {
+            KValue::Object(o) => o.cast::<Self>().ok().map(|duration| duration.0),
+            _ => None,
+        }
+    }
+
+    fn scaled(&self, scale: f64) -> Result<KValue> {
+        match std::time::Duration::try_from_secs_f64(self.0.as_secs_f64() * scale) {
+            Ok(duration) => Ok(duration.into()),
+            Err(_) => runtime_error!("invalid duration scale: {scale}"),
+        }
+    }
+
+    #[koto_method]
+    fn seconds(&self) -> KValue {
+        self.0.as_secs_f64().into()
+    }
+}
+
+impl KotoObject for Duration {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Duration({}s)", self.0.as_secs_f64()));
+        Ok(())
+    }
+
+    fn add(&self, other: &KValue) -> Result<KValue> {
+        match Self::from_value(other) {
+            Some(other) => match self.0.checked_add(other) {
+                Some(result) => Ok(result.into()),
+                None => runtime_error!("duration overflow"),
+            },
+            None => unexpected_type(Self::type_static(), other),
+        }
+    }
+
+    fn subtract(&self, other: &KValue) -> Result<KValue> {
+        match Self::from_value(other) {
+            Some(other) => match self.0.checked_sub(other) {
+                Some(result) => Ok(result.into()),
+                None => runtime_error!("the resulting duration would be negative"),
+            },
+            None => unexpected_type(Self::type_static(), other),
+        }
+    }
+
+    fn multiply(&self, other: &KValue) -> Result<KValue> {
+        match other {
+            KValue::Number(n) => self.scaled(n.into()),
+            unexpected => unexpected_type("Number", unexpected),
+        }
+    }
+
+    fn multiply_rhs(&self, other: &KValue) -> Result<KValue> {
+        self.multiply(other)
+    }
+
+    fn divide(&self, other: &KValue) -> Result<KValue> {
+        match other {
+            KValue::Number(n) => self.scaled(1.0 / f64::from(n)),
+            unexpected => unexpected_type("Number", unexpected),
+        }
+    }
+
+    fn less(&self, other: &KValue) -> Result<bool> {
+        match Self::from_value(other) {
+            Some(other) => Ok(self.0 < other),
+            None => unexpected_type(Self::type_static(), other),
+        }
+    }
+
+    fn equal(&self, other: &KValue) -> Result<bool> {
+        match Self::from_value(other) {
+            Some(other) => Ok(self.0 == other),
+            None => unexpected_type(Self::type_static(), other),
+        }
+    }
+}
+
+impl From<std::time::Duration> for KValue {
+    fn from(duration: std::time::Duration) -> Self {
+        KObject::from(Duration(duration)).into()
+    }
+}
+
+/// Durations can be converted from Duration objects, or from non-negative numbers of seconds.
+///
+/// If conversion fails then the input value will be returned.
+impl TryFrom<KValue> for std::time::Duration {
+    type Error = KValue;
+
+    fn try_from(value: KValue) -> StdResult<Self, KValue> {
+        match &value {
+            KValue::Number(seconds) => {
+                std::time::Duration::try_from_secs_f64(seconds.into()).map_err(|_| value)
+            }
+            _ => Duration::from_value(&value).ok_or(value),
+        }
+    }
+}
+
 /// The underlying data type returned by `os.start_timer()`
 #[derive(Clone, Debug, KotoCopy, KotoType)]
 #[koto(runtime = crate)]
@@ -200,3 +343,32 @@ impl KotoObject for Timer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_round_trip() {
+        let duration = std::time::Duration::from_millis(1500);
+        let value = KValue::from(duration);
+        assert_eq!(value.type_as_string(), "Duration");
+        assert_eq!(std::time::Duration::try_from(value).unwrap(), duration);
+    }
+
+    #[test]
+    fn duration_from_seconds() {
+        let duration = std::time::Duration::try_from(KValue::from(2.5)).unwrap();
+        assert_eq!(duration, std::time::Duration::from_millis(2500));
+        assert!(std::time::Duration::try_from(KValue::from(-1)).is_err());
+        assert!(std::time::Duration::try_from(KValue::from("1s")).is_err());
+    }
+
+    #[test]
+    fn system_time_round_trip() {
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        let value = KValue::from(time);
+        assert_eq!(value.type_as_string(), "DateTime");
+        assert_eq!(SystemTime::try_from(value).unwrap(), time);
+    }
+}
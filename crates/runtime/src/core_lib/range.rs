@@ -69,6 +69,18 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("length", |ctx| {
+        let expected_error = "|Range|";
+
+        match ctx.instance_and_args(is_range, expected_error)? {
+            (KValue::Range(r), []) => match r.size() {
+                Some(size) => Ok(size.into()),
+                None => runtime_error!("range.length can't be used with '{r}'"),
+            },
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("start", |ctx| {
         let expected_error = "|Range|";
 
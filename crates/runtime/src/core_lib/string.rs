@@ -2,11 +2,14 @@
 
 pub mod iterators;
 
-use super::iterator::collect_pair;
+use super::iterator::{capacity_hint, collect_pair, make_collectable_iterator};
 use crate::{
-    error::{unexpected_args, unexpected_args_after_instance},
+    Result,
+    error::{unexpected_args, unexpected_args_after_instance, unexpected_type},
     prelude::*,
 };
+use std::{iter, ops::Range};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Initializes the `string` core library module
 pub fn make_module() -> KMap {
@@ -77,9 +80,8 @@ pub fn make_module() -> KMap {
     result.add_fn("from_bytes", |ctx| match ctx.args() {
         [iterable] if iterable.is_iterable() => {
             let iterable = iterable.clone();
-            let iterator = ctx.vm.make_iterator(iterable)?;
-            let (size_hint, _) = iterator.size_hint();
-            let mut bytes = Vec::<u8>::with_capacity(size_hint);
+            let iterator = make_collectable_iterator(ctx.vm, iterable)?;
+            let mut bytes = Vec::<u8>::with_capacity(capacity_hint(&iterator));
 
             for output in iterator.map(collect_pair) {
                 use KIteratorOutput as Output;
@@ -111,6 +113,34 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("join", |ctx| match ctx.args() {
+        [iterable, KValue::Str(separator)] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            let separator = separator.clone();
+            let iterator = make_collectable_iterator(ctx.vm, iterable)?;
+            let capacity = capacity_hint(&iterator);
+            let mut display_context = DisplayContext::with_vm_and_capacity(ctx.vm, capacity);
+
+            for (i, output) in iterator.map(collect_pair).enumerate() {
+                use KIteratorOutput as Output;
+
+                if i > 0 {
+                    display_context.append(separator.clone());
+                }
+
+                match output {
+                    Output::Value(KValue::Str(s)) => display_context.append(s),
+                    Output::Value(value) => value.display(&mut display_context)?,
+                    Output::Error(error) => return Err(error),
+                    _ => unreachable!(),
+                }
+            }
+
+            Ok(display_context.result().into())
+        }
+        unexpected => unexpected_args("|Iterable, String|", unexpected),
+    });
+
     result.add_fn("lines", |ctx| {
         let expected_error = "|String|";
 
@@ -140,12 +170,30 @@ pub fn make_module() -> KMap {
     });
 
     result.add_fn("replace", |ctx| {
-        let expected_error = "|String, String, String|";
+        let expected_error = "|String, String, String|, or |String, String, String, Number|";
 
         match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(_), [KValue::Str(pattern), KValue::Str(_), ..]) if pattern.is_empty() => {
+                runtime_error!("the string to be replaced must not be empty")
+            }
             (KValue::Str(input), [KValue::Str(pattern), KValue::Str(replace)]) => {
                 Ok(input.replace(pattern.as_str(), replace).into())
             }
+            (
+                KValue::Str(input),
+                [
+                    KValue::Str(pattern),
+                    KValue::Str(replace),
+                    KValue::Number(count),
+                ],
+            ) => {
+                if *count >= 0.0 {
+                    let result = input.replacen(pattern.as_str(), replace, usize::from(count));
+                    Ok(result.into())
+                } else {
+                    runtime_error!("expected a non-negative number")
+                }
+            }
             (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
         }
     });
@@ -218,6 +266,30 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("substr_bytes", |ctx| {
+        let expected_error = "|String, Number, Number|";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), [KValue::Number(start), KValue::Number(end)]) => {
+                if *start < 0.0 || *end < 0.0 {
+                    return runtime_error!("byte offsets must be non-negative");
+                }
+                let (start, end) = (usize::from(start), usize::from(end));
+                if start > end || end > s.len() {
+                    return runtime_error!(
+                        "{start}..{end} is out of bounds for a string with {} bytes",
+                        s.len()
+                    );
+                }
+                match s.with_bounds(start..end) {
+                    Some(result) => Ok(result.into()),
+                    None => runtime_error!("{start}..{end} doesn't lie on character boundaries"),
+                }
+            }
+            (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+        }
+    });
+
     result.add_fn("to_lowercase", |ctx| {
         let expected_error = "|String|";
 
@@ -282,24 +354,10 @@ pub fn make_module() -> KMap {
     });
 
     result.add_fn("trim", |ctx| {
-        let expected_error = "|String|, or |String, String|";
+        let (input, pattern) = trim_args(ctx)?;
 
-        let (input, trimmed_start, trimmed_end) =
-            match ctx.instance_and_args(is_string, expected_error)? {
-                (KValue::Str(s), []) => {
-                    let trimmed_start = s.trim_start();
-                    let trimmed_end = trimmed_start.trim_end();
-                    (s, trimmed_start, trimmed_end)
-                }
-                (KValue::Str(s), [KValue::Str(pattern)]) => {
-                    let trimmed_start = s.trim_start_matches(pattern.as_str());
-                    let trimmed_end = trimmed_start.trim_end_matches(pattern.as_str());
-                    (s, trimmed_start, trimmed_end)
-                }
-                (instance, args) => {
-                    return unexpected_args_after_instance(expected_error, instance, args);
-                }
-            };
+        let trimmed_start = pattern.trim_start(input);
+        let trimmed_end = pattern.trim_end(trimmed_start);
 
         let new_start = input.len() - trimmed_start.len();
         let new_end = new_start + trimmed_end.len();
@@ -307,32 +365,17 @@ pub fn make_module() -> KMap {
     });
 
     result.add_fn("trim_start", |ctx| {
-        let expected_error = "|String|, or |String, String|";
-
-        let (input, trimmed_start) = match ctx.instance_and_args(is_string, expected_error)? {
-            (KValue::Str(s), []) => (s, s.trim_start()),
-            (KValue::Str(s), [KValue::Str(pattern)]) => (s, s.trim_start_matches(pattern.as_str())),
-            (instance, args) => {
-                return unexpected_args_after_instance(expected_error, instance, args);
-            }
-        };
+        let (input, pattern) = trim_args(ctx)?;
 
-        let new_start = input.len() - trimmed_start.len();
+        let new_start = input.len() - pattern.trim_start(input).len();
         Ok(input.with_bounds(new_start..input.len()).unwrap().into())
     });
 
     result.add_fn("trim_end", |ctx| {
-        let expected_error = "|String|, or |String, String|";
-
-        let (input, trimmed_end) = match ctx.instance_and_args(is_string, expected_error)? {
-            (KValue::Str(s), []) => (s, s.trim_end()),
-            (KValue::Str(s), [KValue::Str(pattern)]) => (s, s.trim_end_matches(pattern.as_str())),
-            (instance, args) => {
-                return unexpected_args_after_instance(expected_error, instance, args);
-            }
-        };
+        let (input, pattern) = trim_args(ctx)?;
 
-        Ok(input.with_bounds(0..trimmed_end.len()).unwrap().into())
+        let new_end = pattern.trim_end(input).len();
+        Ok(input.with_bounds(0..new_end).unwrap().into())
     });
 
     result
@@ -341,3 +384,111 @@ pub fn make_module() -> KMap {
 fn is_string(value: &KValue) -> bool {
     matches!(value, KValue::Str(_))
 }
+
+/// Returns the number of characters in the string
+///
+/// Each character is a grapheme cluster, matching the output of `string.chars`.
+/// This is used as a string's size, and strings are indexed by character (see [char_bounds]).
+pub(crate) fn char_count(s: &str) -> usize {
+    if has_single_byte_chars(s) {
+        s.len()
+    } else {
+        s.graphemes(true).count()
+    }
+}
+
+/// Converts a range of character indices into the corresponding range of byte offsets
+///
+/// Indices beyond the end of the string are clamped to the string's length.
+pub(crate) fn char_bounds(s: &str, chars: Range<usize>) -> Range<usize> {
+    if has_single_byte_chars(s) {
+        let end = chars.end.min(s.len());
+        return chars.start.min(end)..end;
+    }
+
+    let mut offsets = s
+        .grapheme_indices(true)
+        .map(|(offset, _)| offset)
+        .chain(iter::once(s.len()));
+
+    let start = offsets.nth(chars.start).unwrap_or(s.len());
+    let end = match chars.end.checked_sub(chars.start + 1) {
+        Some(n) => offsets.nth(n).unwrap_or(s.len()),
+        None => start,
+    };
+
+    start..end
+}
+
+// ASCII strings have a single byte per character, with the exception of `\r\n`,
+// which is treated as a single grapheme cluster.
+fn has_single_byte_chars(s: &str) -> bool {
+    s.is_ascii() && !s.contains('\r')
+}
+
+// The patterns that can be removed by the `trim` functions
+enum TrimPattern {
+    Whitespace,
+    Str(KString),
+    // Any of the strings in the set will be removed, e.g. `x.trim ('-', '_')`
+    Set(Vec<KString>),
+}
+
+impl TrimPattern {
+    fn from_set(set: &[KValue]) -> Result<Self> {
+        set.iter()
+            .map(|value| match value {
+                KValue::Str(s) => Ok(s.clone()),
+                unexpected => unexpected_type("a String in the set of patterns", unexpected),
+            })
+            .collect::<Result<_>>()
+            .map(Self::Set)
+    }
+
+    fn trim_start<'a>(&self, input: &'a str) -> &'a str {
+        match self {
+            Self::Whitespace => input.trim_start(),
+            Self::Str(pattern) => input.trim_start_matches(pattern.as_str()),
+            Self::Set(patterns) => {
+                let mut result = input;
+                while let Some(pattern) = patterns
+                    .iter()
+                    .find(|pattern| !pattern.is_empty() && result.starts_with(pattern.as_str()))
+                {
+                    result = &result[pattern.len()..];
+                }
+                result
+            }
+        }
+    }
+
+    fn trim_end<'a>(&self, input: &'a str) -> &'a str {
+        match self {
+            Self::Whitespace => input.trim_end(),
+            Self::Str(pattern) => input.trim_end_matches(pattern.as_str()),
+            Self::Set(patterns) => {
+                let mut result = input;
+                while let Some(pattern) = patterns
+                    .iter()
+                    .find(|pattern| !pattern.is_empty() && result.ends_with(pattern.as_str()))
+                {
+                    result = &result[..result.len() - pattern.len()];
+                }
+                result
+            }
+        }
+    }
+}
+
+// Returns the input string and pattern for the `trim` functions
+fn trim_args<'a>(ctx: &'a CallContext) -> Result<(&'a KString, TrimPattern)> {
+    let expected_error = "|String|, |String, String|, |String, Tuple|, or |String, List|";
+
+    match ctx.instance_and_args(is_string, expected_error)? {
+        (KValue::Str(s), []) => Ok((s, TrimPattern::Whitespace)),
+        (KValue::Str(s), [KValue::Str(pattern)]) => Ok((s, TrimPattern::Str(pattern.clone()))),
+        (KValue::Str(s), [KValue::Tuple(set)]) => Ok((s, TrimPattern::from_set(set)?)),
+        (KValue::Str(s), [KValue::List(set)]) => Ok((s, TrimPattern::from_set(&set.data())?)),
+        (instance, args) => unexpected_args_after_instance(expected_error, instance, args),
+    }
+}
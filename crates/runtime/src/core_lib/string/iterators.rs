@@ -173,7 +173,14 @@ impl Iterator for Split {
 
     fn next(&mut self) -> Option<Self::Item> {
         let start = self.start;
-        if start <= self.input.len() {
+        if self.pattern.is_empty() {
+            // An empty pattern splits the input into its individual characters
+            let end = start + self.input[start..].graphemes(true).next()?.len();
+            self.start = end;
+            Some(Output::Value(
+                self.input.with_bounds(start..end).unwrap().into(),
+            ))
+        } else if start <= self.input.len() {
             let end = match self.input[start..].find(self.pattern.as_str()) {
                 Some(end) => start + end,
                 None => self.input.len(),
@@ -1,6 +1,7 @@
 //! The `test` core library module
 
-use crate::{Result, prelude::*};
+use crate::{ErrorKind, Result, prelude::*};
+use koto_lexer::{is_id_continue, is_id_start};
 
 /// Initializes the `test` core library module
 pub fn make_module() -> KMap {
@@ -28,11 +29,22 @@ pub fn make_module() -> KMap {
             match result {
                 Ok(KValue::Bool(true)) => Ok(KValue::Null),
                 Ok(KValue::Bool(false)) => {
-                    runtime_error!(
-                        "assertion failed, '{}' is not equal to '{}'",
-                        ctx.vm.value_to_string(&a)?,
-                        ctx.vm.value_to_string(&b)?,
-                    )
+                    let mismatch =
+                        find_first_mismatch(ctx.vm, a.clone(), b.clone(), String::new())?;
+                    if mismatch.path.is_empty() {
+                        runtime_error!(
+                            "assertion failed, '{}' is not equal to '{}'",
+                            ctx.vm.value_to_string(&a)?,
+                            ctx.vm.value_to_string(&b)?,
+                        )
+                    } else {
+                        runtime_error!(
+                            "assertion failed, mismatch at {}: {} != {}",
+                            mismatch.path,
+                            describe_mismatched_value(ctx.vm, mismatch.a)?,
+                            describe_mismatched_value(ctx.vm, mismatch.b)?,
+                        )
+                    }
                 }
                 Ok(unexpected) => unexpected_type("Bool from equality comparison", &unexpected),
                 Err(e) => Err(e),
@@ -74,6 +86,39 @@ pub fn make_module() -> KMap {
         unexpected => unexpected_args("|Number, Number, Number|", unexpected),
     });
 
+    result.add_fn("assert_throws", |ctx| {
+        let (f, expected) = match ctx.args() {
+            [f] if f.is_callable() => (f.clone(), None),
+            [f, expected] if f.is_callable() => (f.clone(), Some(expected.clone())),
+            unexpected => return unexpected_args("|| -> Any|, or ||| -> Any, Any|", unexpected),
+        };
+
+        let thrown = match ctx.vm.call_function(f, &[]) {
+            Ok(_) => return runtime_error!("assertion failed, no error was thrown"),
+            Err(error) => match error.error {
+                ErrorKind::KotoError { thrown_value, .. } => thrown_value,
+                other => KValue::Str(other.to_string().into()),
+            },
+        };
+
+        let Some(expected) = expected else {
+            return Ok(KValue::Null);
+        };
+
+        match ctx
+            .vm
+            .run_binary_op(BinaryOp::Equal, thrown.clone(), expected.clone())?
+        {
+            KValue::Bool(true) => Ok(KValue::Null),
+            KValue::Bool(false) => runtime_error!(
+                "assertion failed, the thrown error '{}' is not equal to '{}'",
+                ctx.vm.value_to_string(&thrown)?,
+                ctx.vm.value_to_string(&expected)?,
+            ),
+            unexpected => unexpected_type("Bool from equality comparison", &unexpected),
+        }
+    });
+
     result.add_fn("run_tests", |ctx| match ctx.args() {
         [KValue::Map(tests)] => {
             let tests = tests.clone();
@@ -85,6 +130,117 @@ pub fn make_module() -> KMap {
     result
 }
 
+// The location of the first difference between two values, found by `find_first_mismatch`
+struct Mismatch {
+    // The path to the differing values, e.g. `[2].name`, empty if the values differ at the top level
+    path: String,
+    // The differing values, `None` if the value is missing from one side of the comparison
+    a: Option<KValue>,
+    b: Option<KValue>,
+}
+
+// Finds the first difference between two values that are known to be unequal
+//
+// Lists, tuples, and maps are compared entry by entry, with the path to the first unequal entry
+// being recorded. Maps that override equality are compared as a whole.
+fn find_first_mismatch(vm: &mut KotoVm, a: KValue, b: KValue, path: String) -> Result<Mismatch> {
+    use KValue::{List, Map, Tuple};
+
+    let entries = match (&a, &b) {
+        (List(a), List(b)) => Some((a.data().to_vec(), b.data().to_vec())),
+        (Tuple(a), Tuple(b)) => Some((a.to_vec(), b.to_vec())),
+        _ => None,
+    };
+
+    if let Some((a_entries, b_entries)) = entries {
+        for i in 0..a_entries.len().max(b_entries.len()) {
+            let entry_path = format!("{path}[{i}]");
+            match (a_entries.get(i), b_entries.get(i)) {
+                (Some(a), Some(b)) => {
+                    if !values_are_equal(vm, a.clone(), b.clone())? {
+                        return find_first_mismatch(vm, a.clone(), b.clone(), entry_path);
+                    }
+                }
+                (a, b) => {
+                    return Ok(Mismatch {
+                        path: entry_path,
+                        a: a.cloned(),
+                        b: b.cloned(),
+                    });
+                }
+            }
+        }
+    }
+
+    if let (Map(a_map), Map(b_map)) = (&a, &b)
+        && !a_map.contains_meta_key(&BinaryOp::Equal.into())
+        && !b_map.contains_meta_key(&BinaryOp::Equal.into())
+    {
+        let a_entries = a_map.data().clone();
+        let b_entries = b_map.data().clone();
+
+        for (key, a) in a_entries.iter() {
+            let entry_path = format!("{path}{}", key_path(vm, key)?);
+            match b_entries.get(key) {
+                Some(b) => {
+                    if !values_are_equal(vm, a.clone(), b.clone())? {
+                        return find_first_mismatch(vm, a.clone(), b.clone(), entry_path);
+                    }
+                }
+                None => {
+                    return Ok(Mismatch {
+                        path: entry_path,
+                        a: Some(a.clone()),
+                        b: None,
+                    });
+                }
+            }
+        }
+
+        if let Some((key, b)) = b_entries
+            .iter()
+            .find(|(key, _)| !a_entries.contains_key(*key))
+        {
+            return Ok(Mismatch {
+                path: format!("{path}{}", key_path(vm, key)?),
+                a: None,
+                b: Some(b.clone()),
+            });
+        }
+    }
+
+    Ok(Mismatch {
+        path,
+        a: Some(a),
+        b: Some(b),
+    })
+}
+
+fn values_are_equal(vm: &mut KotoVm, a: KValue, b: KValue) -> Result<bool> {
+    match vm.run_binary_op(BinaryOp::Equal, a, b)? {
+        KValue::Bool(result) => Ok(result),
+        unexpected => unexpected_type("Bool from equality comparison", &unexpected),
+    }
+}
+
+// Renders a map key as part of a mismatch path, e.g. `.name` or `[42]`
+fn key_path(vm: &mut KotoVm, key: &ValueKey) -> Result<String> {
+    match key.value() {
+        KValue::Str(s) if s.starts_with(is_id_start) && s.chars().skip(1).all(is_id_continue) => {
+            Ok(format!(".{s}"))
+        }
+        KValue::Str(s) => Ok(format!("['{s}']")),
+        other => Ok(format!("[{}]", vm.value_to_string(other)?)),
+    }
+}
+
+fn describe_mismatched_value(vm: &mut KotoVm, value: Option<KValue>) -> Result<String> {
+    match value {
+        Some(value) => Ok(format!("'{}'", vm.value_to_string(&value)?)),
+        None => Ok("(missing)".into()),
+    }
+}
+
 fn f64_near(a: f64, b: f64, allowed_diff: f64) -> bool {
     (a - b).abs() <= allowed_diff
 }
@@ -60,13 +60,12 @@ pub fn make_module() -> KMap {
             }
         };
 
-        if *index >= 0 {
-            match tuple.get(usize::from(index)) {
-                Some(value) => Ok(value.clone()),
-                None => Ok(default),
-            }
-        } else {
-            Ok(default)
+        match index
+            .resolve_index(tuple.len())
+            .and_then(|index| tuple.get(index).cloned())
+        {
+            Some(value) => Ok(value),
+            None => Ok(default),
         }
     });
 
@@ -96,3 +96,68 @@ pub fn compare_values(vm: &mut KotoVm, a: &KValue, b: &KValue) -> Result<Orderin
         }
     }
 }
+
+/// Compares values using an order that extends across types, see `koto.compare`
+///
+/// Lists and tuples are compared lexicographically, and values of differing types are ordered by
+/// type. Other values of matching types are compared using Koto operators, with an error being
+/// returned if the values can't be compared.
+pub fn compare_values_across_types(
+    vm: &mut KotoVm,
+    a: &KValue,
+    b: &KValue,
+) -> Result<Ordering, Error> {
+    use KValue::*;
+
+    match (a, b) {
+        (Null, Null) => Ok(Ordering::Equal),
+        (Bool(a), Bool(b)) => Ok(a.cmp(b)),
+        (List(_) | Tuple(_), List(_) | Tuple(_)) => {
+            // The values are cloned to allow overridden operators to access the containers
+            let a_values = sequence_values(a);
+            let b_values = sequence_values(b);
+
+            for (a_value, b_value) in a_values.iter().zip(b_values.iter()) {
+                match compare_values_across_types(vm, a_value, b_value)? {
+                    Ordering::Equal => {}
+                    ordering => return Ok(ordering),
+                }
+            }
+
+            Ok(a_values
+                .len()
+                .cmp(&b_values.len())
+                .then(type_order(a).cmp(&type_order(b))))
+        }
+        _ if type_order(a) == type_order(b) => compare_values(vm, a, b),
+        _ => Ok(type_order(a).cmp(&type_order(b))),
+    }
+}
+
+fn sequence_values(value: &KValue) -> Vec<KValue> {
+    match value {
+        KValue::List(l) => l.data().to_vec(),
+        KValue::Tuple(t) => t.to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+// The order used when comparing values of differing types
+fn type_order(value: &KValue) -> u8 {
+    use KValue::*;
+
+    match value {
+        Null => 0,
+        Bool(_) => 1,
+        Number(_) => 2,
+        Str(_) => 3,
+        List(_) => 4,
+        Tuple(_) => 5,
+        Range(_) => 6,
+        Map(_) => 7,
+        Object(_) => 8,
+        Function(_) | NativeFunction(_) => 9,
+        Iterator(_) => 10,
+        TemporaryTuple(_) => 11,
+    }
+}
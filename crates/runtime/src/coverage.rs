@@ -0,0 +1,101 @@
+use crate::{KString, Ptr};
+use koto_bytecode::Chunk;
+use std::collections::BTreeMap;
+
+/// A report of the lines that were executed while coverage collection was enabled
+///
+/// See [KotoVmSettings::collect_coverage](crate::KotoVmSettings::collect_coverage).
+#[derive(Clone, Debug, Default)]
+pub struct CoverageReport {
+    /// The coverage of each script that was executed
+    pub scripts: Vec<ScriptCoverage>,
+}
+
+/// The line coverage of an executed script
+///
+/// Lines are zero-based, matching the lines used in source spans.
+#[derive(Clone, Debug, Default)]
+pub struct ScriptCoverage {
+    /// The script's path, if available
+    pub path: Option<KString>,
+    /// The lines that were executed, in ascending order
+    pub covered_lines: Vec<u32>,
+    /// The lines that contain instructions that weren't executed, in ascending order
+    pub uncovered_lines: Vec<u32>,
+}
+
+// The instructions that have been executed in each chunk
+#[derive(Default)]
+pub(crate) struct CoverageData {
+    // Each chunk is paired with a flag for each byte in the chunk,
+    // with the flags at the start of executed instructions being set to true.
+    chunks: Vec<(Ptr<Chunk>, Vec<bool>)>,
+    // The index of the most recently used chunk
+    last_index: usize,
+}
+
+impl CoverageData {
+    // Records that the instruction at the given ip has been executed
+    pub fn record(&mut self, chunk: &Ptr<Chunk>, ip: u32) {
+        let index = match self.chunks.get(self.last_index) {
+            Some((last_chunk, _)) if Ptr::ptr_eq(last_chunk, chunk) => self.last_index,
+            _ => match self
+                .chunks
+                .iter()
+                .position(|(recorded, _)| Ptr::ptr_eq(recorded, chunk))
+            {
+                Some(index) => index,
+                None => {
+                    self.chunks
+                        .push((chunk.clone(), vec![false; chunk.bytes.len()]));
+                    self.chunks.len() - 1
+                }
+            },
+        };
+
+        self.last_index = index;
+
+        if let Some(executed) = self.chunks[index].1.get_mut(ip as usize) {
+            *executed = true;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.last_index = 0;
+    }
+
+    pub fn report(&self) -> CoverageReport {
+        let scripts = self
+            .chunks
+            .iter()
+            .map(|(chunk, executed)| {
+                // Lines mapped to whether or not they've been executed
+                let mut lines = BTreeMap::<u32, bool>::new();
+
+                let source_map = chunk.debug_info.source_map();
+                for (i, (start_ip, span)) in source_map.iter().enumerate() {
+                    let end_ip = source_map
+                        .get(i + 1)
+                        .map_or(executed.len(), |(next_ip, _)| *next_ip as usize);
+                    let covered = executed
+                        .get(*start_ip as usize..end_ip)
+                        .is_some_and(|instructions| instructions.contains(&true));
+
+                    *lines.entry(span.start.line).or_default() |= covered;
+                }
+
+                let (covered, uncovered): (Vec<_>, Vec<_>) =
+                    lines.into_iter().partition(|(_, covered)| *covered);
+
+                ScriptCoverage {
+                    path: chunk.path.clone(),
+                    covered_lines: covered.into_iter().map(|(line, _)| line).collect(),
+                    uncovered_lines: uncovered.into_iter().map(|(line, _)| line).collect(),
+                }
+            })
+            .collect();
+
+        CoverageReport { scripts }
+    }
+}
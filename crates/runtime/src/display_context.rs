@@ -15,6 +15,10 @@ pub struct DisplayContext<'a> {
     parent_containers: Vec<Address>,
     /// True when the resulting string is to be used in a debug context.
     debug: bool,
+    // The maximum nesting depth for containers, with deeper containers having their contents elided
+    max_depth: Option<usize>,
+    // If set, then containers will be displayed across multiple lines with the given indentation
+    pretty_indent: Option<usize>,
 }
 
 impl<'a> DisplayContext<'a> {
@@ -25,6 +29,8 @@ impl<'a> DisplayContext<'a> {
             vm: Some(vm),
             parent_containers: Vec::default(),
             debug: false,
+            max_depth: vm.max_display_depth(),
+            pretty_indent: None,
         }
     }
 
@@ -35,6 +41,8 @@ impl<'a> DisplayContext<'a> {
             vm: Some(vm),
             parent_containers: Vec::default(),
             debug: false,
+            max_depth: vm.max_display_depth(),
+            pretty_indent: None,
         }
     }
 
@@ -44,6 +52,23 @@ impl<'a> DisplayContext<'a> {
         self
     }
 
+    /// Sets the maximum nesting depth for containers
+    ///
+    /// Containers that are nested beyond the maximum depth will have their contents replaced
+    /// with `...`.
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Enables pretty-printing of containers, with the given number of spaces used for indentation
+    ///
+    /// Each entry in a container will be displayed on its own line.
+    pub fn with_pretty_indent(mut self, indent: usize) -> Self {
+        self.pretty_indent = Some(indent);
+        self
+    }
+
     /// Returns the resulting string and consumes the context
     pub fn result(self) -> String {
         self.result
@@ -77,6 +102,16 @@ impl<'a> DisplayContext<'a> {
         self.parent_containers.contains(&id)
     }
 
+    /// Returns true if a container with the given ID should have its contents elided
+    ///
+    /// This is the case when the container is present in the parent container list,
+    /// or when the maximum nesting depth has been reached.
+    pub fn should_elide_container(&self, id: Address) -> bool {
+        self.max_depth
+            .is_some_and(|max_depth| self.parent_containers.len() >= max_depth)
+            || self.is_in_parents(id)
+    }
+
     /// Adds the given ID to the parents list
     ///
     /// Containers should call this before displaying their contained values.
@@ -90,6 +125,40 @@ impl<'a> DisplayContext<'a> {
     pub fn pop_container(&mut self) {
         self.parent_containers.pop();
     }
+
+    /// Appends the separator that should precede a container's entry
+    ///
+    /// Containers should call this before displaying each of their entries, after calling
+    /// [Self::push_container].
+    pub fn append_entry_separator(&mut self, entry_index: usize) {
+        if entry_index > 0 {
+            self.append(',');
+        }
+
+        match self.pretty_indent {
+            Some(indent) => self.append_pretty_line_start(indent * self.parent_containers.len()),
+            None if entry_index > 0 => self.append(' '),
+            None => {}
+        }
+    }
+
+    /// Appends the text that should follow a container's entries
+    ///
+    /// Containers should call this after displaying their entries, before calling
+    /// [Self::pop_container].
+    pub fn append_entries_end(&mut self, entry_count: usize) {
+        if let Some(indent) = self.pretty_indent
+            && entry_count > 0
+        {
+            let depth = self.parent_containers.len().saturating_sub(1);
+            self.append_pretty_line_start(indent * depth);
+        }
+    }
+
+    fn append_pretty_line_start(&mut self, indent: usize) {
+        self.result.push('\n');
+        self.result.extend(std::iter::repeat_n(' ', indent));
+    }
 }
 
 impl fmt::Write for DisplayContext<'_> {
@@ -1,4 +1,8 @@
-use crate::{Ptr, prelude::*};
+use crate::{
+    Ptr, PtrMut,
+    prelude::*,
+    vm::{TypeNames, registered_type_name, value_type_name},
+};
 use koto_bytecode::{Chunk, ModuleLoaderError};
 use koto_parser::format_source_excerpt;
 use std::{error, fmt, time::Duration};
@@ -28,10 +32,7 @@ pub enum ErrorKind {
     Timeout(Duration),
     #[error("unable to borrow an object that is already mutably borrowed")]
     UnableToBorrowObject,
-    #[error(
-        "Unexpected arguments.\n  Expected: {expected}\n  Provided: |{}|",
-        value_types_as_string(unexpected)
-    )]
+    #[error("{}", unexpected_arguments_message(expected, unexpected, None))]
     UnexpectedArguments {
         expected: String,
         unexpected: Vec<KValue>,
@@ -40,22 +41,22 @@ pub enum ErrorKind {
     InsufficientArguments { expected: u8, actual: u8 },
     #[error("too many arguments ({actual}, expected {expected})")]
     TooManyArguments { expected: u8, actual: u8 },
-    #[error("expected {expected}, found {}", unexpected.type_as_string())]
+    #[error("{}", unexpected_type_message(expected, unexpected, None))]
     UnexpectedType {
         expected: String,
         unexpected: KValue,
     },
-    #[error("expected {expected}, found {unexpected}")]
+    #[error("{}", unexpected_object_type_message(expected, unexpected, None))]
     UnexpectedObjectType {
         expected: &'static str,
         unexpected: KString,
     },
-    #[error("{fn_name} is unimplemented for {object_type}")]
+    #[error("{}", unimplemented_message(fn_name, object_type, None))]
     Unimplemented {
         fn_name: &'static str,
         object_type: KString,
     },
-    #[error("unable to perform operation '{op}' with '{}' and '{}'", lhs.type_as_string(), rhs.type_as_string())]
+    #[error("{}", invalid_binary_op_message(lhs, rhs, *op, None))]
     InvalidBinaryOp {
         lhs: KValue,
         rhs: KValue,
@@ -90,6 +91,95 @@ fn display_thrown_value(value: &KValue, vm: Option<&KotoVm>) -> String {
     "Unable to display error message".into()
 }
 
+impl ErrorKind {
+    // Renders the error using registered names for object types
+    //
+    // `None` is returned for errors that don't refer to any value types.
+    fn display_with_type_names(&self, type_names: &TypeNames) -> Option<String> {
+        let type_names = Some(type_names);
+        let result = match self {
+            Self::UnexpectedArguments {
+                expected,
+                unexpected,
+            } => unexpected_arguments_message(expected, unexpected, type_names),
+            Self::UnexpectedType {
+                expected,
+                unexpected,
+            } => unexpected_type_message(expected, unexpected, type_names),
+            Self::UnexpectedObjectType {
+                expected,
+                unexpected,
+            } => unexpected_object_type_message(expected, unexpected, type_names),
+            Self::Unimplemented {
+                fn_name,
+                object_type,
+            } => unimplemented_message(fn_name, object_type, type_names),
+            Self::InvalidBinaryOp { lhs, rhs, op } => {
+                invalid_binary_op_message(lhs, rhs, *op, type_names)
+            }
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+fn unexpected_arguments_message(
+    expected: &str,
+    unexpected: &[KValue],
+    type_names: Option<&TypeNames>,
+) -> String {
+    format!(
+        "Unexpected arguments.\n  Expected: {expected}\n  Provided: |{}|",
+        value_types_as_string(unexpected, type_names)
+    )
+}
+
+fn unexpected_type_message(
+    expected: &str,
+    unexpected: &KValue,
+    type_names: Option<&TypeNames>,
+) -> String {
+    format!(
+        "expected {expected}, found {}",
+        value_type_name(unexpected, type_names)
+    )
+}
+
+fn unexpected_object_type_message(
+    expected: &str,
+    unexpected: &KString,
+    type_names: Option<&TypeNames>,
+) -> String {
+    format!(
+        "expected {expected}, found {}",
+        registered_type_name(unexpected, type_names)
+    )
+}
+
+fn unimplemented_message(
+    fn_name: &str,
+    object_type: &KString,
+    type_names: Option<&TypeNames>,
+) -> String {
+    format!(
+        "{fn_name} is unimplemented for {}",
+        registered_type_name(object_type, type_names)
+    )
+}
+
+fn invalid_binary_op_message(
+    lhs: &KValue,
+    rhs: &KValue,
+    op: BinaryOp,
+    type_names: Option<&TypeNames>,
+) -> String {
+    format!(
+        "unable to perform operation '{op}' with '{}' and '{}'",
+        value_type_name(lhs, type_names),
+        value_type_name(rhs, type_names)
+    )
+}
+
 impl fmt::Debug for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(self, f)
@@ -105,6 +195,10 @@ pub struct Error {
     pub context: Vec<String>,
     /// The stack trace at the point when the error was thrown
     pub trace: Vec<InstructionFrame>,
+    // Display names for object types, attached by the VM when registered names are available
+    //
+    // See `KotoVm::register_type_name`.
+    pub(crate) type_names: Option<PtrMut<TypeNames>>,
 }
 
 impl Error {
@@ -114,6 +208,7 @@ impl Error {
             error,
             context: Vec::new(),
             trace: Vec::new(),
+            type_names: None,
         }
     }
 
@@ -127,6 +222,7 @@ impl Error {
             error,
             context: Vec::new(),
             trace: vec![error_frame],
+            type_names: None,
         }
     }
 
@@ -166,7 +262,14 @@ impl Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.error)?;
+        let message_with_type_names = self
+            .type_names
+            .as_ref()
+            .and_then(|type_names| self.error.display_with_type_names(&type_names.borrow()));
+        match message_with_type_names {
+            Some(message) => f.write_str(&message)?,
+            None => write!(f, "{}", self.error)?,
+        }
         for context in self.context.iter() {
             write!(f, " ({context})")?;
         }
@@ -277,10 +380,10 @@ pub fn unexpected_args_after_instance<T>(
     })
 }
 
-fn value_types_as_string(values: &[KValue]) -> String {
+fn value_types_as_string(values: &[KValue], type_names: Option<&TypeNames>) -> String {
     match values {
         [] => "".to_string(),
-        [single_value] => single_value.type_as_string().to_string(),
+        [single_value] => value_type_name(single_value, type_names).to_string(),
         _ => {
             let mut result = String::new();
             let mut first = true;
@@ -289,7 +392,7 @@ fn value_types_as_string(values: &[KValue]) -> String {
                     result.push_str(", ");
                 }
                 first = false;
-                result.push_str(&value.type_as_string());
+                result.push_str(&value_type_name(value, type_names));
             }
             result
         }
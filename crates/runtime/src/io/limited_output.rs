@@ -0,0 +1,86 @@
+use crate::{KString, KotoFile, KotoRead, KotoWrite, Ptr, PtrMut, Result, runtime_error};
+
+/// Keeps track of the number of bytes written to a VM's output streams
+///
+/// See [KotoVmSettings::max_output_size](crate::KotoVmSettings::max_output_size).
+pub(crate) struct OutputBudget {
+    limit: usize,
+    written: usize,
+}
+
+impl OutputBudget {
+    pub fn new(limit: usize) -> Self {
+        Self { limit, written: 0 }
+    }
+
+    fn consume(&mut self, bytes: usize) -> Result<()> {
+        let written = self.written.saturating_add(bytes);
+
+        if written > self.limit {
+            let limit = self.limit;
+            return runtime_error!("the maximum output size ({limit} bytes) has been exceeded");
+        }
+
+        self.written = written;
+        Ok(())
+    }
+}
+
+/// A wrapper for an output stream that counts written bytes against an [OutputBudget]
+///
+/// The budget is shared between the VM's `stdout` and `stderr`.
+pub(crate) struct LimitedOutput {
+    output: Ptr<dyn KotoFile>,
+    budget: PtrMut<OutputBudget>,
+}
+
+impl LimitedOutput {
+    pub fn new(output: Ptr<dyn KotoFile>, budget: PtrMut<OutputBudget>) -> Self {
+        Self { output, budget }
+    }
+}
+
+impl KotoFile for LimitedOutput {
+    fn id(&self) -> KString {
+        self.output.id()
+    }
+
+    fn path(&self) -> Result<KString> {
+        self.output.path()
+    }
+
+    fn seek(&self, position: u64) -> Result<()> {
+        self.output.seek(position)
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.output.is_terminal()
+    }
+}
+
+impl KotoRead for LimitedOutput {
+    fn read_line(&self) -> Result<Option<String>> {
+        self.output.read_line()
+    }
+
+    fn read_to_string(&self) -> Result<String> {
+        self.output.read_to_string()
+    }
+}
+
+impl KotoWrite for LimitedOutput {
+    fn write(&self, bytes: &[u8]) -> Result<()> {
+        self.budget.borrow_mut().consume(bytes.len())?;
+        self.output.write(bytes)
+    }
+
+    fn write_line(&self, text: &str) -> Result<()> {
+        // The appended newline counts towards the budget
+        self.budget.borrow_mut().consume(text.len() + 1)?;
+        self.output.write_line(text)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.output.flush()
+    }
+}
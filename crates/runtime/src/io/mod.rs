@@ -1,10 +1,15 @@
 mod buffered_file;
 mod file;
+mod limited_output;
+mod path_permissions;
 mod stdio;
 
+pub(crate) use self::limited_output::{LimitedOutput, OutputBudget};
+
 pub use self::{
     buffered_file::BufferedFile,
     file::{KotoFile, KotoRead, KotoWrite},
+    path_permissions::PathPermissions,
     stdio::{
         SystemStderr, SystemStdin, SystemStdout, UnavailableStderr, UnavailableStdin,
         UnavailableStdout,
@@ -0,0 +1,96 @@
+use crate::{Result, runtime_error};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Allow-lists of the paths that can be accessed by the `io` core library module
+///
+/// A path is accessible if it's contained within one of the allowed paths (or matches it exactly).
+/// Paths are canonicalized before being checked, so `..` components and symlinks can't be used
+/// to escape from the allowed paths. The canonicalized paths are only used for checking
+/// permissions, with the `io` functions operating on the paths that were provided.
+///
+/// Read and write permissions are independent, so a path that should be both readable and writable
+/// needs to be included in both lists.
+///
+/// See [KotoVmSettings::path_permissions](crate::KotoVmSettings::path_permissions).
+#[derive(Clone, Debug, Default)]
+pub struct PathPermissions {
+    /// Paths that can be read from
+    pub readable: Vec<PathBuf>,
+    /// Paths that can be written to
+    pub writable: Vec<PathBuf>,
+}
+
+impl PathPermissions {
+    /// Allows the given path to be read from
+    #[must_use]
+    pub fn allow_read(mut self, path: impl Into<PathBuf>) -> Self {
+        self.readable.push(path.into());
+        self
+    }
+
+    /// Allows the given path to be written to
+    #[must_use]
+    pub fn allow_write(mut self, path: impl Into<PathBuf>) -> Self {
+        self.writable.push(path.into());
+        self
+    }
+
+    /// Checks that the path is allowed to be read from, otherwise an error is returned
+    pub fn check_read(&self, path: &Path) -> Result<()> {
+        match resolve_path(path) {
+            Some(resolved) if is_allowed(&resolved, &self.readable) => Ok(()),
+            _ => runtime_error!("reading from '{}' isn't permitted", path.to_string_lossy()),
+        }
+    }
+
+    /// Checks that the path is allowed to be written to, otherwise an error is returned
+    ///
+    /// If the path is a symlink then the link's target is checked, given that writing to the link
+    /// will modify the target.
+    pub fn check_write(&self, path: &Path) -> Result<()> {
+        match resolve_path(path) {
+            Some(resolved) if is_allowed(&resolved, &self.writable) => Ok(()),
+            _ => runtime_error!("writing to '{}' isn't permitted", path.to_string_lossy()),
+        }
+    }
+
+    /// Checks that the path is allowed to be removed, otherwise an error is returned
+    ///
+    /// If the path is a symlink then the link itself is checked rather than its target,
+    /// given that removing the link leaves the target unchanged.
+    pub fn check_remove(&self, path: &Path) -> Result<()> {
+        match resolve_parent(path) {
+            Some(resolved) if is_allowed(&resolved, &self.writable) => Ok(()),
+            _ => runtime_error!("removing '{}' isn't permitted", path.to_string_lossy()),
+        }
+    }
+}
+
+// Canonicalizes the path, allowing for paths to files that don't exist yet
+fn resolve_path(path: &Path) -> Option<PathBuf> {
+    match fs::canonicalize(path) {
+        Ok(resolved) => Some(resolved),
+        Err(_) => resolve_parent(path),
+    }
+}
+
+// Canonicalizes the path's parent and then appends the file name
+fn resolve_parent(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::canonicalize(parent)
+        .ok()
+        .map(|parent| parent.join(file_name))
+}
+
+fn is_allowed(resolved: &Path, allowed: &[PathBuf]) -> bool {
+    allowed
+        .iter()
+        .any(|allowed| fs::canonicalize(allowed).is_ok_and(|allowed| resolved.starts_with(allowed)))
+}
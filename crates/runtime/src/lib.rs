@@ -2,25 +2,29 @@
 
 #![warn(missing_docs)]
 
+mod access_cache;
+mod coverage;
 mod display_context;
 mod error;
 mod io;
 mod types;
 mod vm;
+mod vm_snapshot;
 
 pub mod core_lib;
 pub mod prelude;
 mod send_sync;
 
 pub use crate::{
+    coverage::{CoverageReport, ScriptCoverage},
     display_context::DisplayContext,
     error::{
         Error, ErrorKind, InstructionFrame, Result, unexpected_args,
         unexpected_args_after_instance, unexpected_type,
     },
     io::{
-        BufferedFile, KotoFile, KotoRead, KotoWrite, SystemStderr, SystemStdin, SystemStdout,
-        UnavailableStderr, UnavailableStdin, UnavailableStdout,
+        BufferedFile, KotoFile, KotoRead, KotoWrite, PathPermissions, SystemStderr, SystemStdin,
+        SystemStdout, UnavailableStderr, UnavailableStdin, UnavailableStdout,
     },
     send_sync::{KotoSend, KotoSync},
     types::{
@@ -29,7 +33,11 @@ pub use crate::{
         KotoField, KotoFunction, KotoHasher, KotoIterator, KotoObject, KotoType, MetaKey, MetaMap,
         MethodContext, ReadOp, UnaryOp, ValueKey, ValueMap, ValueVec, WriteOp,
     },
-    vm::{CallArgs, KotoVm, KotoVmSettings, ModuleImportedCallback, ReturnOrYield},
+    vm::{
+        BreakpointCallback, CallArgs, KotoVm, KotoVmSettings, ModuleImportedCallback,
+        ReturnOrYield, StepMode,
+    },
+    vm_snapshot::{StackFrameSnapshot, VmSnapshot},
 };
 pub use koto_derive as derive;
 pub use koto_memory::{Borrow, BorrowMut, KCell, Ptr, PtrMut, lazy, make_ptr, make_ptr_mut};
@@ -201,11 +201,15 @@ struct RangeIterator {
 }
 
 impl RangeIterator {
+    // Ranges with a start bound can be used as iterators,
+    // with ranges like `0..` producing values indefinitely.
     fn new(range: KRange) -> Result<Self> {
-        if range.is_bounded() {
+        if range.start().is_some() {
             Ok(Self { range })
         } else {
-            runtime_error!("unbounded ranges can't be used as iterators (range: {range})")
+            runtime_error!(
+                "ranges without a start value can't be used as iterators (range: {range})"
+            )
         }
     }
 }
@@ -216,7 +220,7 @@ impl KotoIterator for RangeIterator {
     }
 
     fn is_bidirectional(&self) -> bool {
-        true
+        self.range.is_bounded()
     }
 
     fn next_back(&mut self) -> Option<KIteratorOutput> {
@@ -240,9 +244,12 @@ impl Iterator for RangeIterator {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        // Unwrap: only bounded ranges can be used as iterators
-        let remaining = self.range.size().unwrap();
-        (remaining, Some(remaining))
+        match self.range.size() {
+            Some(remaining) => (remaining, Some(remaining)),
+            // Ranges without an end bound are infinite, so there's no upper bound,
+            // and reporting a zero lower bound prevents callers from reserving capacity.
+            None => (0, None),
+        }
     }
 }
 
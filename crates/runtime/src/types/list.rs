@@ -1,26 +1,168 @@
-use crate::{Borrow, BorrowMut, PtrMut, Result, prelude::*};
+use crate::{Borrow, BorrowMut, Ptr, PtrMut, Result, prelude::*};
+use koto_memory::Address;
+use std::{mem, ops::Range};
 
 /// The underlying `Vec` type used by [KList]
 pub type ValueVec = smallvec::SmallVec<[KValue; 4]>;
 
+// The data shared between instances of a KList
+#[derive(Default)]
+struct ListData {
+    values: ListValues,
+    frozen: bool,
+}
+
+// The values contained in a list
+//
+// Sub-lists share their values with the list they were made from, with the shared values only being
+// copied when one of the lists is modified.
+enum ListValues {
+    Owned(ValueVec),
+    Shared {
+        values: Ptr<ValueVec>,
+        bounds: Range<usize>,
+    },
+}
+
+impl ListValues {
+    fn as_slice(&self) -> &[KValue] {
+        match self {
+            Self::Owned(values) => values,
+            Self::Shared { values, bounds } => &values[bounds.clone()],
+        }
+    }
+
+    // Converts shared values into owned values, copying the values if necessary
+    fn make_owned(&mut self) -> &mut ValueVec {
+        if let Self::Shared { values, bounds } = self {
+            let owned = if Ptr::ref_count(values) == 1 {
+                // The values aren't shared with any other lists, so they can be taken directly
+                let values = Ptr::make_mut(values);
+                values.truncate(bounds.end);
+                values.drain(..bounds.start);
+                mem::take(values)
+            } else {
+                values[bounds.clone()].iter().cloned().collect()
+            };
+            *self = Self::Owned(owned);
+        }
+
+        match self {
+            Self::Owned(values) => values,
+            Self::Shared { .. } => unreachable!(),
+        }
+    }
+
+    // Converts owned values into shared values, returning the shared values and their bounds
+    fn make_shared(&mut self) -> (&Ptr<ValueVec>, &Range<usize>) {
+        if let Self::Owned(values) = self {
+            let values = mem::take(values);
+            *self = Self::Shared {
+                bounds: 0..values.len(),
+                values: values.into(),
+            };
+        }
+
+        match self {
+            Self::Shared { values, bounds } => (values, bounds),
+            Self::Owned(_) => unreachable!(),
+        }
+    }
+}
+
+// Sub-lists with fewer values than this are copied rather than shared
+const MIN_SHARED_LEN: usize = 16;
+
+// Returns true if a sub-list of the given length should share its values
+//
+// Sharing is limited to sub-lists that cover at least half of the shared values,
+// which keeps the cost of copying the shared values when either list is modified proportional to
+// the size of the sub-list, and prevents small sub-lists from keeping large lists alive.
+fn should_share(sub_list_len: usize, shared_len: usize) -> bool {
+    sub_list_len >= MIN_SHARED_LEN && sub_list_len * 2 >= shared_len
+}
+
+impl Default for ListValues {
+    fn default() -> Self {
+        Self::Owned(ValueVec::new())
+    }
+}
+
 /// The List type used by the Koto runtime
 #[derive(Clone, Default)]
-pub struct KList(PtrMut<ValueVec>);
+pub struct KList(PtrMut<ListData>);
 
 impl KList {
     /// Creates an empty list with the given capacity
     pub fn with_capacity(capacity: usize) -> Self {
-        Self(ValueVec::with_capacity(capacity).into())
+        Self::with_data(ValueVec::with_capacity(capacity))
     }
 
     /// Creates a list containing the provided data
-    pub fn with_data(data: ValueVec) -> Self {
-        Self(data.into())
+    pub fn with_data(values: ValueVec) -> Self {
+        Self(
+            ListData {
+                values: ListValues::Owned(values),
+                frozen: false,
+            }
+            .into(),
+        )
     }
 
     /// Creates a list containing the provided slice of [Values](crate::KValue)
     pub fn from_slice(data: &[KValue]) -> Self {
-        Self(data.iter().cloned().collect::<ValueVec>().into())
+        Self::with_data(data.iter().cloned().collect())
+    }
+
+    /// Returns a new list containing the values within the given bounds
+    ///
+    /// Large sub-lists share their values with the original list until either list is modified,
+    /// at which point the modified list makes its own copy of the values. Small sub-lists, or
+    /// sub-lists that only cover a small part of the original list, are copied.
+    ///
+    /// If the bounds aren't valid for the list then `None` is returned.
+    pub fn make_sub_list(&self, bounds: Range<usize>) -> Option<Self> {
+        let data = self.0.borrow();
+
+        if bounds.start > bounds.end || bounds.end > data.values.as_slice().len() {
+            return None;
+        }
+
+        match &data.values {
+            ListValues::Shared {
+                values,
+                bounds: current_bounds,
+            } if should_share(bounds.len(), values.len()) => {
+                let offset = current_bounds.start;
+                let bounds = (bounds.start + offset)..(bounds.end + offset);
+                return Some(Self::with_shared_values(values.clone(), bounds));
+            }
+            ListValues::Owned(values) if should_share(bounds.len(), values.len()) => {}
+            _ => return Some(Self::from_slice(&data.values.as_slice()[bounds])),
+        }
+
+        drop(data);
+
+        // Owned values need to be moved into shared storage before they can be shared, which
+        // isn't possible while the list is borrowed elsewhere (e.g. when the list is sliced
+        // while it's being sorted), so the values are copied instead.
+        match self.0.try_borrow_mut() {
+            Some(mut data) => {
+                let (values, _) = data.values.make_shared();
+                Some(Self::with_shared_values(values.clone(), bounds))
+            }
+            None => Some(Self::from_slice(&self.data()[bounds])),
+        }
+    }
+
+    fn with_shared_values(values: Ptr<ValueVec>, bounds: Range<usize>) -> Self {
+        Self(
+            ListData {
+                values: ListValues::Shared { values, bounds },
+                frozen: false,
+            }
+            .into(),
+        )
     }
 
     /// Returns the number of entries of the list
@@ -34,13 +176,37 @@ impl KList {
     }
 
     /// Returns a reference to the list's entries
-    pub fn data(&self) -> Borrow<'_, ValueVec> {
-        self.0.borrow()
+    pub fn data(&self) -> Borrow<'_, [KValue]> {
+        Borrow::map(self.0.borrow(), |data| data.values.as_slice())
     }
 
     /// Returns a mutable reference to the list's entries
+    ///
+    /// The list's entries are returned even if the list has been frozen,
+    /// see [KList::data_mut_checked] for a version that respects the list's frozen state.
     pub fn data_mut(&self) -> BorrowMut<'_, ValueVec> {
-        self.0.borrow_mut()
+        BorrowMut::map(self.0.borrow_mut(), |data| data.values.make_owned())
+    }
+
+    /// Returns a mutable reference to the list's entries, or an error if the list is frozen
+    pub fn data_mut_checked(&self) -> Result<BorrowMut<'_, ValueVec>> {
+        if self.is_frozen() {
+            runtime_error!("unable to modify a frozen List")
+        } else {
+            Ok(self.data_mut())
+        }
+    }
+
+    /// Freezes the list, preventing modifications from Koto scripts
+    ///
+    /// Freezing is shared between all instances of the list.
+    pub fn freeze(&self) {
+        self.0.borrow_mut().frozen = true;
+    }
+
+    /// Returns true if the list has been frozen
+    pub fn is_frozen(&self) -> bool {
+        self.0.borrow().frozen
     }
 
     /// Returns true if the lists refer to the same underlying data
@@ -48,23 +214,28 @@ impl KList {
         PtrMut::ptr_eq(&self.0, &other.0)
     }
 
+    // Returns the address of the list's shared data
+    pub(crate) fn address(&self) -> Address {
+        PtrMut::address(&self.0)
+    }
+
     /// Renders the list to the provided display context
     pub fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
         ctx.append('[');
 
-        let id = PtrMut::address(&self.0);
-        if ctx.is_in_parents(id) {
+        let id = self.address();
+        if ctx.should_elide_container(id) {
             ctx.append("...");
         } else {
             ctx.push_container(id);
 
-            for (i, value) in self.data().iter().enumerate() {
-                if i > 0 {
-                    ctx.append(", ");
-                }
+            let data = self.data();
+            for (i, value) in data.iter().enumerate() {
+                ctx.append_entry_separator(i);
                 value.display(ctx)?;
             }
 
+            ctx.append_entries_end(data.len());
             ctx.pop_container();
         }
 
@@ -72,3 +243,112 @@ impl KList {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_of_numbers(range: Range<i64>) -> KList {
+        KList::with_data(range.map(KValue::from).collect())
+    }
+
+    fn list_as_numbers(list: &KList) -> Vec<i64> {
+        list.data()
+            .iter()
+            .map(|value| match value {
+                KValue::Number(n) => n.into(),
+                unexpected => panic!("Expected a number, found {unexpected:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_list_mem_size() {
+        assert!(size_of::<KList>() <= 16);
+    }
+
+    #[test]
+    fn sub_list_shares_values() {
+        let list = list_of_numbers(0..40);
+        let sub_list = list.make_sub_list(2..38).unwrap();
+        assert_eq!(list_as_numbers(&sub_list), (2..38).collect::<Vec<_>>());
+        assert_eq!(sub_list.data().as_ptr(), list.data()[2..].as_ptr());
+
+        let sub_sub_list = sub_list.make_sub_list(1..33).unwrap();
+        assert_eq!(list_as_numbers(&sub_sub_list), (3..35).collect::<Vec<_>>());
+        assert_eq!(sub_sub_list.data().as_ptr(), list.data()[3..].as_ptr());
+    }
+
+    #[test]
+    fn small_sub_list_is_copied() {
+        let list = list_of_numbers(0..10);
+        let sub_list = list.make_sub_list(2..5).unwrap();
+        assert_eq!(list_as_numbers(&sub_list), [2, 3, 4]);
+        assert_ne!(sub_list.data().as_ptr(), list.data()[2..].as_ptr());
+    }
+
+    #[test]
+    fn sub_list_covering_a_small_part_of_the_list_is_copied() {
+        let list = list_of_numbers(0..100);
+        let sub_list = list.make_sub_list(10..30).unwrap();
+        assert_eq!(list_as_numbers(&sub_list), (10..30).collect::<Vec<_>>());
+        assert_ne!(sub_list.data().as_ptr(), list.data()[10..].as_ptr());
+    }
+
+    #[test]
+    fn sub_list_of_a_borrowed_list_is_copied() {
+        let list = list_of_numbers(0..40);
+        let data = list.data();
+        let sub_list = list.make_sub_list(0..40).unwrap();
+        assert_eq!(list_as_numbers(&sub_list), (0..40).collect::<Vec<_>>());
+        assert_ne!(sub_list.data().as_ptr(), data.as_ptr());
+    }
+
+    #[test]
+    fn sub_list_with_invalid_bounds() {
+        let list = list_of_numbers(0..40);
+        assert!(list.make_sub_list(5..41).is_none());
+        let sub_list = list.make_sub_list(5..40).unwrap();
+        assert!(sub_list.make_sub_list(0..36).is_none());
+    }
+
+    #[test]
+    fn modifying_a_sub_list_copies_values() {
+        let list = list_of_numbers(0..40);
+        let sub_list = list.make_sub_list(2..38).unwrap();
+
+        sub_list.data_mut().push(99.into());
+        assert_ne!(sub_list.data().as_ptr(), list.data()[2..].as_ptr());
+        let mut expected = (2..38).collect::<Vec<_>>();
+        expected.push(99);
+        assert_eq!(list_as_numbers(&sub_list), expected);
+        assert_eq!(list_as_numbers(&list), (0..40).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn modifying_the_original_list_copies_values() {
+        let list = list_of_numbers(0..40);
+        let sub_list = list.make_sub_list(2..38).unwrap();
+
+        list.data_mut()[3] = 99.into();
+        let mut expected = (0..40).collect::<Vec<_>>();
+        expected[3] = 99;
+        assert_eq!(list_as_numbers(&list), expected);
+        assert_eq!(list_as_numbers(&sub_list), (2..38).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn values_are_reclaimed_when_no_longer_shared() {
+        let list = list_of_numbers(0..40);
+        let sub_list = list.make_sub_list(2..38).unwrap();
+        let original_ptr = list.data().as_ptr();
+        drop(list);
+
+        sub_list.data_mut().push(99.into());
+        let mut expected = (2..38).collect::<Vec<_>>();
+        expected.push(99);
+        assert_eq!(list_as_numbers(&sub_list), expected);
+        // The values were moved within the existing allocation rather than being copied
+        assert_eq!(sub_list.data().as_ptr(), original_ptr);
+    }
+}
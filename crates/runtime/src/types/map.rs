@@ -1,5 +1,6 @@
 use crate::{Borrow, BorrowMut, Error, PtrMut, Result, prelude::*};
 use indexmap::{Equivalent, IndexMap};
+use koto_memory::Address;
 use rustc_hash::FxHasher;
 use std::{
     hash::{BuildHasherDefault, Hash},
@@ -58,10 +59,17 @@ impl FromIterator<(ValueKey, KValue)> for ValueMap {
     }
 }
 
+// The data shared between instances of a KMap
+#[derive(Default)]
+struct MapData {
+    entries: ValueMap,
+    frozen: bool,
+}
+
 /// The core hash map value type used in Koto, containing a [ValueMap] and a [MetaMap]
 #[derive(Clone, Default)]
 pub struct KMap {
-    data: PtrMut<ValueMap>,
+    data: PtrMut<MapData>,
     meta: Option<PtrMut<MetaMap>>,
 }
 
@@ -91,7 +99,11 @@ impl KMap {
     /// Creates a KMap initialized with the provided data and meta map
     pub fn with_contents(data: ValueMap, meta: Option<MetaMap>) -> Self {
         Self {
-            data: data.into(),
+            data: MapData {
+                entries: data,
+                frozen: false,
+            }
+            .into(),
             meta: meta.map(PtrMut::from),
         }
     }
@@ -106,12 +118,36 @@ impl KMap {
 
     /// Provides a reference to the data map
     pub fn data(&self) -> Borrow<'_, ValueMap> {
-        self.data.borrow()
+        Borrow::map(self.data.borrow(), |data| &data.entries)
     }
 
     /// Provides a mutable reference to the data map
+    ///
+    /// The data map is returned even if the map has been frozen,
+    /// see [KMap::data_mut_checked] for a version that respects the map's frozen state.
     pub fn data_mut(&self) -> BorrowMut<'_, ValueMap> {
-        self.data.borrow_mut()
+        BorrowMut::map(self.data.borrow_mut(), |data| &mut data.entries)
+    }
+
+    /// Provides a mutable reference to the data map, or an error if the map is frozen
+    pub fn data_mut_checked(&self) -> Result<BorrowMut<'_, ValueMap>> {
+        if self.is_frozen() {
+            runtime_error!("unable to modify a frozen Map")
+        } else {
+            Ok(self.data_mut())
+        }
+    }
+
+    /// Freezes the map, preventing modifications to its entries from Koto scripts
+    ///
+    /// Freezing is shared between all instances of the map.
+    pub fn freeze(&self) {
+        self.data.borrow_mut().frozen = true;
+    }
+
+    /// Returns true if the map has been frozen
+    pub fn is_frozen(&self) -> bool {
+        self.data.borrow().frozen
     }
 
     /// Provides a reference to the KMap's meta map
@@ -140,7 +176,7 @@ impl KMap {
     where
         K: Hash + Equivalent<ValueKey> + ?Sized,
     {
-        self.data.borrow().get(key).cloned()
+        self.data().get(key).cloned()
     }
 
     /// Returns a clone of the meta value corresponding to the given key
@@ -220,6 +256,11 @@ impl KMap {
         PtrMut::ptr_eq(&self.data, &other.data)
     }
 
+    // Returns the address of the map's shared data
+    pub(crate) fn address(&self) -> Address {
+        PtrMut::address(&self.data)
+    }
+
     /// If present, returns the @type meta value as a [KString], recursively going up the @base chain.
     pub fn meta_type(&self) -> Option<KString> {
         use KValue::*;
@@ -255,17 +296,16 @@ impl KMap {
 
             ctx.append('{');
 
-            let id = PtrMut::address(&self.data);
+            let id = self.address();
 
-            if ctx.is_in_parents(id) {
+            if ctx.should_elide_container(id) {
                 ctx.append("...");
             } else {
                 ctx.push_container(id);
 
-                for (i, (key, value)) in self.data().iter().enumerate() {
-                    if i > 0 {
-                        ctx.append(", ");
-                    }
+                let data = self.data();
+                for (i, (key, value)) in data.iter().enumerate() {
+                    ctx.append_entry_separator(i);
 
                     let mut key_ctx = DisplayContext::default();
                     key.value().display(&mut key_ctx)?;
@@ -275,6 +315,7 @@ impl KMap {
                     value.display(ctx)?;
                 }
 
+                ctx.append_entries_end(data.len());
                 ctx.pop_container();
             }
 
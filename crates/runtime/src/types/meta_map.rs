@@ -73,6 +73,20 @@ pub enum MetaKey {
     /// Named entries are used in [`KMaps`][crate::KMap], so that shared named items can be
     /// made available without them being inserted into the map's contents.
     Named(KString),
+    /// A computed property getter
+    ///
+    /// e.g. `@get my_property`
+    ///
+    /// The getter is called when the property is accessed on a [KMap](crate::KMap) that doesn't
+    /// contain a matching data entry.
+    Get(KString),
+    /// A computed property setter
+    ///
+    /// e.g. `@set my_property`
+    ///
+    /// The setter is called when the property is assigned on a [KMap](crate::KMap) that doesn't
+    /// contain a matching data entry.
+    Set(KString),
     /// A test function
     ///
     /// e.g. `@test my_test`
@@ -339,6 +353,12 @@ pub fn meta_id_to_key(id: MetaKeyId, name: Option<KString>) -> Result<MetaKey> {
             MetaKey::Named(name.ok_or_else(|| Error::from("missing name for named meta entry"))?)
         }
         MetaKeyId::Test => MetaKey::Test(name.ok_or_else(|| Error::from("missing name for test"))?),
+        MetaKeyId::Get => {
+            MetaKey::Get(name.ok_or_else(|| Error::from("missing name for property getter"))?)
+        }
+        MetaKeyId::Set => {
+            MetaKey::Set(name.ok_or_else(|| Error::from("missing name for property setter"))?)
+        }
         MetaKeyId::PreTest => MetaKey::PreTest,
         MetaKeyId::PostTest => MetaKey::PostTest,
         MetaKeyId::Main => MetaKey::Main,
@@ -350,6 +370,29 @@ pub fn meta_id_to_key(id: MetaKeyId, name: Option<KString>) -> Result<MetaKey> {
     Ok(result)
 }
 
+/// Converts a meta key string into a [MetaKey]
+///
+/// The string should match the key as it's written in a script, e.g. `@type`, `@+`,
+/// or `@meta my_named_key`.
+pub fn meta_key_from_str(key: &str) -> Result<MetaKey> {
+    let (id, name) = match key.split_once(' ') {
+        Some((id, name)) => (id, Some(name.trim())),
+        None => (key, None),
+    };
+
+    let meta_id = (0..MetaKeyId::Invalid as u8)
+        .filter_map(|byte| MetaKeyId::try_from(byte).ok())
+        .find(|meta_id| meta_id.as_str() == id);
+
+    match (meta_id, name) {
+        (Some(meta_id), Some(name)) if meta_id.is_named() && !name.is_empty() => {
+            meta_id_to_key(meta_id, Some(name.into()))
+        }
+        (Some(meta_id), None) if !meta_id.is_named() => meta_id_to_key(meta_id, None),
+        _ => runtime_error!("'{key}' is not a valid meta key"),
+    }
+}
+
 /// Support efficient map accesses with `&str`
 impl Equivalent<MetaKey> for str {
     fn equivalent(&self, other: &MetaKey) -> bool {
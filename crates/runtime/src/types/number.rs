@@ -46,11 +46,13 @@ impl KNumber {
 
     /// Returns the integer closest to the number
     ///
-    /// Half-way values get rounded away from zero.
+    /// Half-way values get rounded to the nearest even integer.
+    /// NaN and infinite values are returned unchanged.
     #[must_use]
     pub fn round(self) -> Self {
         match self {
-            Self::F64(n) => Self::I64(n.round() as i64),
+            Self::F64(n) if !n.is_finite() => self,
+            Self::F64(n) => Self::I64(n.round_ties_even() as i64),
             Self::I64(n) => Self::I64(n),
         }
     }
@@ -90,6 +92,20 @@ impl KNumber {
         }
     }
 
+    /// Converts a possibly-negative index into an index for a container with the given size
+    ///
+    /// Negative indices count back from the end of the container, with `-1` referring to the last
+    /// entry. `None` is returned if the index is out of bounds.
+    pub(crate) fn resolve_index(self, size: usize) -> Option<usize> {
+        let result = if self < 0.0 {
+            size.checked_sub(i64::from(self).unsigned_abs() as usize)?
+        } else {
+            usize::from(self)
+        };
+
+        (result < size).then_some(result)
+    }
+
     /// Returns the result of raising self to the power of `other`
     ///
     /// If both inputs are i64s then the result will also be an i64,
@@ -137,6 +137,15 @@ pub trait KotoObject: KotoType + KotoCopy + KotoAccess + KotoSend + KotoSync + A
         unimplemented_error("@index", self.type_string())
     }
 
+    /// Called for indexing operations that use a range, e.g. `x[1..3]`
+    ///
+    /// Implementing `slice` allows a type to return a cheap view of its data.
+    ///
+    /// The default implementation defers to [`KotoObject::index`].
+    fn slice(&self, range: &KRange) -> Result<KValue> {
+        self.index(&range.clone().into())
+    }
+
     /// Called when assigning a value via indexing, e.g. `x[0] = 99`
     ///
     /// See also: [KotoObject::size]
@@ -171,6 +180,9 @@ pub trait KotoObject: KotoType + KotoCopy + KotoAccess + KotoSend + KotoSync + A
     /// Allows the object to behave as a function
     ///
     /// Objects that implement `call` should return `true` from [`KotoObject::is_callable`].
+    ///
+    /// The call's arguments are available via [`CallContext::args`], with any unpacked arguments
+    /// (e.g. `x args...`) already expanded into individual values.
     fn call(&mut self, ctx: &mut CallContext) -> Result<KValue> {
         let _ = ctx;
         unimplemented_error("@||", self.type_string())
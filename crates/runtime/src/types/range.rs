@@ -147,6 +147,34 @@ impl KRange {
         (start as usize)..(end as usize)
     }
 
+    /// Returns the range translated into indices for a container with the given size
+    ///
+    /// Negative boundaries count back from the end of the container, in the same way as indexing
+    /// with a negative number, e.g. `x[-2..]` refers to the last two values in `x`.
+    /// The resolved boundaries are then clamped as in [KRange::indices].
+    ///
+    /// An error is returned if a negative boundary would refer to a position before the start of
+    /// the container.
+    pub fn resolve_indices(&self, size: usize) -> Result<Range<usize>, Error> {
+        let resolve = |bound: i64| -> Result<i64, Error> {
+            if bound >= 0 {
+                return Ok(bound);
+            }
+            match KNumber::from(bound).resolve_index(size) {
+                Some(index) => Ok(index as i64),
+                None => runtime_error!("index out of bounds - index: {bound}, size: {size}"),
+            }
+        };
+
+        let start = self.start().map(resolve).transpose()?;
+        let end = match self.end() {
+            Some((end, inclusive)) => Some((resolve(end)?, inclusive)),
+            None => None,
+        };
+
+        Ok(Self::new(start, end).indices(size))
+    }
+
     /// Returns the intersection of two ranges
     pub fn intersection(&self, other: &KRange) -> Option<Self> {
         let this = self.as_bounded_range();
@@ -183,12 +211,22 @@ impl KRange {
     ///
     /// This is used by RangeIterator and in the VM to iterate over temporary ranges.
     ///
-    /// Returns an error if the range is not bounded.
+    /// Ranges without an end bound (e.g. `0..`) will produce values indefinitely.
+    ///
+    /// Returns an error if the range doesn't have a start bound.
     pub fn pop_front(&mut self) -> Result<Option<i64>, Error> {
         use Inner::*;
         use Ordering::*;
 
         let result = match &mut self.0 {
+            From { start } => {
+                let result = *start;
+                match start.checked_add(1) {
+                    Some(next) => *start = next,
+                    None => return runtime_error!("range overflow (range: {self})"),
+                }
+                Some(result)
+            }
             Bounded {
                 start,
                 end,
@@ -135,18 +135,23 @@ impl KTuple {
             Inner::SliceLarge(slice) => &slice.data,
             Inner::Slice(slice) => &slice.data,
         });
-        ctx.push_container(id);
         ctx.append('(');
 
-        for (i, value) in self.iter().enumerate() {
-            if i > 0 {
-                ctx.append(", ");
+        if ctx.should_elide_container(id) {
+            ctx.append("...");
+        } else {
+            ctx.push_container(id);
+
+            for (i, value) in self.iter().enumerate() {
+                ctx.append_entry_separator(i);
+                value.display(ctx)?;
             }
-            value.display(ctx)?;
+
+            ctx.append_entries_end(self.len());
+            ctx.pop_container();
         }
 
         ctx.append(')');
-        ctx.pop_container();
 
         Ok(())
     }
@@ -1,6 +1,8 @@
 //! The core value type used in the Koto runtime
 
 use crate::{KFunction, Ptr, Result, lazy, prelude::*};
+use koto_memory::Address;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::{
     fmt::{self, Write},
     result::Result as StdResult,
@@ -93,6 +95,90 @@ impl KValue {
         Ok(result)
     }
 
+    /// Freezes the value's data, along with the data of any nested containers
+    ///
+    /// Values that aren't containers are left unchanged.
+    /// See [KList::freeze] and [KMap::freeze].
+    pub fn deep_freeze(&self) {
+        // Containers are only visited once, allowing cyclic structures to be frozen
+        let mut visited = FxHashSet::default();
+        let mut pending = vec![self.clone()];
+
+        while let Some(value) = pending.pop() {
+            match value {
+                KValue::List(l) if visited.insert(l.address()) => {
+                    l.freeze();
+                    pending.extend(l.data().iter().cloned());
+                }
+                KValue::Map(m) if visited.insert(m.address()) => {
+                    m.freeze();
+                    pending.extend(m.data().values().cloned());
+                }
+                KValue::Tuple(t) => pending.extend(t.iter().cloned()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns a deep copy of the value, with the copy's containers being frozen
+    ///
+    /// The value itself is left unchanged. Containers that refer to themselves are supported,
+    /// with the copy preserving the structure of the original value.
+    ///
+    /// See [KValue::deep_copy] and [KValue::deep_freeze].
+    pub fn deep_frozen_copy(&self) -> KValue {
+        fn copy(value: &KValue, copies: &mut FxHashMap<Address, KValue>) -> KValue {
+            match value {
+                KValue::List(l) => {
+                    if let Some(existing) = copies.get(&l.address()) {
+                        return existing.clone();
+                    }
+                    // The copy is registered before its contents are copied so that
+                    // self-references are resolved to the copy
+                    let result = KList::with_capacity(l.len());
+                    copies.insert(l.address(), result.clone().into());
+                    let data = l.data().iter().map(|v| copy(v, copies)).collect();
+                    *result.data_mut() = data;
+                    result.freeze();
+                    result.into()
+                }
+                KValue::Map(m) => {
+                    if let Some(existing) = copies.get(&m.address()) {
+                        return existing.clone();
+                    }
+                    let meta = m.meta_map().map(|meta| meta.borrow().clone());
+                    let result = KMap::with_contents(ValueMap::default(), meta);
+                    copies.insert(m.address(), result.clone().into());
+                    let data = m
+                        .data()
+                        .iter()
+                        .map(|(k, v)| (k.clone(), copy(v, copies)))
+                        .collect();
+                    *result.data_mut() = data;
+                    result.freeze();
+                    result.into()
+                }
+                KValue::Tuple(t) => {
+                    let result = t.iter().map(|v| copy(v, copies)).collect::<Vec<_>>();
+                    KValue::Tuple(result.into())
+                }
+                _ => value.clone(),
+            }
+        }
+
+        copy(self, &mut FxHashMap::default())
+    }
+
+    /// Returns true if the value evaluates as `true` in a boolean context
+    ///
+    /// `null` and `false` are falsy, and every other value is truthy,
+    /// including `0`, empty strings, and empty containers.
+    ///
+    /// This is the rule used by conditional expressions, loops, and logical operators.
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, KValue::Null | KValue::Bool(false))
+    }
+
     /// Returns true if the value has function-like callable behaviour
     pub fn is_callable(&self) -> bool {
         use KValue::*;
@@ -1,15 +1,24 @@
 use crate::{
-    InstructionFrame, KFunction, Ptr, Result, UnavailableStderr, UnavailableStdin,
-    UnavailableStdout,
-    core_lib::{CoreLib, io::File, koto::Unimplemented},
+    CoverageReport, InstructionFrame, KFunction, PathPermissions, Ptr, PtrMut, Result,
+    StackFrameSnapshot, UnavailableStderr, UnavailableStdin, UnavailableStdout, VmSnapshot,
+    access_cache::AccessCache,
+    core_lib::{
+        CoreLib,
+        io::File,
+        koto::Unimplemented,
+        string::{char_bounds, char_count},
+    },
+    coverage::CoverageData,
     error::{Error, ErrorKind},
+    io::{LimitedOutput, OutputBudget},
     prelude::*,
     types::{FunctionContext, meta_id_to_key, value::RegisterSlice},
 };
 use instant::Instant;
 use koto_bytecode::{Chunk, Instruction, InstructionReader, ModuleLoader};
 use koto_parser::{
-    ConstantIndex, MetaKeyId, StringAlignment, StringFormatOptions, StringFormatRepresentation,
+    ConstantIndex, MetaKeyId, Span, StringAlignment, StringFormatOptions,
+    StringFormatRepresentation,
 };
 use rustc_hash::FxHasher;
 use smallvec::SmallVec;
@@ -41,6 +50,15 @@ struct VmContext {
     loader: KCell<ModuleLoader>,
     // The cached export maps of imported modules
     module_cache: KCell<ModuleCache>,
+    // Display names for object types, see `KotoVm::register_type_name`
+    //
+    // The names are shared with errors thrown by the VM so that they can be used when the errors
+    // are displayed.
+    type_names: PtrMut<TypeNames>,
+    // Breakpoints and stepping state, see `KotoVmSettings::breakpoint_callback`
+    debugger: KCell<DebuggerState>,
+    // The instructions that have been executed, see `KotoVmSettings::collect_coverage`
+    coverage: KCell<CoverageData>,
 }
 
 impl Default for VmContext {
@@ -50,9 +68,22 @@ impl Default for VmContext {
 }
 
 impl VmContext {
-    fn with_settings(settings: KotoVmSettings) -> Self {
+    fn with_settings(mut settings: KotoVmSettings) -> Self {
         let core_lib = CoreLib::default();
 
+        if settings.sandboxed {
+            core_lib.sandbox();
+        }
+
+        // Output streams are only wrapped when the output size is limited,
+        // see `KotoVmSettings::max_output_size`.
+        if let Some(limit) = settings.max_output_size {
+            let budget: PtrMut<OutputBudget> = make_ptr_mut!(OutputBudget::new(limit));
+            settings.stdout =
+                make_ptr!(LimitedOutput::new(settings.stdout.clone(), budget.clone()));
+            settings.stderr = make_ptr!(LimitedOutput::new(settings.stderr.clone(), budget));
+        }
+
         core_lib.os.insert(
             "args",
             KValue::Tuple(
@@ -83,6 +114,9 @@ impl VmContext {
             core_lib,
             loader: ModuleLoader::default().into(),
             module_cache: ModuleCache::default().into(),
+            type_names: TypeNames::default().into(),
+            debugger: DebuggerState::default().into(),
+            coverage: CoverageData::default().into(),
         }
     }
 }
@@ -93,6 +127,26 @@ pub trait ModuleImportedCallback: Fn(&Path) + KotoSend + KotoSync {}
 // Implement the trait for any matching function
 impl<T> ModuleImportedCallback for T where T: Fn(&Path) + KotoSend + KotoSync {}
 
+/// The trait used by the breakpoint callback mechanism
+///
+/// See [KotoVmSettings::breakpoint_callback].
+pub trait BreakpointCallback: Fn(&mut KotoVm, Span) -> StepMode + KotoSend + KotoSync {}
+
+// Implement the trait for any matching function
+impl<T> BreakpointCallback for T where T: Fn(&mut KotoVm, Span) -> StepMode + KotoSend + KotoSync {}
+
+/// Defines how execution should proceed after execution has been paused
+///
+/// See [KotoVmSettings::breakpoint_callback].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StepMode {
+    /// Execution continues until the next breakpoint is reached
+    #[default]
+    Continue,
+    /// Execution pauses again when the next line of the script is reached
+    Step,
+}
+
 /// The configurable settings that should be used by the Koto runtime
 pub struct KotoVmSettings {
     /// Whether or not tests should be run when importing modules
@@ -139,6 +193,77 @@ pub struct KotoVmSettings {
     ///
     /// Default: `vec![]`
     pub args: Vec<String>,
+
+    /// An optional limit on the nesting depth of containers when values are being displayed
+    ///
+    /// Containers that are nested beyond the limit will be displayed with `...` in place of
+    /// their contents, e.g. with a limit of `2`, `[1, [2, [3]]]` will be displayed as
+    /// `[1, [2, [...]]]`.
+    ///
+    /// Default: `None`
+    pub max_display_depth: Option<usize>,
+
+    /// Whether or not the values of a script's top-level locals should be retained after the
+    /// script has finished running
+    ///
+    /// Retained locals are available via [KotoVm::snapshot] until the next script is run.
+    ///
+    /// Default: `false`
+    pub retain_top_level_locals: bool,
+
+    /// An optional callback that is called whenever execution is paused
+    ///
+    /// Execution is paused when a line containing a breakpoint is reached
+    /// (see [KotoVm::add_breakpoint]), or when a new line is reached while stepping
+    /// (see [KotoVm::set_step_mode]).
+    ///
+    /// The callback is provided with the VM, allowing its state to be inspected via
+    /// [KotoVm::snapshot], along with the span of the source that's about to be executed.
+    /// Execution resumes when the callback returns, with the returned [StepMode] determining
+    /// where execution should be paused next.
+    ///
+    /// Breakpoints are only checked when a callback is provided.
+    ///
+    /// Default: `None`
+    pub breakpoint_callback: Option<Box<dyn BreakpointCallback>>,
+
+    /// Whether or not the runtime should record which lines of source have been executed
+    ///
+    /// The results are available via [KotoVm::coverage].
+    ///
+    /// Default: `false`
+    pub collect_coverage: bool,
+
+    /// An optional limit on the number of bytes that can be written to `stdout` and `stderr`
+    ///
+    /// The limit applies to the combined output of both streams, and writes that would exceed
+    /// the limit will throw an error. The limit is shared by all scripts run by the VM, and can't be
+    /// changed after the VM has been created.
+    ///
+    /// The output streams are only wrapped with a byte counter when a limit is set here.
+    ///
+    /// Default: `None`
+    pub max_output_size: Option<usize>,
+
+    /// Whether or not the runtime should prevent scripts from accessing the filesystem or the
+    /// operating system
+    ///
+    /// When enabled, the core library functions that access the filesystem or the operating
+    /// system (e.g. `io.open`, `os.command`) will throw an error when called,
+    /// and modules can't be imported from disk.
+    ///
+    /// Scripts will still be able to write to the runtime's `stdout` and `stderr`.
+    ///
+    /// Default: `false`
+    pub sandboxed: bool,
+
+    /// Optional allow-lists of the paths that can be accessed by the `io` core library module
+    ///
+    /// When set, `io` functions that read from or write to a path will throw an error if the
+    /// path isn't permitted. See [PathPermissions] for more information.
+    ///
+    /// Default: `None`
+    pub path_permissions: Option<PathPermissions>,
 }
 
 impl Default for KotoVmSettings {
@@ -151,6 +276,13 @@ impl Default for KotoVmSettings {
             stdout: make_ptr!(UnavailableStdout::default()),
             stderr: make_ptr!(UnavailableStderr::default()),
             args: vec![],
+            max_display_depth: None,
+            retain_top_level_locals: false,
+            breakpoint_callback: None,
+            collect_coverage: false,
+            max_output_size: None,
+            sandboxed: false,
+            path_permissions: None,
         }
     }
 }
@@ -178,8 +310,13 @@ pub struct KotoVm {
     string_builders: Vec<String>,
     // The ip that produced the most recently read instruction, used for debug and error traces
     instruction_ip: u32,
+    // Cached map slots for `.` access operations
+    access_cache: AccessCache,
     // The current execution state
     execution_state: ExecutionState,
+    // The top-level locals of the most recently completed run,
+    // see `KotoVmSettings::retain_top_level_locals`
+    retained_locals: Vec<(KString, KValue)>,
 }
 
 /// The execution state of a VM
@@ -213,7 +350,9 @@ impl KotoVm {
             sequence_builders: Vec::new(),
             string_builders: Vec::new(),
             instruction_ip: 0,
+            access_cache: AccessCache::default(),
             execution_state: ExecutionState::Inactive,
+            retained_locals: Vec::new(),
         }
     }
 
@@ -236,7 +375,9 @@ impl KotoVm {
             sequence_builders: Vec::new(),
             string_builders: Vec::new(),
             instruction_ip: 0,
+            access_cache: AccessCache::default(),
             execution_state: ExecutionState::Inactive,
+            retained_locals: Vec::new(),
         }
     }
 
@@ -250,6 +391,35 @@ impl KotoVm {
         &self.context.prelude
     }
 
+    /// Registers a display name for an object type
+    ///
+    /// The name will be used in place of the type's [KotoType::type_static] string whenever the
+    /// type of an object is requested by the runtime, e.g. by `koto.type`, when checking type
+    /// hints, and when rendering error messages.
+    ///
+    /// Registered names are shared between all VMs that have been spawned from this VM.
+    pub fn register_type_name<T: KotoType>(&self, name: impl Into<KString>) {
+        self.context
+            .type_names
+            .borrow_mut()
+            .insert(T::type_static(), name.into());
+    }
+
+    /// Returns the type of the value as a string, taking any registered type names into account
+    ///
+    /// See [KotoVm::register_type_name].
+    pub fn type_name(&self, value: &KValue) -> KString {
+        value_type_name(value, Some(&self.context.type_names.borrow()))
+    }
+
+    // Attaches the registered type names to an error so that they're used when it's displayed
+    fn attach_registered_type_names(&self, mut error: Error) -> Error {
+        if !self.context.type_names.borrow().is_empty() {
+            error.type_names = Some(self.context.type_names.clone());
+        }
+        error
+    }
+
     /// The active module's exports map
     ///
     /// Note that this is the exports map of the active module, so during execution the returned
@@ -268,6 +438,13 @@ impl KotoVm {
         &self.context.settings.stdin
     }
 
+    /// The allow-lists of paths that can be accessed by the `io` core library module
+    ///
+    /// See [KotoVmSettings::path_permissions].
+    pub fn path_permissions(&self) -> Option<&PathPermissions> {
+        self.context.settings.path_permissions.as_ref()
+    }
+
     /// The `stdout` wrapper used by the VM
     pub fn stdout(&self) -> &Ptr<dyn KotoFile> {
         &self.context.settings.stdout
@@ -278,13 +455,104 @@ impl KotoVm {
         &self.context.settings.stderr
     }
 
+    /// The maximum nesting depth of containers when displaying values
+    ///
+    /// See [KotoVmSettings::max_display_depth].
+    pub fn max_display_depth(&self) -> Option<usize> {
+        self.context.settings.max_display_depth
+    }
+
+    /// Adds a breakpoint to the given line of a script
+    ///
+    /// Lines are zero-based, matching the lines used in source spans.
+    /// If `path` is `None` then the breakpoint applies to all scripts.
+    ///
+    /// Breakpoints are shared between all VMs that have been spawned from this VM,
+    /// and are only checked when [KotoVmSettings::breakpoint_callback] is provided.
+    pub fn add_breakpoint(&self, path: Option<KString>, line: u32) {
+        self.context
+            .debugger
+            .borrow_mut()
+            .breakpoints
+            .push((path, line));
+    }
+
+    /// Removes all breakpoints that have been added via [KotoVm::add_breakpoint]
+    pub fn clear_breakpoints(&self) {
+        self.context.debugger.borrow_mut().breakpoints.clear();
+    }
+
+    /// Sets the VM's step mode
+    ///
+    /// Setting the mode to [StepMode::Step] before running a script will cause execution to be
+    /// paused when the script's first line is reached.
+    ///
+    /// See [KotoVmSettings::breakpoint_callback].
+    pub fn set_step_mode(&self, step_mode: StepMode) {
+        self.context.debugger.borrow_mut().step_mode = step_mode;
+    }
+
+    /// Returns a report of the lines that have been executed by the runtime
+    ///
+    /// The report will be empty unless [KotoVmSettings::collect_coverage] is enabled.
+    ///
+    /// Coverage is shared between all VMs that have been spawned from this VM.
+    pub fn coverage(&self) -> CoverageReport {
+        self.context.coverage.borrow().report()
+    }
+
+    /// Clears any coverage that has been collected by the runtime
+    pub fn clear_coverage(&self) {
+        self.context.coverage.borrow_mut().clear();
+    }
+
+    /// Returns a read-only snapshot of the VM's current state
+    ///
+    /// See [VmSnapshot] for more information.
+    pub fn snapshot(&self) -> VmSnapshot {
+        let locals = match self
+            .call_stack
+            .iter()
+            .rev()
+            .find(|frame| frame.is_top_level)
+        {
+            Some(frame) => top_level_locals(&frame.chunk, &self.registers[frame.register_base..]),
+            None => self.retained_locals.clone(),
+        };
+
+        let call_stack = self
+            .call_stack
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let ip = if i == self.call_stack.len() - 1 {
+                    self.instruction_ip
+                } else {
+                    frame.return_instruction_ip
+                };
+
+                StackFrameSnapshot {
+                    path: frame.chunk.path.clone(),
+                    span: frame.chunk.debug_info.get_source_span(ip),
+                }
+            })
+            .collect();
+
+        VmSnapshot {
+            locals,
+            exports: self.exports.data().clone(),
+            call_stack,
+        }
+    }
+
     /// Runs the provided [Chunk], returning the resulting [KValue]
     pub fn run(&mut self, chunk: Ptr<Chunk>) -> Result<KValue> {
         // Set up an execution frame to run the chunk in
         let frame_base = self.next_register();
+        let frame_register_base = self.register_base + frame_base as usize;
         self.registers.push(KValue::Null); // Instance register
         self.push_frame(
-            chunk,
+            chunk.clone(),
             0,
             frame_base,
             None,
@@ -296,12 +564,16 @@ impl KotoVm {
         );
 
         // Ensure that execution stops here if an error is thrown
-        self.frame_mut().execution_barrier = true;
+        let frame = self.frame_mut();
+        frame.execution_barrier = true;
+        frame.is_top_level = true;
 
         // Run the chunk
         let result = self.execute_instructions();
         if result.is_err() {
             self.pop_frame(KValue::Null)?;
+        } else if self.context.settings.retain_top_level_locals && self.call_stack.is_empty() {
+            self.retained_locals = top_level_locals(&chunk, &self.registers[frame_register_base..]);
         }
 
         // Reset the register stack back to where it was at the start of the run
@@ -757,6 +1029,8 @@ impl KotoVm {
             .settings
             .execution_limit
             .map(ExecutionTimeout::new);
+        let debugging = self.context.settings.breakpoint_callback.is_some();
+        let collecting_coverage = self.context.settings.collect_coverage;
 
         self.instruction_ip = self.ip();
 
@@ -777,6 +1051,17 @@ impl KotoVm {
                     .map(|_| KValue::Null);
             }
 
+            if debugging {
+                self.check_for_breakpoint();
+            }
+
+            if collecting_coverage {
+                self.context
+                    .coverage
+                    .borrow_mut()
+                    .record(&self.reader.chunk, self.instruction_ip);
+            }
+
             match self.execute_instruction(instruction) {
                 Ok(ControlFlow::Continue) => {}
                 Ok(ControlFlow::Return(value)) => {
@@ -788,16 +1073,28 @@ impl KotoVm {
                     return Ok(value);
                 }
                 Err(error) => match self.pop_call_stack_on_error(error.clone(), true) {
-                    Ok((recover_register, ip)) => {
+                    Ok((caught_error, recover_register, ip)) => {
+                        let mut error = self.attach_registered_type_names(error);
+
                         let catch_value = match error.error {
                             ErrorKind::KotoError { thrown_value, .. } => thrown_value,
-                            _ => KValue::Str(error.to_string().into()),
+                            _ => {
+                                // The error's stack trace isn't included in the caught message
+                                error.trace.clear();
+                                KValue::Str(error.to_string().into())
+                            }
                         };
 
                         self.set_register(recover_register, catch_value);
+                        // The error (along with its stack trace) is retained so that it can be
+                        // rethrown from the catch block.
+                        self.frame_mut()
+                            .caught_errors
+                            .push((recover_register, caught_error));
                         self.set_ip(ip);
                     }
-                    Err(mut error) => {
+                    Err(error) => {
+                        let mut error = self.attach_registered_type_names(error);
                         // The error hasn't been caught, so is being propagated outside of this.
                         // Koto errors need a VM to allow the error value to be displayed,
                         // so spawn one now.
@@ -817,6 +1114,51 @@ impl KotoVm {
         Ok(KValue::Null)
     }
 
+    // Calls the breakpoint callback if execution should be paused at the current instruction
+    //
+    // Execution is paused when a new line is reached that either contains a breakpoint,
+    // or when the step mode is `StepMode::Step`.
+    fn check_for_breakpoint(&mut self) {
+        // The context is cloned so that the VM can be passed mutably to the callback
+        let context = self.context.clone();
+        let Some(callback) = &context.settings.breakpoint_callback else {
+            return;
+        };
+
+        let chunk = self.chunk();
+        let Some(span) = chunk.debug_info.get_source_span(self.instruction_ip) else {
+            return;
+        };
+        let line = span.start.line;
+
+        {
+            let mut debugger = context.debugger.borrow_mut();
+
+            if let Some((last_chunk, last_line)) = &debugger.last_position
+                && Ptr::ptr_eq(last_chunk, &chunk)
+                && *last_line == line
+            {
+                return;
+            }
+
+            let should_pause = debugger.step_mode == StepMode::Step
+                || debugger.breakpoints.iter().any(|(path, breakpoint_line)| {
+                    *breakpoint_line == line && (path.is_none() || *path == chunk.path)
+                });
+
+            debugger.last_position = Some((chunk, line));
+
+            if !should_pause {
+                return;
+            }
+        }
+
+        // The debugger state isn't borrowed while the callback is being called,
+        // allowing breakpoints to be modified from within the callback.
+        let step_mode = callback(self, span);
+        context.debugger.borrow_mut().step_mode = step_mode;
+    }
+
     fn execute_instruction(&mut self, instruction: Instruction) -> Result<ControlFlow> {
         use Instruction::*;
 
@@ -985,6 +1327,18 @@ impl KotoVm {
             Throw { register } => {
                 return Err(crate::Error::from_koto_value(self.clone_register(register)));
             }
+            Rethrow { register } => {
+                let caught_error = self
+                    .frame()
+                    .caught_errors
+                    .iter()
+                    .rev()
+                    .find(|(error_register, _)| *error_register == register)
+                    .map(|(_, error)| error.clone());
+                return Err(caught_error.unwrap_or_else(|| {
+                    crate::Error::from_koto_value(self.clone_register(register))
+                }));
+            }
             Size { register, value } => self.run_size(register, value, false)?,
             IterNext {
                 result,
@@ -1079,7 +1433,12 @@ impl KotoVm {
                 catch_offset,
             } => {
                 let catch_ip = self.ip() + catch_offset as u32;
-                self.frame_mut().catch_stack.push((arg_register, catch_ip));
+                let frame = self.frame_mut();
+                // Any caught errors at or above the catch point's register are no longer reachable
+                frame
+                    .caught_errors
+                    .retain(|(register, _)| *register < arg_register);
+                frame.catch_stack.push((arg_register, catch_ip));
             }
             TryEnd => {
                 self.frame_mut().catch_stack.pop();
@@ -1273,15 +1632,14 @@ impl KotoVm {
         // place (there should be no other references), and then returned to the iterator.
         let iterable_is_temporary = matches!(
             self.get_register(iterable_register),
-            Range(_) | Tuple(_) | Str(_) | TemporaryTuple { .. }
+            Tuple(_) | Str(_) | TemporaryTuple { .. }
         );
 
-        let output = if iterable_is_temporary {
+        let output = if let Range(r) = self.get_register_mut(iterable_register) {
+            // Fast path for integer ranges, which can be counted directly in their register
+            r.pop_front()?.map(KValue::from)
+        } else if iterable_is_temporary {
             let (output, new_iterable) = match self.remove_register(iterable_register) {
-                Range(mut r) => {
-                    let output = r.pop_front()?;
-                    (output.map(KValue::from), Range(r))
-                }
                 Tuple(mut t) => {
                     let output = t.pop_front();
                     (output, Tuple(t))
@@ -1417,8 +1775,10 @@ impl KotoVm {
                 }
             }
             Str(s) => {
-                let index = signed_index_to_unsigned(index, s.len());
-                s.with_bounds(index..index + 1).into()
+                let index = signed_index_to_unsigned(index, char_count(s));
+                s.with_bounds(char_bounds(s, index..index + 1))
+                    .filter(|result| !result.is_empty())
+                    .into()
             }
             Range(r) => {
                 let result: KNumber = if index < 0 {
@@ -1507,11 +1867,12 @@ impl KotoVm {
                 }
             }
             Str(s) => {
-                let index = signed_index_to_unsigned(index, s.len());
+                let size = char_count(&s);
+                let index = signed_index_to_unsigned(index, size);
                 if is_slice_to {
-                    s.with_bounds(0..index).into()
+                    s.with_bounds(char_bounds(&s, 0..index)).into()
                 } else {
-                    s.with_bounds(index..s.len()).into()
+                    s.with_bounds(char_bounds(&s, index..size)).into()
                 }
             }
             Map(m) if m.contains_meta_key(&index_op) => {
@@ -1544,7 +1905,7 @@ impl KotoVm {
                     } else {
                         index..size as i64
                     };
-                    o.index(&KRange::from(range).into())?
+                    o.slice(&KRange::from(range))?
                 } else {
                     KValue::Null
                 }
@@ -1652,13 +2013,7 @@ impl KotoVm {
     }
 
     fn run_not(&mut self, result: u8, value: u8) -> Result<()> {
-        use KValue::*;
-
-        let result_bool = match &self.get_register(value) {
-            Null => true,
-            Bool(b) if !b => true,
-            _ => false, // All other values coerce to true, so return false
-        };
+        let result_bool = !self.get_register(value).is_truthy();
         self.set_register(result, result_bool.into());
 
         Ok(())
@@ -2179,7 +2534,7 @@ impl KotoVm {
                 other => {
                     return runtime_error!(
                         "Expected Bool from equality comparison, found '{}'",
-                        other.type_as_string()
+                        self.type_name(&other)
                     );
                 }
             }
@@ -2204,7 +2559,7 @@ impl KotoVm {
                 other => {
                     return runtime_error!(
                         "Expected Bool from equality comparison, found '{}'",
-                        other.type_as_string()
+                        self.type_name(&other)
                     );
                 }
             }
@@ -2307,19 +2662,15 @@ impl KotoVm {
     }
 
     fn run_jump_if_true(&mut self, register: u8, offset: u32) -> Result<()> {
-        match self.get_register(register) {
-            KValue::Null => {}
-            KValue::Bool(b) if !b => {}
-            _ => self.jump_ip(offset),
+        if self.get_register(register).is_truthy() {
+            self.jump_ip(offset);
         }
         Ok(())
     }
 
     fn run_jump_if_false(&mut self, register: u8, offset: u32) -> Result<()> {
-        match self.get_register(register) {
-            KValue::Null => self.jump_ip(offset),
-            KValue::Bool(b) if !b => self.jump_ip(offset),
-            _ => {}
+        if !self.get_register(register).is_truthy() {
+            self.jump_ip(offset);
         }
         Ok(())
     }
@@ -2345,7 +2696,7 @@ impl KotoVm {
         let size = match value {
             List(l) => Some(l.len()),
             Tuple(t) => Some(t.len()),
-            Str(l) => Some(l.len()),
+            Str(s) => Some(char_count(s)),
             Range(r) => r.size(),
             Map(m) if m.contains_meta_key(&size_key) => {
                 let op = m.get_meta_value(&size_key).unwrap();
@@ -2404,6 +2755,12 @@ impl KotoVm {
             return self.successful_import(import_register, value, import_all);
         }
 
+        if self.context.settings.sandboxed {
+            return runtime_error!(
+                "'{import_name}' can't be imported from disk in a sandboxed runtime"
+            );
+        }
+
         // Attempt to compile the imported module from disk,
         // using the current source path as the relative starting location
         let source_path = self.reader.chunk.path.clone();
@@ -2514,19 +2871,15 @@ impl KotoVm {
 
         match indexable {
             List(list) => {
-                let mut list_data = list.data_mut();
+                let mut list_data = list.data_mut_checked()?;
                 let list_len = list_data.len();
                 match index_value {
                     Number(index) => {
-                        let u_index = usize::from(index);
-                        if *index >= 0.0 && u_index < list_len {
-                            list_data[u_index] = value.clone();
-                        } else {
-                            return runtime_error!("invalid index ({index})");
-                        }
+                        let index = self.validate_index(*index, Some(list_len))?;
+                        list_data[index] = value.clone();
                     }
                     Range(range) => {
-                        for i in range.indices(list_len) {
+                        for i in range.resolve_indices(list_len)? {
                             list_data[i] = value.clone();
                         }
                     }
@@ -2543,7 +2896,7 @@ impl KotoVm {
             }
             Map(map) => match index_value {
                 Number(index) => {
-                    let mut map_data = map.data_mut();
+                    let mut map_data = map.data_mut_checked()?;
                     let map_len = map_data.len();
                     let u_index = usize::from(index);
                     if *index >= 0.0 && u_index < map_len {
@@ -2573,17 +2926,14 @@ impl KotoVm {
     }
 
     fn validate_index(&self, n: KNumber, size: Option<usize>) -> Result<usize> {
-        let index = usize::from(n);
-
-        if n < 0.0 {
-            return runtime_error!("negative indices aren't allowed ('{n}')");
-        } else if let Some(size) = size
-            && index >= size
-        {
-            return runtime_error!("index out of bounds - index: {n}, size: {size}");
+        match size {
+            Some(size) => match n.resolve_index(size) {
+                Some(index) => Ok(index),
+                None => runtime_error!("index out of bounds - index: {n}, size: {size}"),
+            },
+            None if n < 0.0 => runtime_error!("negative indices aren't allowed ('{n}')"),
+            None => Ok(usize::from(n)),
         }
-
-        Ok(index)
     }
 
     fn run_index(
@@ -2603,15 +2953,19 @@ impl KotoVm {
                 l.data()[index].clone()
             }
             (List(l), Range(range)) => {
-                let indices = range.indices(l.len());
-                List(KList::from_slice(&l.data()[indices]))
+                let indices = range.resolve_indices(l.len())?;
+                let Some(result) = l.make_sub_list(indices) else {
+                    // `range.indices` is guaranteed to return valid indices for the list
+                    unreachable!();
+                };
+                List(result)
             }
             (Tuple(t), Number(n)) => {
                 let index = self.validate_index(n, Some(t.len()))?;
                 t[index].clone()
             }
             (Tuple(t), Range(range)) => {
-                let indices = range.indices(t.len());
+                let indices = range.resolve_indices(t.len())?;
                 let Some(result) = t.make_sub_tuple(indices) else {
                     // `range.indices` is guaranteed to return valid indices for the tuple
                     unreachable!();
@@ -2619,20 +2973,18 @@ impl KotoVm {
                 Tuple(result)
             }
             (Str(s), Number(n)) => {
-                let index = self.validate_index(n, Some(s.len()))?;
-                let Some(result) = s.with_bounds(index..index + 1) else {
-                    return runtime_error!(
-                        "indexing with ({index}) would result in invalid UTF-8 data"
-                    );
+                let index = self.validate_index(n, Some(char_count(s)))?;
+                let Some(result) = s.with_bounds(char_bounds(s, index..index + 1)) else {
+                    // Character bounds always lie on UTF-8 boundaries
+                    unreachable!();
                 };
                 Str(result)
             }
             (Str(s), Range(range)) => {
-                let indices = range.indices(s.len());
-                let Some(result) = s.with_bounds(indices) else {
-                    return runtime_error!(
-                        "indexing with ({range}) would result in invalid UTF-8 data"
-                    );
+                let indices = range.resolve_indices(char_count(s))?;
+                let Some(result) = s.with_bounds(char_bounds(s, indices)) else {
+                    // Character bounds always lie on UTF-8 boundaries
+                    unreachable!();
                 };
                 Str(result)
             }
@@ -2641,6 +2993,10 @@ impl KotoVm {
                 return self.call_overridden_op_2(Some(result_register), value, index, op);
             }
             (Map(m), Number(n)) => {
+                // Negative indices are only supported for sequences
+                if n < 0.0 {
+                    return runtime_error!("negative indices aren't allowed ('{n}')");
+                }
                 let entries = m.data();
                 let index = self.validate_index(n, Some(entries.len()))?;
                 let Some((key, value)) = entries.get_index(index) else {
@@ -2655,12 +3011,13 @@ impl KotoVm {
                 let index = self.validate_index(n, r.size())?;
                 Number((start + index as i64).into())
             }
+            (Object(o), Range(range)) => o.try_borrow()?.slice(&range)?,
             (Object(o), index) => o.try_borrow()?.index(&index)?,
             (unexpected_value, unexpected_index) => {
                 return runtime_error!(
                     "Unable to index '{}' with '{}'",
-                    unexpected_value.type_as_string(),
-                    unexpected_index.type_as_string(),
+                    self.type_name(unexpected_value),
+                    self.type_name(&unexpected_index),
                 );
             }
         };
@@ -2685,8 +3042,20 @@ impl KotoVm {
                 self.call_overridden_op_3(None, map.clone().into(), key.clone(), value.clone(), op)
             }
             KValue::Map(map) => {
+                if let KValue::Str(key_string) = key
+                    && let Some(setter) = find_property_setter(map, key_string)?
+                {
+                    // Call the property setter with the assigned map as `self`
+                    return self.call_overridden_op_2(
+                        None,
+                        map.clone().into(),
+                        value.clone(),
+                        setter,
+                    );
+                }
+
                 let key = ValueKey::try_from(key.clone())?;
-                map.data_mut().insert(key, value.clone());
+                map.data_mut_checked()?.insert(key, value.clone());
                 Ok(())
             }
             KValue::Object(o) => match key {
@@ -2844,16 +3213,43 @@ impl KotoVm {
                 let mut access_map = map.clone();
                 let mut access_result = None;
                 while access_result.is_none() {
-                    let maybe_value = access_map.get(&key);
+                    let maybe_value = if access_map.is_same_instance(map) {
+                        self.access_cache.get(
+                            &self.reader.chunk,
+                            self.instruction_ip,
+                            &access_map.data(),
+                            &key,
+                        )
+                    } else {
+                        access_map.get(&key)
+                    };
                     match maybe_value {
                         Some(value) => access_result = Some(value),
                         // Fallback to the map module when there's no metamap
                         None if access_map.meta_map().is_none() => {
                             return core_op!(map, error_if_not_found);
                         }
-                        _ => match access_map.get_meta_value(&MetaKey::Named(key_string.clone())) {
-                            Some(value) => access_result = Some(value),
-                            None => match access_map.get_meta_value(&MetaKey::Base) {
+                        _ => {
+                            if let Some(value) =
+                                access_map.get_meta_value(&MetaKey::Named(key_string.clone()))
+                            {
+                                access_result = Some(value);
+                                continue;
+                            }
+
+                            if let Some(getter) =
+                                access_map.get_meta_value(&MetaKey::Get(key_string.clone()))
+                            {
+                                // Call the property getter with the accessed map as `self`
+                                self.call_overridden_op_1(
+                                    Some(result_register),
+                                    value_register,
+                                    getter,
+                                )?;
+                                return Ok(true);
+                            }
+
+                            match access_map.get_meta_value(&MetaKey::Base) {
                                 Some(Map(base)) => {
                                     // Attempt the access again with the base map
                                     access_map = base;
@@ -2862,8 +3258,8 @@ impl KotoVm {
                                     return unexpected_type("Map as base value", &unexpected);
                                 }
                                 None => break,
-                            },
-                        },
+                            }
+                        }
                     }
                 }
 
@@ -2890,7 +3286,7 @@ impl KotoVm {
                         if error_if_not_found {
                             runtime_error!(
                                 "'{key}' not found in '{}'",
-                                accessed_value.type_as_string()
+                                self.type_name(&accessed_value)
                             )
                         } else {
                             Ok(false)
@@ -3314,7 +3710,7 @@ impl KotoVm {
             "Indexable" => value.is_indexable(),
             "Iterable" => value.is_iterable(),
             expected_type => {
-                if value.type_as_string() == expected_type {
+                if self.type_name(value) == expected_type {
                     true
                 } else {
                     // The type didn't match, so look for a base value to check
@@ -3324,7 +3720,7 @@ impl KotoVm {
                         match value {
                             KValue::Map(m) if m.contains_meta_key(&MetaKey::Base) => {
                                 let base = m.get_meta_value(&MetaKey::Base).unwrap();
-                                if base.type_as_string() == expected_type {
+                                if self.type_name(&base) == expected_type {
                                     return true;
                                 } else {
                                     // The base didn't match the expected type,
@@ -3389,26 +3785,73 @@ impl KotoVm {
         // Render the value as a string, applying the precision option if specified
         let precision = format_options.and_then(|options| options.precision);
         let representation = format_options.and_then(|options| options.representation);
+        let alternate_form = format_options.is_some_and(|options| options.alternate_form);
         let rendered = match value {
-            KValue::Number(n) => match (precision, representation) {
-                (_, Some(representation)) => {
-                    let n = i64::from(n);
-                    match representation {
-                        StringFormatRepresentation::Debug => format!("{n:?}"),
-                        StringFormatRepresentation::HexLower => format!("{n:x}"),
-                        StringFormatRepresentation::HexUpper => format!("{n:X}"),
-                        StringFormatRepresentation::Binary => format!("{n:b}"),
-                        StringFormatRepresentation::Octal => format!("{n:o}"),
-                        StringFormatRepresentation::ExpLower => format!("{n:e}"),
-                        StringFormatRepresentation::ExpUpper => format!("{n:E}"),
+            KValue::Number(n) => {
+                let mut rendered = match (precision, representation) {
+                    (
+                        _,
+                        Some(
+                            representation @ (StringFormatRepresentation::Binary
+                            | StringFormatRepresentation::Octal
+                            | StringFormatRepresentation::HexLower
+                            | StringFormatRepresentation::HexUpper),
+                        ),
+                    ) => {
+                        let n = i64::from(n);
+                        match representation {
+                            StringFormatRepresentation::HexLower if alternate_form => {
+                                format!("{n:#x}")
+                            }
+                            StringFormatRepresentation::HexLower => format!("{n:x}"),
+                            StringFormatRepresentation::HexUpper if alternate_form => {
+                                format!("{n:#X}")
+                            }
+                            StringFormatRepresentation::HexUpper => format!("{n:X}"),
+                            StringFormatRepresentation::Binary if alternate_form => {
+                                format!("{n:#b}")
+                            }
+                            StringFormatRepresentation::Binary => format!("{n:b}"),
+                            StringFormatRepresentation::Octal if alternate_form => {
+                                format!("{n:#o}")
+                            }
+                            _ => format!("{n:o}"),
+                        }
+                    }
+                    (precision, Some(StringFormatRepresentation::ExpLower)) => {
+                        format_exponent(n, precision, false)
+                    }
+                    (precision, Some(StringFormatRepresentation::ExpUpper)) => {
+                        format_exponent(n, precision, true)
+                    }
+                    (Some(precision), None | Some(StringFormatRepresentation::Debug))
+                        if n.is_f64() || n.is_i64_in_f64_range() =>
+                    {
+                        format!("{:.*}", precision as usize, f64::from(n))
+                    }
+                    _ => n.to_string(),
+                };
+
+                if let Some(options) = format_options {
+                    if options.group_thousands && representation.is_none() {
+                        rendered = group_thousands(&rendered);
+                    }
+                    if options.show_sign && !rendered.starts_with('-') && !n.is_nan() {
+                        rendered.insert(0, '+');
                     }
                 }
-                (Some(precision), None) if n.is_f64() || n.is_i64_in_f64_range() => {
-                    format!("{:.*}", precision as usize, f64::from(n))
-                }
-                _ => n.to_string(),
-            },
+
+                rendered
+            }
             other => match representation {
+                Some(
+                    StringFormatRepresentation::Binary
+                    | StringFormatRepresentation::Octal
+                    | StringFormatRepresentation::HexLower
+                    | StringFormatRepresentation::HexUpper
+                    | StringFormatRepresentation::ExpLower
+                    | StringFormatRepresentation::ExpUpper,
+                ) => return unexpected_type("Number", &other),
                 Some(StringFormatRepresentation::Debug) => {
                     match self.run_unary_op(UnaryOp::Debug, other)? {
                         KValue::Str(rendered) => match precision {
@@ -3459,6 +3902,15 @@ impl KotoVm {
                     let fill_chars = min_width - len;
 
                     match options.alignment {
+                        // Zero-padding for numbers is inserted following the sign and prefix
+                        StringAlignment::Default | StringAlignment::Right
+                            if value_is_number && fill == "0" =>
+                        {
+                            let sign_len = usize::from(rendered.starts_with(['+', '-']));
+                            let prefix_len = if options.alternate_form { 2 } else { 0 };
+                            let (prefix, digits) = rendered.split_at(sign_len + prefix_len);
+                            format!("{prefix}{}{digits}", fill.repeat(fill_chars))
+                        }
                         StringAlignment::Default => {
                             if value_is_number {
                                 // Right-alignment by default for numbers
@@ -3637,20 +4089,20 @@ impl KotoVm {
 
     // Called when an error occurs and the stack needs to be unwound
     //
-    // If `allow_catch` is true and a `catch` expression is encountered then the recovery register
-    // and ip will be returned. Otherwise, the error will be returned with the popped frames added
-    // to the error's stack trace.
+    // If `allow_catch` is true and a `catch` expression is encountered then the error will be
+    // returned along with the recovery register and ip. Otherwise, the error will be returned with
+    // the popped frames added to the error's stack trace.
     fn pop_call_stack_on_error(
         &mut self,
         mut error: Error,
         allow_catch: bool,
-    ) -> Result<(u8, u32)> {
+    ) -> Result<(Error, u8, u32)> {
         error.extend_trace(self.instruction_frame());
 
         while let Some(frame) = self.call_stack.last() {
             match frame.catch_stack.last() {
                 Some((error_register, catch_ip)) if allow_catch => {
-                    return Ok((*error_register, *catch_ip));
+                    return Ok((error, *error_register, *catch_ip));
                 }
                 _ => {
                     if frame.execution_barrier {
@@ -3781,6 +4233,44 @@ fn signed_index_to_unsigned(index: i8, size: usize) -> usize {
     }
 }
 
+// Returns the values of a chunk's top-level locals, paired with their names
+//
+// `registers` should start with the first register of the frame that ran the chunk.
+fn top_level_locals(chunk: &Chunk, registers: &[KValue]) -> Vec<(KString, KValue)> {
+    chunk
+        .debug_info
+        .top_level_locals
+        .iter()
+        .filter_map(|(id, register)| {
+            let value = registers.get(*register as usize)?;
+            Some((chunk.constants.get_string_slice(*id).into(), value.clone()))
+        })
+        .collect()
+}
+
+// Finds the `@set` property setter for a key that's missing from a map's data
+//
+// Base maps are checked for the setter if it's not found in the map's own meta map.
+fn find_property_setter(map: &KMap, key: &KString) -> Result<Option<KValue>> {
+    if map.data().contains_key(key) {
+        return Ok(None);
+    }
+
+    let setter_key = MetaKey::Set(key.clone());
+    let mut setter_map = map.clone();
+    loop {
+        if let Some(setter) = setter_map.get_meta_value(&setter_key) {
+            return Ok(Some(setter));
+        }
+
+        match setter_map.get_meta_value(&MetaKey::Base) {
+            Some(KValue::Map(base)) => setter_map = base,
+            Some(unexpected) => return unexpected_type("Map as base value", &unexpected),
+            None => return Ok(None),
+        }
+    }
+}
+
 // See [KotoVm::call_koto_function] and [KotoVm::call_generator]
 fn apply_optional_arguments(
     registers: &mut Vec<KValue>,
@@ -3928,6 +4418,39 @@ impl<'a, const N: usize> From<&'a [KValue; N]> for CallArgs<'a> {
 //
 // The Map is optional to prevent recursive imports (see Vm::run_import).
 type ModuleCache = HashMap<PathBuf, Option<KMap>, BuildHasherDefault<FxHasher>>;
+// Display names for object types, keyed by the type's `KotoType::type_static` string
+pub(crate) type TypeNames = HashMap<&'static str, KString, BuildHasherDefault<FxHasher>>;
+
+// Returns the value's type as a string, using a registered type name for objects if available
+pub(crate) fn value_type_name(value: &KValue, type_names: Option<&TypeNames>) -> KString {
+    let type_string = value.type_as_string();
+    match value {
+        KValue::Object(_) => registered_type_name(&type_string, type_names),
+        _ => type_string,
+    }
+}
+
+// Returns the registered name for an object's type string, or the type string if there's no match
+pub(crate) fn registered_type_name(
+    type_string: &KString,
+    type_names: Option<&TypeNames>,
+) -> KString {
+    type_names
+        .and_then(|type_names| type_names.get(type_string.as_str()))
+        .cloned()
+        .unwrap_or_else(|| type_string.clone())
+}
+
+// The state used by the breakpoint mechanism, see `KotoVmSettings::breakpoint_callback`
+#[derive(Default)]
+struct DebuggerState {
+    // The breakpoints that have been added to the VM
+    breakpoints: Vec<(Option<KString>, u32)>, // script path, line
+    // Determines where execution should next be paused
+    step_mode: StepMode,
+    // The most recently executed line
+    last_position: Option<(Ptr<Chunk>, u32)>, // chunk, line
+}
 
 // A frame in the VM's call stack
 #[derive(Clone)]
@@ -3950,13 +4473,16 @@ struct Frame {
     pub return_value_register: Option<u8>,
     // A stack of catch points for handling errors
     pub catch_stack: Vec<(u8, u32)>, // catch error register, catch ip
+    // Errors that have been caught in the frame, available for rethrowing
+    pub caught_errors: Vec<(u8, Error)>, // catch error register, caught error
     // True if the frame should prevent execution from continuing after the frame is exited.
     // e.g.
     //   - a function is being called externally from the VM
     //   - an overridden operator is being executed as a result of a regular instruction
     //   - an external function is calling back into the VM with a functor
     //   - a module is being imported
-    pub execution_barrier: bool,
+    pub execution_barrier: bool, // True if the frame is running a chunk's top-level main block
+    pub is_top_level: bool,
 }
 
 impl Frame {
@@ -3970,7 +4496,9 @@ impl Frame {
             return_value_register: None,
             return_instruction_ip: 0,
             catch_stack: vec![],
+            caught_errors: vec![],
             execution_barrier: false,
+            is_top_level: false,
         }
     }
 
@@ -4122,6 +4650,46 @@ pub enum ReturnOrYield {
 }
 
 // A collection of macros that avoid duplicated boilerplate in the various operator functions
+
+// Formats a number in exponential notation, e.g. `1.23e4`
+//
+// The output matches the syntax of number literals with exponents, so it can be parsed as Koto.
+fn format_exponent(n: KNumber, precision: Option<u32>, upper_case: bool) -> String {
+    match (n, precision, upper_case) {
+        (KNumber::F64(n), Some(precision), false) => format!("{n:.*e}", precision as usize),
+        (KNumber::F64(n), Some(precision), true) => format!("{n:.*E}", precision as usize),
+        (KNumber::F64(n), None, false) => format!("{n:e}"),
+        (KNumber::F64(n), None, true) => format!("{n:E}"),
+        (KNumber::I64(n), Some(precision), false) => format!("{n:.*e}", precision as usize),
+        (KNumber::I64(n), Some(precision), true) => format!("{n:.*E}", precision as usize),
+        (KNumber::I64(n), None, false) => format!("{n:e}"),
+        (KNumber::I64(n), None, true) => format!("{n:E}"),
+    }
+}
+
+// Inserts `,` separators between each group of three digits in the integer part of a number
+fn group_thousands(rendered: &str) -> String {
+    let (sign, unsigned) = match rendered.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", rendered),
+    };
+    let digits_end = unsigned
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(unsigned.len());
+    let (digits, rest) = unsigned.split_at(digits_end);
+
+    let mut result = String::with_capacity(rendered.len() + digits.len() / 3);
+    result.push_str(sign);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(digit);
+    }
+    result.push_str(rest);
+    result
+}
+
 mod macros {
     macro_rules! call_metamap_binary_op_rhs {
         ($self:expr, $op:ident, $map:expr, $lhs_value:expr, $rhs_value:expr, $result_register:expr) => {{
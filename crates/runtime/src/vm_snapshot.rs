@@ -0,0 +1,34 @@
+use crate::{KString, KValue, ValueMap};
+use koto_parser::Span;
+
+/// A read-only snapshot of a [KotoVm](crate::KotoVm)'s state
+///
+/// Snapshots are intended to help with building debugging tools,
+/// see [KotoVm::snapshot](crate::KotoVm::snapshot).
+#[derive(Clone, Default)]
+pub struct VmSnapshot {
+    /// The top-level locals of the script that's currently being run
+    ///
+    /// If the VM isn't running a script then the locals from the most recently completed run are
+    /// provided, as long as [KotoVmSettings::retain_top_level_locals] is enabled.
+    ///
+    /// [KotoVmSettings::retain_top_level_locals]: crate::KotoVmSettings::retain_top_level_locals
+    pub locals: Vec<(KString, KValue)>,
+    /// The values that have been exported by the script
+    pub exports: ValueMap,
+    /// The VM's call stack, starting from the outermost frame
+    ///
+    /// The call stack will be empty if the VM isn't currently running a script.
+    pub call_stack: Vec<StackFrameSnapshot>,
+}
+
+/// A snapshot of a frame in a VM's call stack
+///
+/// See [VmSnapshot::call_stack].
+#[derive(Clone, Debug)]
+pub struct StackFrameSnapshot {
+    /// The path of the script that's being executed in the frame, if available
+    pub path: Option<KString>,
+    /// The span of the source that produced the frame's current instruction
+    pub span: Option<Span>,
+}
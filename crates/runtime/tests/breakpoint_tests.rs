@@ -0,0 +1,129 @@
+mod breakpoints {
+    use koto_bytecode::{CompilerSettings, ModuleLoader};
+    use koto_parser::Span;
+    use koto_runtime::{StepMode, prelude::*};
+    use std::sync::{Arc, Mutex};
+
+    // The lines and local values that were seen when execution was paused
+    type PausedLog = Arc<Mutex<Vec<(u32, Vec<(String, String)>)>>>;
+
+    fn make_vm(step_mode: StepMode) -> (KotoVm, PausedLog) {
+        let log = PausedLog::default();
+        let callback_log = log.clone();
+
+        let vm = KotoVm::with_settings(KotoVmSettings {
+            breakpoint_callback: Some(Box::new(move |vm: &mut KotoVm, span: Span| {
+                let locals = vm
+                    .snapshot()
+                    .locals
+                    .into_iter()
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), vm.value_to_string(&value).unwrap()))
+                    .collect();
+                callback_log.lock().unwrap().push((span.start.line, locals));
+                step_mode
+            })),
+            ..Default::default()
+        });
+
+        (vm, log)
+    }
+
+    fn run_script(vm: &mut KotoVm, script: &str) -> KValue {
+        let mut loader = ModuleLoader::default();
+        let chunk = match loader.compile_script(script, None, CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => panic!("Error while compiling script: {error}"),
+        };
+
+        match vm.run(chunk) {
+            Ok(result) => result,
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    fn locals(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn pause_at_breakpoint() {
+        let script = "
+x = 1
+y = x + 1
+z = x + y
+";
+        let (mut vm, log) = make_vm(StepMode::Continue);
+        vm.add_breakpoint(None, 3);
+
+        let result = run_script(&mut vm, script);
+        assert!(matches!(result, KValue::Number(n) if n == 3));
+
+        let log = log.lock().unwrap();
+        assert_eq!(
+            *log,
+            [(3, locals(&[("x", "1"), ("y", "2"), ("z", "null")]))]
+        );
+    }
+
+    #[test]
+    fn pause_at_breakpoint_in_loop() {
+        let script = "
+n = 0
+for i in 0..3
+  n += i
+n * 2
+";
+        let (mut vm, log) = make_vm(StepMode::Continue);
+        vm.add_breakpoint(None, 3);
+
+        run_script(&mut vm, script);
+
+        let lines = log
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(line, _)| *line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [3, 3, 3]);
+    }
+
+    #[test]
+    fn breakpoints_are_ignored_for_other_scripts() {
+        let script = "
+x = 1
+y = 2
+";
+        let (mut vm, log) = make_vm(StepMode::Continue);
+        vm.add_breakpoint(Some("other.koto".into()), 2);
+
+        run_script(&mut vm, script);
+
+        assert!(log.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn step_through_each_line() {
+        let script = "\
+x = 1
+y = 2
+x + y
+";
+        let (mut vm, log) = make_vm(StepMode::Step);
+        vm.set_step_mode(StepMode::Step);
+
+        run_script(&mut vm, script);
+
+        let lines = log
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(line, _)| *line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [0, 1, 2]);
+    }
+}
@@ -16,6 +16,22 @@ x.next().get() # null
 ";
             check_script_output(script, KValue::Null);
         }
+
+        #[test]
+        fn yielded_null_is_distinct_from_exhaustion() {
+            let script = "
+gen = ||
+  yield 1
+  yield null
+  yield 3
+x = gen()
+result = []
+while output = x.next()
+  result.push output.get()
+result
+";
+            check_script_output(script, list(&[1.into(), KValue::Null, 3.into()]));
+        }
     }
 
     mod next_back {
@@ -32,6 +48,24 @@ x.next_back().get() # null
         }
     }
 
+    mod count {
+        use super::*;
+
+        #[test]
+        fn with_predicate() {
+            check_script_output("(1..=10).count |n| n % 3 == 0", 3);
+        }
+
+        #[test]
+        fn with_predicate_and_value_pairs() {
+            let script = "
+x = {foo: 1, bar: 2, baz: 3}
+x.count |(key, value)| key.starts_with('b') and value > 2
+";
+            check_script_output(script, 1);
+        }
+    }
+
     mod chain {
         use super::*;
 
@@ -139,6 +173,62 @@ y.next().get()
         }
     }
 
+    mod dedup {
+        use super::*;
+
+        #[test]
+        fn runs_from_generator() {
+            let script = "
+gen = ||
+  for x in (1, 1, 2, 2, 2, 3, 1, 1)
+    yield x
+gen().dedup().to_tuple()
+";
+            check_script_output(script, number_tuple(&[1, 2, 3, 1]));
+        }
+
+        #[test]
+        fn dedup_is_lazy() {
+            let script = "
+gen = ||
+  n = 0
+  loop
+    yield n
+    yield n
+    n += 1
+gen().dedup().take(4).to_tuple()
+";
+            check_script_output(script, number_tuple(&[0, 1, 2, 3]));
+        }
+
+        #[test]
+        fn dedup_pairs() {
+            let script = "
+{a: 1, b: 2}.dedup().to_tuple()
+";
+            check_script_output(
+                script,
+                tuple(&[
+                    tuple(&["a".into(), 1.into()]),
+                    tuple(&["b".into(), 2.into()]),
+                ]),
+            );
+        }
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = (1, 1, 2, 2, 3).dedup()
+x.next().get() # 1
+y = copy x
+x.next().get() # 2
+x.next().get() # 3
+y.next().get()
+";
+            check_script_output(script, 2);
+        }
+    }
+
     mod each {
         use super::*;
 
@@ -173,7 +263,7 @@ x.next().get()
  .each number.round
  .to_tuple()
 ";
-            check_script_output(script, number_tuple(&[3, 5, 6]));
+            check_script_output(script, number_tuple(&[2, 5, 6]));
         }
     }
 
@@ -203,6 +293,55 @@ y.next().get()
         }
     }
 
+    mod inspect {
+        use super::*;
+
+        #[test]
+        fn each_value_is_inspected_once() {
+            let script = "
+seen = []
+result = (1..=5)
+  .inspect |x| seen.push x
+  .to_tuple()
+result, seen.to_tuple()
+";
+            check_script_output(
+                script,
+                tuple(&[
+                    number_tuple(&[1, 2, 3, 4, 5]),
+                    number_tuple(&[1, 2, 3, 4, 5]),
+                ]),
+            );
+        }
+
+        #[test]
+        fn inspect_is_lazy() {
+            let script = "
+seen = []
+x = (1, 2, 3).inspect |x| seen.push x
+a = x.next().get()
+a, koto.size seen
+";
+            check_script_output(script, number_tuple(&[1, 1]));
+        }
+
+        #[test]
+        fn inspect_reversed() {
+            let script = "
+seen = []
+result = (1, 2, 3)
+  .inspect |x| seen.push x
+  .reversed()
+  .to_tuple()
+result, seen.to_tuple()
+";
+            check_script_output(
+                script,
+                tuple(&[number_tuple(&[3, 2, 1]), number_tuple(&[3, 2, 1])]),
+            );
+        }
+    }
+
     mod intersperse {
         use super::*;
 
@@ -233,6 +372,89 @@ y.next().get()
 ";
             check_script_output(script, 20);
         }
+
+        #[test]
+        fn intersperse_list() {
+            let script = "
+[1, 2, 3].intersperse(0).to_tuple()
+";
+            check_script_output(script, number_tuple(&[1, 0, 2, 0, 3]));
+        }
+
+        #[test]
+        fn intersperse_single_value() {
+            let script = "
+[1].intersperse(0).to_tuple()
+";
+            check_script_output(script, number_tuple(&[1]));
+        }
+
+        #[test]
+        fn intersperse_empty() {
+            let script = "
+[].intersperse(0).to_tuple()
+";
+            check_script_output(script, tuple(&[]));
+        }
+
+        #[test]
+        fn intersperse_is_lazy() {
+            let script = "
+seen = []
+x = (1, 2, 3)
+  .inspect |x| seen.push x
+  .intersperse 0
+x.next() # 1
+koto.size seen
+";
+            check_script_output(script, 1);
+        }
+    }
+
+    mod iter {
+        use super::*;
+
+        #[test]
+        fn manual_iteration_until_exhausted() {
+            let script = "
+i = iterator.iter [1, 2, 3]
+result = []
+while output = i.next()
+  result.push output.get()
+result.push i.next() # null after exhaustion
+";
+            check_script_output(script, list(&[1.into(), 2.into(), 3.into(), KValue::Null]));
+        }
+
+        #[test]
+        fn manual_iteration_over_map() {
+            let script = "
+i = iterator.iter {foo: 42}
+key, value = i.next().get()
+key, value, i.next()
+";
+            check_script_output(script, tuple(&["foo".into(), 42.into(), KValue::Null]));
+        }
+
+        #[test]
+        fn manual_iteration_over_string() {
+            let script = "
+i = iterator.iter 'hé'
+i.next().get(), i.next().get(), i.next()
+";
+            check_script_output(script, tuple(&["h".into(), "é".into(), KValue::Null]));
+        }
+
+        #[test]
+        fn iter_of_iterator_shares_position() {
+            let script = "
+x = (1..=3).iter()
+x.next()
+y = iterator.iter x
+y.next().get()
+";
+            check_script_output(script, 2);
+        }
     }
 
     mod generate {
@@ -723,5 +945,43 @@ y.next().get()
 ";
             check_script_output(script, "2");
         }
+
+        #[test]
+        fn multi_char_pattern() {
+            let script = "
+'a::b::::c::'.split('::').to_tuple()
+";
+            check_script_output(
+                script,
+                tuple(&["a".into(), "b".into(), "".into(), "c".into(), "".into()]),
+            );
+        }
+
+        #[test]
+        fn utf8_content() {
+            let script = "
+'héllo→wörld→👋🏽'.split('→').to_tuple()
+";
+            check_script_output(
+                script,
+                tuple(&["héllo".into(), "wörld".into(), "👋🏽".into()]),
+            );
+        }
+
+        #[test]
+        fn empty_pattern() {
+            let script = "
+'aé👋🏽'.split('').to_tuple()
+";
+            check_script_output(script, tuple(&["a".into(), "é".into(), "👋🏽".into()]));
+        }
+
+        #[test]
+        fn pattern_not_found() {
+            let script = "
+'abc'.split('x').to_tuple()
+";
+            check_script_output(script, tuple(&["abc".into()]));
+        }
     }
 }
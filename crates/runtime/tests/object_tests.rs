@@ -149,21 +149,15 @@ mod objects {
                     let result = self.x + i64::from(index);
                     Ok(result.into())
                 }
-                KValue::Range(range) => match (range.start(), range.end()) {
-                    (Some(start), Some((end, inclusive))) => {
-                        let end = if inclusive {
-                            self.x + end + 1
-                        } else {
-                            self.x + end
-                        };
-                        Ok(KRange::from((self.x + start)..end).into())
-                    }
-                    _ => unimplemented!(),
-                },
                 unexpected => unexpected_type("Number as index", unexpected),
             }
         }
 
+        fn slice(&self, range: &KRange) -> Result<KValue> {
+            let indices = range.indices(self.size().unwrap_or_default());
+            Ok(Self::make_value(indices.len() as i64))
+        }
+
         fn index_assign(&mut self, index: &KValue, value: &KValue) -> Result<()> {
             match index {
                 KValue::Number(index) => {
@@ -188,8 +182,13 @@ mod objects {
             true
         }
 
-        fn call(&mut self, _ctx: &mut CallContext) -> Result<KValue> {
-            Ok(self.x.into())
+        fn call(&mut self, ctx: &mut CallContext) -> Result<KValue> {
+            if ctx.args().is_empty() {
+                Ok(self.x.into())
+            } else {
+                // Return the call's arguments so that they can be checked in tests
+                Ok(KTuple::from(ctx.args()).into())
+            }
         }
 
         fn negate(&self) -> Result<KValue> {
@@ -693,6 +692,25 @@ x.as_number()
         }
     }
 
+    mod thrown_object {
+        use super::*;
+
+        #[test]
+        fn catch_with_object_type_check() {
+            let script = "
+try
+  throw make_object 42
+catch error: Number
+  throw 'Caught number'
+catch error: TestObject
+  error.as_number()
+catch error
+  throw 'Fallback'
+";
+            test_object_script(script, 42);
+        }
+    }
+
     mod generic_object {
         use super::*;
 
@@ -797,6 +815,19 @@ make_object(10)
 ";
             test_object_script(script, number_tuple(&[12, 11, 10]));
         }
+
+        #[test]
+        fn next_and_next_back() {
+            let script = "
+x = make_object(10).iter()
+a = x.next().get()
+b = x.next().get()
+c = x.next_back().get()
+d = x.next_back().get()
+a, b, c, d
+";
+            test_object_script(script, number_tuple(&[11, 12, 11, 10]));
+        }
     }
 
     mod binary_op {
@@ -1077,6 +1108,102 @@ x.as_number()
         }
     }
 
+    mod registered_type_names {
+        use super::*;
+
+        fn test_script_with_type_name(script: &str, expected_output: impl Into<KValue>) {
+            let vm = KotoVm::default();
+            vm.register_type_name::<TestObject>("Thing");
+            vm.prelude().add_fn("make_object", make_object);
+
+            if let Err(e) = check_script_output_with_vm(vm, script, expected_output.into()) {
+                panic!("{e}");
+            }
+        }
+
+        #[test]
+        fn koto_type() {
+            test_script_with_type_name("koto.type make_object 1", "Thing");
+        }
+
+        #[test]
+        fn type_hint() {
+            let script = "
+let x: Thing = make_object 256
+x.as_number()
+";
+            test_script_with_type_name(script, 256);
+        }
+
+        #[test]
+        fn unexpected_type_error() {
+            let script = "
+try
+  koto.range (make_object 1), 2
+catch error
+  error
+";
+            test_script_with_type_name(
+                script,
+                "expected an integer for the range's start, found Thing",
+            );
+        }
+
+        #[test]
+        fn unexpected_arguments_error() {
+            let script = "
+try
+  'abc'.contains make_object 1
+catch error
+  error
+";
+            test_script_with_type_name(
+                script,
+                "Unexpected arguments.\n  Expected: |String|\n  Provided: |String, Thing|",
+            );
+        }
+
+        #[test]
+        fn invalid_binary_op_error() {
+            let script = "
+try
+  true < make_object 1
+catch error
+  error
+";
+            test_script_with_type_name(
+                script,
+                "unable to perform operation '<' with 'Bool' and 'Thing'",
+            );
+        }
+
+        #[test]
+        fn uncaught_error_keeps_its_kind() {
+            use koto_bytecode::{CompilerSettings, ModuleLoader};
+            use koto_runtime::ErrorKind;
+
+            let mut vm = KotoVm::default();
+            vm.register_type_name::<TestObject>("Thing");
+            vm.prelude().add_fn("make_object", make_object);
+
+            let chunk = ModuleLoader::default()
+                .compile_script(
+                    "koto.range (make_object 1), 2",
+                    None,
+                    CompilerSettings::default(),
+                )
+                .unwrap();
+
+            let error = vm.run(chunk).unwrap_err();
+            assert!(matches!(error.error, ErrorKind::UnexpectedType { .. }));
+            assert!(
+                error
+                    .to_string()
+                    .starts_with("expected an integer for the range's start, found Thing")
+            );
+        }
+    }
+
     mod index_and_size {
         use super::*;
 
@@ -1089,6 +1216,26 @@ x[23]
             test_object_script(script, 123);
         }
 
+        #[test]
+        fn slice() {
+            let script = "
+x = make_object 100
+y = x[10..20]
+assert_eq (koto.type y), 'TestObject'
+y.as_number()
+";
+            test_object_script(script, 10);
+        }
+
+        #[test]
+        fn slice_from() {
+            let script = "
+x = make_object 100
+x[95..].as_number()
+";
+            test_object_script(script, 5);
+        }
+
         #[test]
         fn size() {
             let script = "
@@ -1147,6 +1294,26 @@ x()
         test_object_script(script, 256);
     }
 
+    #[test]
+    fn call_with_args() {
+        let script = "
+x = make_object 256
+x 1, 2, 3
+";
+        test_object_script(script, number_tuple(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn call_with_unpacked_args() {
+        let script = "
+x = make_object 256
+args = 2, 3
+more = [5, 6]
+x 1, args..., 4, more...
+";
+        test_object_script(script, number_tuple(&[1, 2, 3, 4, 5, 6]));
+    }
+
     #[test]
     fn object_access() {
         let script = r##"
@@ -0,0 +1,93 @@
+mod output_limit {
+    use koto_bytecode::{CompilerSettings, ModuleLoader};
+    use koto_runtime::{Ptr, prelude::*};
+    use koto_test_utils::OutputCapture;
+
+    fn make_vm_with_output_limit(limit: usize) -> (KotoVm, OutputCapture) {
+        let output = OutputCapture::default();
+
+        let vm = KotoVm::with_settings(KotoVmSettings {
+            stdout: make_ptr!(output.clone()),
+            stderr: make_ptr!(output.clone()),
+            max_output_size: Some(limit),
+            ..Default::default()
+        });
+
+        (vm, output)
+    }
+
+    fn compile(script: &str) -> Ptr<koto_bytecode::Chunk> {
+        match ModuleLoader::default().compile_script(script, None, CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => panic!("Error while compiling script: {error}"),
+        }
+    }
+
+    #[test]
+    fn output_isnt_wrapped_without_a_limit() {
+        let stdout: Ptr<dyn KotoFile> = make_ptr!(OutputCapture::default());
+
+        let vm = KotoVm::with_settings(KotoVmSettings {
+            stdout: stdout.clone(),
+            ..Default::default()
+        });
+
+        assert!(Ptr::ptr_eq(vm.stdout(), &stdout));
+    }
+
+    #[test]
+    fn output_within_limit() {
+        let (mut vm, output) = make_vm_with_output_limit(100);
+
+        let script = "
+for i in 0..3
+  print i
+";
+        vm.run(compile(script)).unwrap();
+
+        assert_eq!(*output.captured_output(), "0\n1\n2\n");
+    }
+
+    #[test]
+    fn infinite_printing_loop() {
+        let (mut vm, output) = make_vm_with_output_limit(100);
+
+        let script = "
+while true
+  print 'hello'
+";
+        let error = vm.run(compile(script)).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("the maximum output size (100 bytes) has been exceeded"),
+            "unexpected error: {error}"
+        );
+        assert_eq!(output.captured_output().len(), 96);
+    }
+
+    #[test]
+    fn stdout_and_stderr_share_the_limit() {
+        let (mut vm, _) = make_vm_with_output_limit(10);
+
+        let script = "
+io.stdout.write '12345'
+io.stderr.write '67890'
+io.stderr.write '!'
+";
+        assert!(vm.run(compile(script)).is_err());
+    }
+
+    #[test]
+    fn the_limit_is_shared_between_runs() {
+        let (mut vm, _) = make_vm_with_output_limit(10);
+
+        let script = "
+io.stdout.write '1234567890'
+";
+        let chunk = compile(script);
+        vm.run(chunk.clone()).unwrap();
+        assert!(vm.run(chunk).is_err());
+    }
+}
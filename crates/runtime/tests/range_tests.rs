@@ -43,10 +43,46 @@ assert_eq ({b}..={b}).union({a}), {expected}
         check_script_output(&script, ());
     }
 
+    #[test_case("10..20", 10, true)]
+    #[test_case("10..20", 19, true)]
+    #[test_case("10..20", 20, false)]
+    #[test_case("10..=20", 20, true)]
+    #[test_case("10..=20", 21, false)]
+    #[test_case("10..=20", 9, false)]
+    #[test_case("10..", 10, true ; "without_end")]
+    #[test_case("..10", 10, false)]
+    #[test_case("..=10", 10, true ; "inclusive_without_start")]
+    fn contains(range: &str, n: i64, expected: bool) {
+        let script = format!(
+            "
+assert_eq ({range}).contains({n}), {expected}"
+        );
+        check_script_output(&script, ());
+    }
+
+    #[test_case("10..20", 10)]
+    #[test_case("10..=20", 11)]
+    #[test_case("-5..5", 10)]
+    #[test_case("5..=5", 1)]
+    #[test_case("5..5", 0)]
+    #[test_case("20..10", 0)]
+    fn length(range: &str, expected: i64) {
+        let script = format!(
+            "
+assert_eq ({range}).length(), {expected}
+assert_eq ({range}).length(), koto.size {range}"
+        );
+        check_script_output(&script, ());
+    }
+
     #[test_case("1..=3", "(1, 2, 3)")]
     #[test_case("(1..=3).reversed()", "(3, 2, 1)")]
     #[test_case("-3..0", "(-3, -2, -1)")]
     #[test_case("(-3..0).reversed()", "(-1, -2, -3)")]
+    #[test_case("(0..).take(3)", "(0, 1, 2)")]
+    #[test_case("(-2..).skip(1).take(2)", "(-1, 0)")]
+    #[test_case("(0..).each(|n| n * 2).take(3)", "(0, 2, 4)")]
+    #[test_case("[1].extend((5..).take(2))", "(1, 5, 6)")]
     fn as_iterator(range: &str, expected: &str) {
         let script = format!(
             "
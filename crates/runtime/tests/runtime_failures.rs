@@ -75,6 +75,50 @@ mod runtime {
                 check_script_fails("assert_eq 0, 1");
             }
 
+            #[test]
+            fn check_assert_eq_message() {
+                check_script_fails_with_error(
+                    "assert_eq 0, 1",
+                    "assertion failed, '0' is not equal to '1'",
+                );
+            }
+
+            #[test]
+            fn check_assert_eq_nested_mismatch() {
+                check_script_fails_with_error(
+                    "assert_eq [1, 2, {name: 'a'}], [1, 2, {name: 'b'}]",
+                    "assertion failed, mismatch at [2].name: 'a' != 'b'",
+                );
+            }
+
+            #[test]
+            fn check_assert_eq_mismatch_in_nested_tuple() {
+                check_script_fails_with_error(
+                    "assert_eq {x: {y: (1, (2, 3))}}, {x: {y: (1, (2, 4))}}",
+                    "assertion failed, mismatch at .x.y[1][1]: '3' != '4'",
+                );
+            }
+
+            #[test]
+            fn check_assert_eq_mismatch_with_non_id_key() {
+                check_script_fails_with_error(
+                    "assert_eq {'a b': [1]}, {'a b': [2]}",
+                    "assertion failed, mismatch at ['a b'][0]: '1' != '2'",
+                );
+            }
+
+            #[test]
+            fn check_assert_eq_mismatched_sizes() {
+                check_script_fails_with_error(
+                    "assert_eq [1, [2, 3]], [1, [2]]",
+                    "assertion failed, mismatch at [1][1]: '3' != (missing)",
+                );
+                check_script_fails_with_error(
+                    "assert_eq {a: 1}, {a: 1, b: 2}",
+                    "assertion failed, mismatch at .b: (missing) != '2'",
+                );
+            }
+
             #[test]
             fn check_assert_ne() {
                 check_script_fails("assert_ne 1, 1");
@@ -84,6 +128,22 @@ mod runtime {
             fn check_assert_near() {
                 check_script_fails("assert_near 1, 2, 0.1");
             }
+
+            #[test]
+            fn check_assert_throws_without_error() {
+                check_script_fails_with_error(
+                    "assert_throws || 42",
+                    "assertion failed, no error was thrown",
+                );
+            }
+
+            #[test]
+            fn check_assert_throws_with_unexpected_error() {
+                check_script_fails_with_error(
+                    "assert_throws (|| throw 'foo'), 'bar'",
+                    "assertion failed, the thrown error 'foo' is not equal to 'bar'",
+                );
+            }
         }
 
         mod type_checks {
@@ -508,6 +568,19 @@ x
         mod iterators {
             use super::*;
 
+            #[test]
+            fn next_back_with_generator() {
+                let script = "
+g = ||
+  yield 1
+g().next_back()
+";
+                check_script_fails_with_error(
+                    script,
+                    "next_back can only be used with bidirectional iterators",
+                );
+            }
+
             #[test]
             fn advance_should_propagate_error() {
                 let script = "\
@@ -553,6 +626,14 @@ g().advance 2
                 );
             }
 
+            #[test]
+            fn count_with_predicate_returning_a_non_bool() {
+                check_script_fails_with_error(
+                    "(1..5).count |n| n",
+                    "expected a Bool to be returned from the predicate, found Number",
+                );
+            }
+
             #[test]
             fn count_should_propagate_error() {
                 let script = "\
@@ -584,6 +665,29 @@ g().advance 2
                 check_script_fails(script);
             }
 
+            #[test]
+            fn inspect_should_propagate_error() {
+                let script = "\
+(1..5)
+  .inspect |_| assert false
+#              ^^^^^^^^^^^^
+  .to_list()
+";
+                check_script_fails_with_span(
+                    script,
+                    Span {
+                        start: Position {
+                            line: 1,
+                            column: 15,
+                        },
+                        end: Position {
+                            line: 1,
+                            column: 27,
+                        },
+                    },
+                );
+            }
+
             #[test]
             fn keep_function_missing_argument() {
                 let script = "\
@@ -628,6 +732,35 @@ g().advance 2
                 check_script_fails(script);
             }
 
+            #[test]
+            fn string_replace_with_empty_pattern() {
+                check_script_fails_with_error(
+                    "'abc'.replace '', 'x'",
+                    "the string to be replaced must not be empty",
+                );
+            }
+
+            #[test]
+            fn string_replace_with_negative_count() {
+                check_script_fails("'abc'.replace 'a', 'x', -1");
+            }
+
+            #[test]
+            fn string_trim_with_non_string_in_set() {
+                let script = "
+'--x--'.trim ('-', 42)
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn string_split_with_non_string_pattern() {
+                let script = "
+'abc'.split(42).to_tuple()
+";
+                check_script_fails(script);
+            }
+
             #[test]
             fn string_split_function_missing_argument() {
                 let script = "\
@@ -665,21 +798,74 @@ g().advance 2
             }
 
             #[test]
-            fn unbounded_range_used_as_iterator() {
+            fn range_without_start_used_as_iterator() {
                 let script = "
-(1..).count()
+(..1).count()
 ";
                 check_script_fails(script);
             }
 
             #[test]
-            fn unbounded_range_used_in_for_loop() {
+            fn range_without_start_used_in_for_loop() {
                 let script = "
-for i in 0..
+for i in ..=10
   print i
 ";
                 check_script_fails(script);
             }
+
+            #[test]
+            fn size_of_range_without_end() {
+                let script = "
+koto.size (0..)
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn range_without_end_collected_into_list() {
+                let script = "
+(0..).to_list()
+";
+                check_script_fails_with_error(
+                    script,
+                    "unbounded ranges can't be collected (range: 0..)",
+                );
+            }
+
+            #[test]
+            fn list_extended_with_range_without_end() {
+                let script = "
+x = [1, 2, 3]
+x.extend 5..
+";
+                check_script_fails_with_error(
+                    script,
+                    "unbounded ranges can't be collected (range: 5..)",
+                );
+            }
+
+            #[test]
+            fn size_of_a_value_without_a_size() {
+                let script = "
+koto.size 42
+";
+                check_script_fails_with_error(
+                    script,
+                    "expected a value with a defined size, found Number",
+                );
+            }
+
+            #[test]
+            fn is_empty_with_a_value_without_a_size() {
+                let script = "
+koto.is_empty true
+";
+                check_script_fails_with_error(
+                    script,
+                    "expected a value with a defined size, found Bool",
+                );
+            }
         }
 
         mod function_calls {
@@ -773,6 +959,42 @@ x[3] = 3
                 check_script_fails(script);
             }
 
+            #[test]
+            fn negative_index_out_of_bounds_list() {
+                let script = "
+x = [1, 2, 3]
+x[-5]
+";
+                check_script_fails_with_error(script, "index out of bounds - index: -5, size: 3");
+            }
+
+            #[test]
+            fn negative_range_bound_out_of_bounds() {
+                let script = "
+x = [1, 2, 3]
+x[-5..]
+";
+                check_script_fails_with_error(script, "index out of bounds - index: -5, size: 3");
+            }
+
+            #[test]
+            fn negative_index_of_map() {
+                let script = "
+x = {foo: 42}
+x[-1]
+";
+                check_script_fails_with_error(script, "negative indices aren't allowed ('-1')");
+            }
+
+            #[test]
+            fn negative_index_of_unbounded_range() {
+                let script = "
+x = 10..
+x[-1]
+";
+                check_script_fails_with_error(script, "negative indices aren't allowed ('-1')");
+            }
+
             #[test]
             fn index_out_of_bounds_range() {
                 let script = "
@@ -813,6 +1035,18 @@ x.insert (1, [2, 3]), 'hello'
                 check_script_fails(script);
             }
 
+            #[test]
+            fn contains_key_with_list() {
+                let script = "
+x = {}
+x.contains_key [1, 2]
+";
+                check_script_fails_with_error(
+                    script,
+                    "only hashable values can be used as value keys",
+                );
+            }
+
             #[test]
             fn missing_entry() {
                 let script = "\
@@ -928,6 +1162,34 @@ x = '{foo}'
                 check_script_fails(script);
             }
 
+            #[test]
+            fn hex_representation_of_string() {
+                let script = "
+x = 'abc'
+'{x:x}'
+";
+                check_script_fails_with_error(script, "expected Number, found String");
+            }
+
+            #[test]
+            fn substr_bytes_mid_codepoint() {
+                let script = "
+'héllo'.substr_bytes 0, 2
+";
+                check_script_fails_with_error(script, "0..2 doesn't lie on character boundaries");
+            }
+
+            #[test]
+            fn substr_bytes_out_of_bounds() {
+                let script = "
+'abc'.substr_bytes 1, 4
+";
+                check_script_fails_with_error(
+                    script,
+                    "1..4 is out of bounds for a string with 3 bytes",
+                );
+            }
+
             #[test]
             fn invalid_raw_string_delimiter() {
                 // 256 #s in the delimiter is over the limit
@@ -991,6 +1253,262 @@ f -1
             }
         }
 
+        mod two_argument_functions {
+            use super::*;
+
+            #[test]
+            fn hypot_with_non_number_arg() {
+                check_script_fails("number.hypot 3, 'x'");
+            }
+
+            #[test]
+            fn atan2_with_missing_arg() {
+                check_script_fails("number.atan2 1");
+            }
+
+            #[test]
+            fn pow_with_non_number_arg() {
+                check_script_fails("number.pow 2, 'x'");
+            }
+
+            #[test]
+            fn pow_with_too_many_args() {
+                check_script_fails("number.pow 2, 3, 4");
+            }
+        }
+
+        mod integer_functions {
+            use super::*;
+
+            #[test]
+            fn factorial_overflow() {
+                check_script_fails_with_error(
+                    "21.factorial()",
+                    "the factorial of 21 is too large to be represented",
+                );
+            }
+
+            #[test]
+            fn gcd_with_negative_input() {
+                check_script_fails_with_error(
+                    "number.gcd 12, -18",
+                    "expected a non-negative integer, found -18",
+                );
+            }
+
+            #[test]
+            fn lcm_with_float_input() {
+                check_script_fails_with_error(
+                    "number.lcm 1.5, 2",
+                    "expected a non-negative integer, found 1.5",
+                );
+            }
+        }
+
+        mod number_parsing {
+            use super::*;
+
+            #[test]
+            fn parse_int_with_invalid_digit() {
+                check_script_fails_with_error(
+                    "number.parse_int 'fg', 16",
+                    "invalid digit 'g' at position 1 in 'fg' (radix 16)",
+                );
+            }
+
+            #[test]
+            fn parse_int_with_invalid_radix() {
+                check_script_fails_with_error(
+                    "number.parse_int '1', 37",
+                    "the radix must be within 2..=36, found 37",
+                );
+            }
+
+            #[test]
+            fn parse_int_out_of_range() {
+                check_script_fails_with_error(
+                    "number.parse_int '99999999999999999999'",
+                    "'99999999999999999999' is out of range for a 64-bit integer",
+                );
+            }
+
+            #[test]
+            fn parse_float_malformed() {
+                check_script_fails_with_error(
+                    "number.parse_float '1.2.3'",
+                    "unexpected '.' at position 3 in '1.2.3'",
+                );
+            }
+
+            #[test]
+            fn parse_float_empty() {
+                check_script_fails_with_error(
+                    "number.parse_float ''",
+                    "expected a number, found an empty string",
+                );
+            }
+        }
+
+        mod compare {
+            use super::*;
+
+            #[test]
+            fn maps() {
+                check_script_fails_with_error(
+                    "koto.compare {a: 1}, {b: 2}",
+                    "unable to perform operation '<' with 'Map' and 'Map'",
+                );
+            }
+
+            #[test]
+            fn ranges() {
+                check_script_fails_with_error(
+                    "koto.compare 0..1, 1..2",
+                    "unable to perform operation '<' with 'Range' and 'Range'",
+                );
+            }
+
+            #[test]
+            fn functions() {
+                check_script_fails_with_error(
+                    "koto.compare (|| 1), (|| 2)",
+                    "unable to perform operation '<' with 'Function' and 'Function'",
+                );
+            }
+        }
+
+        mod make_range {
+            use super::*;
+
+            #[test]
+            fn non_integer_start() {
+                check_script_fails_with_error(
+                    "koto.range 1.5, 3",
+                    "expected an integer for the range's start, found 1.5",
+                );
+            }
+
+            #[test]
+            fn non_number_end() {
+                check_script_fails_with_error(
+                    "koto.range 1, 'x'",
+                    "expected an integer for the range's end, found String",
+                );
+            }
+        }
+
+        mod to_string {
+            use super::*;
+
+            #[test]
+            fn display_returning_a_non_string() {
+                let script = "
+foo =
+  @display: || 42
+koto.to_string foo
+";
+                check_script_fails_with_error(
+                    script,
+                    "expected String as @display result, found Number",
+                );
+            }
+        }
+
+        mod meta_introspection {
+            use super::*;
+
+            #[test]
+            fn invalid_meta_key() {
+                check_script_fails_with_error(
+                    "koto.meta {}, '@foo'",
+                    "'@foo' is not a valid meta key",
+                );
+            }
+
+            #[test]
+            fn named_meta_key_without_a_name() {
+                check_script_fails_with_error(
+                    "koto.with_meta_key {}, '@meta', 42",
+                    "'@meta' is not a valid meta key",
+                );
+            }
+        }
+
+        mod frozen_values {
+            use super::*;
+
+            #[test]
+            fn list_push() {
+                let script = "
+x = koto.freeze [1, 2, 3]
+assert_eq x[0], 1
+assert_eq (koto.size x), 3
+x.push 4
+";
+                check_script_fails_with_error(script, "unable to modify a frozen List");
+            }
+
+            #[test]
+            fn list_index_assign() {
+                let script = "
+x = koto.freeze [1, 2, 3]
+assert_eq x.first(), 1
+x[0] = 99
+";
+                check_script_fails_with_error(script, "unable to modify a frozen List");
+            }
+
+            #[test]
+            fn list_sort() {
+                let script = "
+x = koto.freeze [3, 2, 1]
+x.sort()
+";
+                check_script_fails_with_error(script, "unable to modify a frozen List");
+            }
+
+            #[test]
+            fn map_insert() {
+                let script = "
+x = koto.freeze {foo: 42}
+assert_eq x.foo, 42
+x.insert 'bar', 99
+";
+                check_script_fails_with_error(script, "unable to modify a frozen Map");
+            }
+
+            #[test]
+            fn deep_frozen_inner_list() {
+                let script = "
+x = koto.deep_freeze {foo: {bar: [1, 2, 3]}}
+assert_eq x.foo.bar[0], 1
+x.foo.bar.push 4
+";
+                check_script_fails_with_error(script, "unable to modify a frozen List");
+            }
+
+            #[test]
+            fn deep_frozen_cyclic_map() {
+                let script = "
+x = {foo: [1, 2, 3]}
+x.foo.push x
+x.self = x
+x = koto.deep_freeze x
+x.foo[3].self.foo[0] = 99
+";
+                check_script_fails_with_error(script, "unable to modify a frozen List");
+            }
+
+            #[test]
+            fn map_access_assign() {
+                let script = "
+x = koto.freeze {foo: 42}
+x.foo = 99
+";
+                check_script_fails_with_error(script, "unable to modify a frozen Map");
+            }
+        }
+
         mod stdio {
             use super::*;
 
@@ -1006,5 +1524,33 @@ f -1
                 check_script_fails_with_error("print 'test'", "stdout is unavailable");
             }
         }
+
+        mod rethrow {
+            use super::*;
+
+            #[test]
+            fn rethrown_error_keeps_original_span() {
+                let script = "\
+f = ||
+  try
+    [1, 2, 3][10]
+#   ^^^^^^^^^
+  catch _
+    throw
+f()
+";
+                check_script_fails_with_error_span(
+                    script,
+                    "index out of bounds - index: 10, size: 3",
+                    Span {
+                        start: Position { line: 2, column: 4 },
+                        end: Position {
+                            line: 2,
+                            column: 13,
+                        },
+                    },
+                );
+            }
+        }
     }
 }
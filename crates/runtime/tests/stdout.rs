@@ -80,6 +80,17 @@ Hello from @display
         check_logged_output(script, "[1] 2 + 2: 4\n");
     }
 
+    #[test]
+    fn tap() {
+        let script = "
+x = koto.tap 1 + 2
+y = koto.tap [x, 'foo'], 'list'
+print x, y
+";
+
+        check_logged_output(script, "3\nlist: [3, 'foo']\n(3, [3, 'foo'])\n");
+    }
+
     #[test]
     fn debug_map_no_type() {
         let script = "
@@ -108,6 +108,152 @@ a = 99
         }
     }
 
+    mod number_constants {
+        use super::*;
+
+        #[test]
+        fn pi() {
+            check_script_output("(number.pi - 3.14159).abs() < 1e-5", true);
+        }
+
+        #[test]
+        fn tau_and_e() {
+            let script = "
+from number import e, pi, tau
+tau == pi * 2, (e - 2.71828).abs() < 1e-5
+";
+            check_script_output(script, tuple(&[true.into(), true.into()]));
+        }
+
+        #[test]
+        fn infinity_and_nan() {
+            let script = "
+from number import infinity, nan, negative_infinity
+infinity > 1e308, negative_infinity < -1e308, nan.is_nan()
+";
+            check_script_output(script, tuple(&[true.into(), true.into(), true.into()]));
+        }
+
+        #[test]
+        fn epsilon() {
+            check_script_output("number.epsilon", f64::EPSILON);
+        }
+    }
+
+    mod two_argument_functions {
+        use super::*;
+
+        #[test]
+        fn atan2() {
+            let script = "
+from number import atan2, pi
+(atan2(1, 1) - pi / 4).abs() < 1e-12
+";
+            check_script_output(script, true);
+        }
+
+        #[test]
+        fn hypot() {
+            check_script_output("number.hypot 3, 4", 5.0);
+        }
+
+        #[test]
+        fn pow() {
+            check_script_output("number.pow 2, 10", 1024);
+            check_script_output("3.pow 2", 9);
+            check_script_output("number.pow 4, 0.5", 2.0);
+            check_script_output("number.pow 2, -2", 0.25);
+        }
+    }
+
+    mod integer_functions {
+        use super::*;
+
+        #[test]
+        fn gcd() {
+            check_script_output("number.gcd 12, 18", 6);
+        }
+
+        #[test]
+        fn lcm() {
+            check_script_output("number.lcm 4, 6", 12);
+        }
+
+        #[test]
+        fn round_ties_to_even() {
+            check_script_output(
+                "2.5.round(), 3.5.round(), -2.5.round()",
+                number_tuple(&[2, 4, -2]),
+            );
+        }
+
+        #[test]
+        fn round_half_up() {
+            check_script_output("number.round_half_up 2.5", 3);
+        }
+
+        #[test]
+        fn round_nan() {
+            check_script_output(
+                "
+x = number.nan
+(x.round().is_nan(), (x.round 2).is_nan(), x.round_half_up().is_nan())
+",
+                tuple(&[true.into(), true.into(), true.into()]),
+            );
+        }
+
+        #[test]
+        fn round_infinity() {
+            check_script_output(
+                "
+x = number.infinity
+(x.round(), (-x).round(), x.round -2, x.round_half_up 3)
+",
+                tuple(&[
+                    f64::INFINITY.into(),
+                    f64::NEG_INFINITY.into(),
+                    f64::INFINITY.into(),
+                    f64::INFINITY.into(),
+                ]),
+            );
+        }
+
+        #[test]
+        fn round_large_number_to_digits() {
+            check_script_output("1e300.round 100", 1e300);
+        }
+
+        #[test]
+        fn round_to_digits() {
+            check_script_output("number.round 2.71828, 2", 2.72);
+        }
+
+        #[test]
+        fn factorial() {
+            check_script_output("10.factorial()", 3628800);
+        }
+    }
+
+    mod number_parsing {
+        use super::*;
+
+        #[test]
+        fn parse_int_with_radix() {
+            check_script_output("number.parse_int 'ff', 16", 255);
+        }
+
+        #[test]
+        fn parse_int_with_sign() {
+            check_script_output("number.parse_int '-123'", -123);
+        }
+
+        #[test]
+        fn parse_float() {
+            check_script_output("number.parse_float '-1.25'", -1.25);
+        }
+    }
+
     mod logic {
         use super::*;
 
@@ -159,6 +305,62 @@ x = 99
 x or 42";
             check_script_output(script, 99);
         }
+
+        mod truthiness {
+            use super::*;
+
+            // Evaluates each value with `if`, `while`, `and`, `or`, and `not`,
+            // checking that each construct agrees on the value's truthiness.
+            fn check_truthiness(value: &str, expected: bool) {
+                let script = format!(
+                    "
+value = {value}
+from_if = if value then true else false
+from_while = false
+while value
+  from_while = true
+  break
+from_and = (value and true) == true
+from_or = not ((value or false) == false)
+from_not = not not value
+from_if, from_while, from_and, from_or, from_not
+"
+                );
+                check_script_output(&script, tuple(&vec![expected.into(); 5]));
+            }
+
+            #[test]
+            fn null_and_false_are_falsy() {
+                check_truthiness("null", false);
+                check_truthiness("false", false);
+            }
+
+            #[test]
+            fn true_is_truthy() {
+                check_truthiness("true", true);
+            }
+
+            #[test]
+            fn numbers_are_truthy() {
+                check_truthiness("0", true);
+                check_truthiness("-1.5", true);
+                check_truthiness("number.nan", true);
+            }
+
+            #[test]
+            fn strings_are_truthy() {
+                check_truthiness("''", true);
+                check_truthiness("'false'", true);
+            }
+
+            #[test]
+            fn empty_containers_are_truthy() {
+                check_truthiness("[]", true);
+                check_truthiness("()", true);
+                check_truthiness("{}", true);
+                check_truthiness("0..0", true);
+            }
+        }
     }
 
     mod assignment {
@@ -222,6 +424,32 @@ a, b, c
     mod ranges {
         use super::*;
 
+        #[test]
+        fn make_range_with_computed_bounds() {
+            let script = "
+n = 2
+koto.range(n, n * 3).to_tuple()
+";
+            check_script_output(script, number_tuple(&[2, 3, 4, 5]));
+        }
+
+        #[test]
+        fn make_inclusive_range() {
+            let script = "
+r = koto.range 1, 4, true
+r.to_tuple(), r == (1..=4)
+";
+            check_script_output(script, tuple(&[number_tuple(&[1, 2, 3, 4]), true.into()]));
+        }
+
+        #[test]
+        fn make_range_without_end() {
+            let script = "
+koto.range(10, null).take(3).to_tuple()
+";
+            check_script_output(script, number_tuple(&[10, 11, 12]));
+        }
+
         #[test]
         fn range() {
             check_script_output("0..10", KRange::from(0..10));
@@ -327,6 +555,36 @@ a, b, c
         fn tuple_slice_from_slice_at_end() {
             check_script_output("(0, 1, 2, 3, 4, 5)[2..][2..]", number_tuple(&[4, 5]));
         }
+
+        #[test]
+        fn tuple_negative_index() {
+            check_script_output("(1, 2, 3)[-1]", 3);
+            check_script_output("(1, 2, 3)[-2]", 2);
+        }
+
+        #[test]
+        fn tuple_range_with_negative_bounds() {
+            check_script_output("(1, 2, 3, 4)[-2..]", number_tuple(&[3, 4]));
+            check_script_output("(1, 2, 3, 4)[..-3]", number_tuple(&[1]));
+        }
+
+        #[test]
+        fn get_in_range() {
+            check_script_output("(10, 20, 30).get 1", 20);
+        }
+
+        #[test]
+        fn get_out_of_range() {
+            check_script_output("(10, 20, 30).get 3", KValue::Null);
+            check_script_output("(10, 20, 30).get 3, 'default'", "default");
+        }
+
+        #[test]
+        fn get_with_negative_index() {
+            check_script_output("(10, 20, 30).get -1", 30);
+            check_script_output("(10, 20, 30).get -3", 10);
+            check_script_output("(10, 20, 30).get -4, 'default'", "default");
+        }
     }
 
     mod lists {
@@ -415,6 +673,84 @@ a[..]";
             check_script_output(script, number_list(&[10, 20, 30, 40, 50]));
         }
 
+        #[test]
+        fn access_negative_index() {
+            check_script_output("[1, 2, 3][-1]", 3);
+            check_script_output("[1, 2, 3][-3]", 1);
+        }
+
+        #[test]
+        fn access_range_with_negative_bounds() {
+            check_script_output("[1, 2, 3, 4][-3..]", number_list(&[2, 3, 4]));
+            check_script_output("[1, 2, 3, 4][..-1]", number_list(&[1, 2, 3]));
+            check_script_output("[1, 2, 3, 4][..=-2]", number_list(&[1, 2, 3]));
+            check_script_output("[1, 2, 3, 4][1..-1]", number_list(&[2, 3]));
+            check_script_output("[1, 2, 3, 4][-1..1]", number_list::<i64>(&[]));
+        }
+
+        #[test]
+        fn assign_range_with_negative_bounds() {
+            let script = "
+a = [1, 2, 3, 4]
+a[-2..] = 0
+a";
+            check_script_output(script, number_list(&[1, 2, 0, 0]));
+        }
+
+        #[test]
+        fn assign_negative_index() {
+            let script = "
+a = [1, 2, 3]
+a[-1] = 99
+a";
+            check_script_output(script, number_list(&[1, 2, 99]));
+        }
+
+        #[test]
+        fn modifying_a_sub_list_doesnt_modify_the_original() {
+            let script = "
+a = [10, 20, 30, 40, 50]
+b = a[1..4]
+b[0] = 99
+b.push 100
+a[2] = -1
+a.extend b
+";
+            check_script_output(script, number_list(&[10, 20, -1, 40, 50, 99, 30, 40, 100]));
+        }
+
+        #[test]
+        fn modifying_a_large_sub_list_doesnt_modify_the_original() {
+            let script = "
+a = (0..20).to_list()
+b = a[1..]
+b[0] = 99
+a[1] = -1
+assert_eq a, [0, -1].extend 2..20
+assert_eq b, [99].extend 2..20
+";
+            check_script_output(script, ());
+        }
+
+        #[test]
+        fn sub_list_made_while_sorting_the_list() {
+            let script = "
+x = [3, 1, 2]
+x.sort |_| size x[0..1]
+";
+            check_script_output(script, number_list(&[3, 1, 2]));
+        }
+
+        #[test]
+        fn large_sub_list_made_while_sorting_the_list() {
+            let script = "
+x = (0..20).to_list()
+x.sort |n| x[..][n]
+assert_eq x, (0..20).to_list()
+";
+            check_script_output(script, ());
+        }
+
         #[test]
         fn assign_element() {
             let script = "
@@ -490,10 +826,125 @@ l2[1]";
             check_script_output(script, 2);
         }
 
+        #[test]
+        fn copy_shares_nested_data() {
+            let script = "
+x = [1, [2, 3]]
+y = koto.copy x
+y[0] = 99
+y[1][0] = -1
+x
+";
+            check_script_output(script, list(&[1.into(), number_list(&[-1, 3])]));
+        }
+
+        #[test]
+        fn deep_copy_makes_nested_data_unique() {
+            let script = "
+x = [1, [2, 3]]
+y = koto.deep_copy x
+y[0] = 99
+y[1][0] = -1
+x
+";
+            check_script_output(script, list(&[1.into(), number_list(&[2, 3])]));
+        }
+
         #[test]
         fn addition() {
             check_script_output("[1, 2, 3] + [4, 5, 6]", number_list(&[1, 2, 3, 4, 5, 6]));
         }
+
+        #[test]
+        fn map() {
+            check_script_output("[1, 2, 3].map |x| x * x", number_list(&[1, 4, 9]));
+        }
+
+        #[test]
+        fn filter() {
+            check_script_output(
+                "[1, 2, 3, 4, 5, 6].filter |x| x % 3 == 0",
+                number_list(&[3, 6]),
+            );
+        }
+
+        #[test]
+        fn fold() {
+            check_script_output("[1, 2, 3, 4].fold 10, |a, b| a - b", 0);
+        }
+
+        #[test]
+        fn for_each_visits_values_in_order() {
+            let script = "
+result = []
+[1, 2, 3].for_each |n| result.push n
+result
+";
+            check_script_output(script, number_list(&[1, 2, 3]));
+        }
+
+        #[test]
+        fn for_each_stops_when_an_error_is_thrown() {
+            let script = "
+result = []
+try
+  [1, 2, 3, 4].for_each |n|
+    if n == 3 then throw 'stop'
+    result.push n
+catch error
+  result.push error
+result
+";
+            check_script_output(script, list(&[1.into(), 2.into(), "stop".into()]));
+        }
+
+        #[test]
+        fn count_even_numbers() {
+            check_script_output("[1, 2, 3, 4, 5, 6, 8].count |n| n % 2 == 0", 4);
+        }
+
+        #[test]
+        fn count_without_a_predicate() {
+            check_script_output("[1, 2, 3].count()", 3);
+        }
+
+        #[test]
+        fn find_index_over_threshold() {
+            check_script_output("[3, 8, 12, 20].find_index |n| n > 10", 2);
+        }
+
+        #[test]
+        fn find_index_without_a_match() {
+            check_script_output("[3, 8].find_index |n| n > 10", KValue::Null);
+        }
+
+        #[test]
+        fn map_leaves_the_original_list_unchanged() {
+            let script = "
+x = [1, 2, 3]
+y = x.map |n| n + 1
+x
+";
+            check_script_output(script, number_list(&[1, 2, 3]));
+        }
+
+        #[test]
+        fn get_in_range() {
+            check_script_output("[10, 20, 30].get 1", 20);
+        }
+
+        #[test]
+        fn get_out_of_range() {
+            check_script_output("[10, 20, 30].get 3", KValue::Null);
+            check_script_output("[10, 20, 30].get 3, 'default'", "default");
+        }
+
+        #[test]
+        fn get_with_negative_index() {
+            check_script_output("[10, 20, 30].get -1", 30);
+            check_script_output("[10, 20, 30].get -3", 10);
+            check_script_output("[10, 20, 30].get -4, 'default'", "default");
+        }
     }
 
     mod multi_assignment {
@@ -854,6 +1305,266 @@ true
         }
     }
 
+    mod sizes {
+        use super::*;
+
+        #[test]
+        fn list() {
+            check_script_output("size [1, 2, 3]", 3);
+        }
+
+        #[test]
+        fn tuple() {
+            check_script_output("size (1, 2, 3, 4)", 4);
+        }
+
+        #[test]
+        fn map() {
+            check_script_output("size {foo: 42, bar: 99}", 2);
+        }
+
+        #[test]
+        fn string_with_multi_byte_chars() {
+            check_script_output("size 'héllø'", 5);
+        }
+
+        #[test]
+        fn string_with_crlf() {
+            check_script_output("size 'a\\r\\nb'", 3);
+            check_script_output("'a\\r\\nb'[2]", "b");
+        }
+
+        #[test]
+        fn string_size_matches_indexing() {
+            let script = "
+s = 'héllø'
+assert_eq s[size(s) - 1], 'ø'
+assert_eq s[size(s) - 1], s.chars().last()
+";
+            check_script_output(script, ());
+        }
+
+        #[test]
+        fn range() {
+            check_script_output("size 10..=20", 11);
+        }
+    }
+
+    mod compare {
+        use super::*;
+
+        #[test]
+        fn lists() {
+            check_script_output(
+                "koto.compare([1, 2], [1, 3]), koto.compare([1, 3], [1, 2]), koto.compare([1, 2], [1, 2])",
+                number_tuple(&[-1, 1, 0]),
+            );
+        }
+
+        #[test]
+        fn tuples_of_differing_length() {
+            check_script_output(
+                "koto.compare((1, 2), (1, 2, 3)), koto.compare((1, 2, 3), (1, 2))",
+                number_tuple(&[-1, 1]),
+            );
+        }
+
+        #[test]
+        fn nested_containers() {
+            check_script_output("koto.compare([(1, [2, 3])], [(1, [2, 4])])", -1);
+        }
+
+        #[test]
+        fn list_and_tuple_with_matching_contents() {
+            check_script_output("koto.compare([1, 2], (1, 2))", -1);
+        }
+
+        #[test]
+        fn differing_types() {
+            check_script_output(
+                "koto.compare(null, false), koto.compare(1, 'a'), koto.compare([1, 'x'], [1, 2])",
+                number_tuple(&[-1, -1, 1]),
+            );
+        }
+
+        #[test]
+        fn maps_with_comparison_operators() {
+            let script = "
+make_foo = |x|
+  x: x
+  @<: |other| self.x < other.x
+  @>: |other| self.x > other.x
+koto.compare (make_foo 1), (make_foo 2)
+";
+            check_script_output(script, -1);
+        }
+    }
+
+    mod pretty {
+        use super::*;
+
+        #[test]
+        fn nested_map() {
+            let script = "
+x =
+  foo: 42
+  bar:
+    baz: [1, 2]
+    qux: ()
+koto.pretty x
+";
+            check_script_output(
+                script,
+                "\
+{
+  foo: 42,
+  bar: {
+    baz: [
+      1,
+      2
+    ],
+    qux: ()
+  }
+}",
+            );
+        }
+
+        #[test]
+        fn custom_indent() {
+            let script = "
+koto.pretty (1, ['a']), 3
+";
+            check_script_output(
+                script,
+                "\
+(
+   1,
+   [
+      'a'
+   ]
+)",
+            );
+        }
+
+        #[test]
+        fn non_container() {
+            check_script_output("koto.pretty 'hello'", "hello");
+        }
+
+        #[test]
+        fn recursive_list() {
+            let script = "
+x = [1]
+x.push x
+koto.pretty x
+";
+            check_script_output(
+                script,
+                "\
+[
+  1,
+  [...]
+]",
+            );
+        }
+    }
+
+    mod null_and_empty_tuple {
+        use super::*;
+
+        #[test]
+        fn empty_tuple_is_not_equal_to_null() {
+            check_script_output("() == null", false);
+        }
+
+        #[test]
+        fn empty_tuple_is_truthy() {
+            check_script_output("if () then 1 else 2", 1);
+        }
+
+        #[test]
+        fn is_null() {
+            check_script_output(
+                "(koto.is_null null), (koto.is_null ()), (koto.is_null 0)",
+                tuple(&[true.into(), false.into(), false.into()]),
+            );
+        }
+
+        #[test]
+        fn is_empty() {
+            check_script_output(
+                "(koto.is_empty ()), (koto.is_empty [1]), (koto.is_empty null)",
+                tuple(&[true.into(), false.into(), false.into()]),
+            );
+        }
+    }
+
+    mod frozen_values {
+        use super::*;
+
+        #[test]
+        fn freezing_a_list_leaves_the_original_unchanged() {
+            let script = "
+x = [1, 2, 3]
+y = koto.freeze x
+x.push 4
+x[0] = 99
+assert koto.is_frozen y
+assert not koto.is_frozen x
+assert_eq y, [1, 2, 3]
+x
+";
+            check_script_output(script, number_list(&[99, 2, 3, 4]));
+        }
+
+        #[test]
+        fn freezing_a_map_leaves_the_original_unchanged() {
+            let script = "
+x = {foo: 42}
+y = koto.freeze x
+x.foo = 99
+x.bar = -1
+assert koto.is_frozen y
+assert not koto.is_frozen x
+assert_eq y, {foo: 42}
+x.foo + x.bar
+";
+            check_script_output(script, 98);
+        }
+
+        #[test]
+        fn deep_freezing_leaves_the_original_unchanged() {
+            let script = "
+x = {foo: [1, 2, 3]}
+y = koto.deep_freeze x
+x.foo.push 4
+x.bar = 99
+assert koto.is_frozen y
+assert koto.is_frozen y.foo
+assert not koto.is_frozen x
+assert not koto.is_frozen x.foo
+assert_eq y, {foo: [1, 2, 3]}
+x.foo
+";
+            check_script_output(script, number_list(&[1, 2, 3, 4]));
+        }
+
+        #[test]
+        fn deep_freezing_a_cyclic_map() {
+            let script = "
+x = {foo: [1, 2]}
+x.foo.push x
+x.self = x
+y = koto.deep_freeze x
+assert koto.is_frozen y.self
+assert koto.is_frozen y.foo[2].foo
+assert not koto.is_frozen x.self
+y.self.foo[2].foo[1]
+";
+            check_script_output(script, 2);
+        }
+    }
+
     mod if_expressions {
         use super::*;
 
@@ -1098,16 +1809,41 @@ match x
         }
 
         #[test]
-        fn match_tuple_subslice_at_start_with_id() {
+        fn match_tuple_subslice_at_start_with_id() {
+            let script = "
+x = 1, 2, 3, 4, 5
+match x
+  (0, rest...) then rest
+  (rest..., 3, 4, 5) then rest
+  (1, 2, rest...) then rest
+  else 123
+";
+            check_script_output(script, number_tuple(&[1, 2]));
+        }
+
+        #[test]
+        fn match_tuple_subslice_in_middle_with_id() {
+            let script = "
+x = 1, 2, 3, 4, 5
+match x
+  (0, mid..., 5) then mid
+  (a, mid..., z) then a, mid, z
+  else 123
+";
+            check_script_output(
+                script,
+                tuple(&[1.into(), number_tuple(&[2, 3, 4]), 5.into()]),
+            );
+        }
+
+        #[test]
+        fn match_list_subslice_in_middle_with_no_extra_values() {
             let script = "
-x = 1, 2, 3, 4, 5
-match x
-  (0, rest...) then rest
-  (rest..., 3, 4, 5) then rest
-  (1, 2, rest...) then rest
+match [1, 2]
+  (a, mid..., z) then a + z + size mid
   else 123
 ";
-            check_script_output(script, number_tuple(&[1, 2]));
+            check_script_output(script, 3);
         }
 
         #[test]
@@ -1366,6 +2102,20 @@ x
         }
     }
 
+    mod assertions {
+        use super::*;
+
+        #[test]
+        fn assert_throws() {
+            let script = "
+assert_throws || throw 'oops'
+assert_throws (|| throw {message: 'oops'}), {message: 'oops'}
+assert_throws (|| assert 1 > 2), 'assertion failed'
+";
+            check_script_output(script, KValue::Null);
+        }
+    }
+
     mod prelude {
         use super::*;
 
@@ -1602,6 +2352,24 @@ f (1, 2, 3, 4, 5)
                 check_script_output(script, 12);
             }
 
+            #[test]
+            fn ellipsis_with_id_in_middle() {
+                let script = "
+f = |(a, others..., y, z)| a + y + z + size others
+f [1, 2, 3, 4, 5]
+";
+                check_script_output(script, 12);
+            }
+
+            #[test]
+            fn ellipsis_in_middle_with_range() {
+                let script = "
+f = |(a, ..., z)| a, z
+f 0..=100
+";
+                check_script_output(script, number_tuple(&[0, 100]));
+            }
+
             #[test]
             fn ellipsis_at_start_and_end_with_no_extra_values() {
                 let script = "
@@ -2245,6 +3013,71 @@ for _ignored in 0..10
             check_script_output(script, 42);
         }
 
+        #[test]
+        fn for_range_exclusive() {
+            let script = "
+result = []
+for i in -2..2
+  result.push i
+result
+";
+            check_script_output(script, number_list(&[-2, -1, 0, 1]));
+        }
+
+        #[test]
+        fn for_range_inclusive() {
+            let script = "
+result = []
+for i in -2..=2
+  result.push i
+result
+";
+            check_script_output(script, number_list(&[-2, -1, 0, 1, 2]));
+        }
+
+        #[test]
+        fn for_range_empty() {
+            let script = "
+result = []
+for i in 3..3
+  result.push i
+for i in 3..1
+  result.push i
+for i in 3..=2
+  result.push i
+result
+";
+            check_script_output(script, number_list::<i64>(&[]));
+        }
+
+        #[test]
+        fn for_range_large_bounds() {
+            let script = "
+result = []
+for i in 9_000_000_000..=9_000_000_002
+  result.push i
+result
+";
+            check_script_output(
+                script,
+                number_list(&[9_000_000_000_i64, 9_000_000_001, 9_000_000_002]),
+            );
+        }
+
+        #[test]
+        fn for_range_shared_with_variable() {
+            let script = "
+r = 0..3
+count = 0
+for i in r
+  count += i
+for i in r
+  count += i
+count, r
+";
+            check_script_output(script, tuple(&[6.into(), range(0..3)]));
+        }
+
         #[test]
         fn for_list() {
             let script = "
@@ -2761,6 +3594,64 @@ m[0]
             check_script_output(script, tuple(&["foo".into(), 42.into()]));
         }
 
+        #[test]
+        fn entries() {
+            let script = "
+m =
+  @display: || 'Foo'
+  foo: 42
+  bar: 'xyz'
+  baz: -1
+map.entries m
+";
+            check_script_output(
+                script,
+                list(&[
+                    tuple(&["foo".into(), 42.into()]),
+                    tuple(&["bar".into(), "xyz".into()]),
+                    tuple(&["baz".into(), (-1).into()]),
+                ]),
+            );
+        }
+
+        #[test]
+        fn repeated_access_with_keys_removed_in_loop() {
+            let script = "
+m = {a: 1, b: 2, c: 3}
+result = []
+for _ in 0..3
+  result.push m.c
+  m.remove m.keys().next().get()
+  m.insert 'x', 0
+result
+";
+            check_script_output(script, number_list(&[3, 3, 3]));
+        }
+
+        #[test]
+        fn repeated_access_with_keys_added_in_loop() {
+            let script = "
+m = {foo: 1, bar: 2}
+result = []
+for i in 0..3
+  result.push m.foo
+  m.remove 'foo'
+  m.insert 'baz{i}', 0
+  m.insert 'foo', i + 10
+result
+";
+            check_script_output(script, number_list(&[1, 10, 11]));
+        }
+
+        #[test]
+        fn repeated_access_with_different_maps() {
+            let script = "
+maps = [{foo: 1}, {bar: 0, foo: 2}, {baz: 0, qux: 0, foo: 3}, {foo: 4}]
+maps.each(|m| m.foo).to_tuple()
+";
+            check_script_output(script, number_tuple(&[1, 2, 3, 4]));
+        }
+
         #[test]
         fn display_map_without_type() {
             let script = "
@@ -2796,6 +3687,109 @@ foo =
 ";
             check_script_output(script, "display - display");
         }
+
+        #[test]
+        fn to_string_with_overridden_display() {
+            let script = "
+foo =
+  @display: || 'Foo({self.x})'
+  x: 42
+koto.to_string foo
+";
+            check_script_output(script, "Foo(42)");
+        }
+
+        #[test]
+        fn to_string_without_overridden_display() {
+            let script = "
+foo =
+  @type: 'Foo'
+  x: 42
+  y: [1, 'two']
+koto.to_string foo
+";
+            check_script_output(script, "Foo {x: 42, y: [1, 'two']}");
+        }
+
+        #[test]
+        fn contains_key() {
+            let script = "
+x = {foo: 42}
+x.insert 3, null
+x.insert (1, 2), 99
+(x.contains_key 'foo'), (x.contains_key (1, 2)), (x.contains_key 3), (x.contains_key 'bar')
+";
+            check_script_output(
+                script,
+                tuple(&[true.into(), true.into(), true.into(), false.into()]),
+            );
+        }
+
+        #[test]
+        fn filter_values_above_threshold() {
+            let script = "
+x = {a: 5, b: 50, c: 'hi', d: 15, e: 8}
+y = x.filter |_, value| type(value) == 'Number' and value > 10
+y.keys().to_tuple(), y.values().to_tuple()
+";
+            check_script_output(
+                script,
+                tuple(&[tuple(&["b".into(), "d".into()]), number_tuple(&[50, 15])]),
+            );
+        }
+
+        #[test]
+        fn map_values_doubling_numbers() {
+            let script = "
+x = {c: 1, a: 2, b: 3}
+y = x.map_values |n| n * 2
+y.keys().to_tuple(), y.values().to_tuple()
+";
+            check_script_output(
+                script,
+                tuple(&[
+                    tuple(&["c".into(), "a".into(), "b".into()]),
+                    number_tuple(&[2, 4, 6]),
+                ]),
+            );
+        }
+
+        #[test]
+        fn map_values_with_key() {
+            let script = "
+x = {foo: 1, bar: 2}
+y = x.map_values |key, value| '{key}: {value}'
+y.bar
+";
+            check_script_output(script, "bar: 2");
+        }
+
+        #[test]
+        fn map_values_propagates_errors() {
+            let script = "
+x = {foo: 1, bar: 2}
+try
+  x.map_values |value|
+    if value == 2 then throw 'oops'
+    value
+catch error
+  error
+";
+            check_script_output(script, "oops");
+        }
+
+        #[test]
+        fn is_empty_and_size() {
+            let script = "
+x = {}
+y = {foo: 42, bar: 99}
+x.is_empty(), x.size(), y.is_empty(), y.size()
+";
+            check_script_output(
+                script,
+                tuple(&[true.into(), 0.into(), false.into(), 2.into()]),
+            );
+        }
     }
 
     mod chains {
@@ -3163,6 +4157,32 @@ else
         mod optional_chaining {
             use super::*;
 
+            #[test]
+            fn failed_check_on_null_short_circuits_chain() {
+                let script = "
+null?.foo.bar
+";
+                check_script_output(script, KValue::Null);
+            }
+
+            #[test]
+            fn failed_check_on_id_short_circuits_chain() {
+                let script = "
+x = null
+x?.foo.bar[0]()
+";
+                check_script_output(script, KValue::Null);
+            }
+
+            #[test]
+            fn successful_check_on_id_continues_chain() {
+                let script = "
+x = {foo: {bar: 42}}
+x?.foo.bar
+";
+                check_script_output(script, 42);
+            }
+
             #[test]
             fn failed_check_after_lookup() {
                 let script = "
@@ -3306,6 +4326,60 @@ match m.get('bar')?.floor()
 ";
                 check_script_output(script, 0);
             }
+
+            #[test]
+            fn failed_check_on_call_result_before_lookup() {
+                let script = "
+maybe_null = || null
+maybe_null()?.field
+";
+                check_script_output(script, KValue::Null);
+            }
+
+            #[test]
+            fn successful_check_on_call_result_before_lookup() {
+                let script = "
+maybe_null = || {field: 42}
+maybe_null()?.field
+";
+                check_script_output(script, 42);
+            }
+
+            #[test]
+            fn chained_checks_on_call_results() {
+                let script = "
+a = |x| x
+null_from_b = {b: || null}
+value_from_c = {b: || {c: || 42}}
+x = a(null)?.b()?.c()
+y = a(null_from_b)?.b()?.c()
+z = a(value_from_c)?.b()?.c()
+x, y, z
+";
+                check_script_output(script, tuple(&[KValue::Null, KValue::Null, 42.into()]));
+            }
+
+            #[test]
+            fn failed_check_short_circuits_the_chain_only() {
+                // The chain's `null` result is used by the rest of the expression
+                let script = "
+m = {}
+(m.get('x')?.foo) or 42
+";
+                check_script_output(script, 42);
+            }
+
+            #[test]
+            fn errors_are_not_caught_by_checks() {
+                let script = "
+f = || throw 'oops'
+try
+  f()?.foo
+catch error
+  'caught {error}'
+";
+                check_script_output(script, "caught oops");
+            }
         }
     }
 
@@ -3468,6 +4542,79 @@ x.next()
     mod strings {
         use super::*;
 
+        #[test]
+        fn join_list_of_numbers() {
+            check_script_output("string.join [1, 2.5, 3], ', '", "1, 2.5, 3");
+        }
+
+        #[test]
+        fn join_generator_of_strings() {
+            let script = "
+gen = ||
+  yield 'a'
+  yield 'b'
+  yield 'c'
+string.join gen(), '-'
+";
+            check_script_output(script, "a-b-c");
+        }
+
+        #[test]
+        fn join_empty() {
+            check_script_output("string.join [], ', '", "");
+        }
+
+        #[test]
+        fn replace_with_no_matches() {
+            check_script_output("'hello'.replace 'x', 'y'", "hello");
+            check_script_output("'hello'.replace 'x', 'y', 1", "hello");
+        }
+
+        #[test]
+        fn replace_overlapping_candidates() {
+            check_script_output("'aaaa'.replace 'aaa', 'b'", "ba");
+            check_script_output("'ababa'.replace 'aba', 'x'", "xba");
+        }
+
+        #[test]
+        fn replace_with_count() {
+            check_script_output("'a-b-c-d'.replace '-', '+', 2", "a+b+c-d");
+            check_script_output("'a-b-c-d'.replace '-', '+', 0", "a-b-c-d");
+            check_script_output("'a-b-c-d'.replace '-', '+', 10", "a+b+c+d");
+        }
+
+        #[test]
+        fn replace_in_interpolated_string() {
+            let script = "
+x, y = 'é', 'ü'
+'{x}-{x}-{y}'.replace '{x}-', '{y}{y}', 1
+";
+            check_script_output(script, "üüé-ü");
+        }
+
+        #[test]
+        fn trim_multibyte_content() {
+            check_script_output("'  café  '.trim()", "café");
+            check_script_output("'\u{3000}ünï\u{3000}'.trim()", "ünï");
+            check_script_output("'éécaféé'.trim 'é'", "caf");
+        }
+
+        #[test]
+        fn trim_whitespace_only() {
+            check_script_output("' \t\n '.trim()", "");
+            check_script_output("'   '.trim_start()", "");
+            check_script_output("'   '.trim_end()", "");
+        }
+
+        #[test]
+        fn trim_with_set() {
+            check_script_output("'-_-x_y-_-'.trim ('-', '_')", "x_y");
+            check_script_output("'-_-x_y-_-'.trim_start ['-', '_']", "x_y-_-");
+            check_script_output("'-_-x_y-_-'.trim_end ('-', '_')", "-_-x_y");
+            check_script_output("'→é→café→é'.trim ('→', 'é')", "caf");
+            check_script_output("'--x--'.trim ('', '-')", "x");
+        }
+
         #[test]
         fn addition() {
             check_script_output(r#""Hello, " + "World!""#, "Hello, World!");
@@ -3502,11 +4649,46 @@ x.next()
             check_script_output("'hello'[1]", "e");
         }
 
+        #[test]
+        fn index_negative() {
+            check_script_output("'abc'[-2]", "b");
+            check_script_output("'abc'[-3]", "a");
+        }
+
+        #[test]
+        fn index_negative_with_multi_byte_chars() {
+            check_script_output("'héllø'[-1]", "ø");
+            check_script_output("'héllø'[-4]", "é");
+        }
+
+        #[test]
+        fn index_range_with_negative_bounds() {
+            check_script_output("'héllø'[-3..]", "llø");
+            check_script_output("'héllø'[..-1]", "héll");
+        }
+
         #[test]
         fn index_start_and_end() {
             check_script_output("'hello'[1..2]", "e");
             check_script_output("'hello'[1..3]", "el");
-            check_script_output("'föo'[1..3]", "ö");
+            check_script_output("'föo'[1..2]", "ö");
+        }
+
+        #[test]
+        fn match_multi_byte_chars() {
+            let script = "
+match 'héllø'
+  (a, b, rest...) then (a, b, rest)
+";
+            check_script_output(script, tuple(&["h".into(), "é".into(), "llø".into()]));
+        }
+
+        #[test]
+        fn index_multi_byte_chars() {
+            check_script_output("'héllø'[1]", "é");
+            check_script_output("'héllø'[4]", "ø");
+            check_script_output("'héllø'[1..=3]", "éll");
+            check_script_output("'👋 hi'[2..]", "hi");
         }
 
         #[test]
@@ -3689,6 +4871,103 @@ x.baz = x
             check_script_output(script, "{foo: 1, bar: 2, baz: {...}}");
         }
 
+        #[test]
+        fn recursive_list_debug() {
+            let script = "
+x = ['a']
+x.push x
+'{x:?}'
+";
+            check_script_output(script, "['a', [...]]");
+        }
+
+        #[test]
+        fn recursive_map_via_list() {
+            let script = "
+x = {foo: 1}
+x.bar = [x, 2]
+'{x}', '{x.bar}'
+";
+            check_script_output(
+                script,
+                tuple(&[
+                    "{foo: 1, bar: [{...}, 2]}".into(),
+                    "[{foo: 1, bar: [...]}, 2]".into(),
+                ]),
+            );
+        }
+
+        #[test]
+        fn recursive_tuple_via_list() {
+            let script = "
+x = ([1], 2)
+x[0].push x
+koto.to_string x
+";
+            check_script_output(script, "([1, (...)], 2)");
+        }
+
+        fn check_output_with_max_display_depth(
+            script: &str,
+            max_depth: usize,
+            expected_output: impl Into<KValue>,
+        ) {
+            let vm = KotoVm::with_settings(KotoVmSettings {
+                max_display_depth: Some(max_depth),
+                ..Default::default()
+            });
+
+            if let Err(e) = check_script_output_with_vm(vm, script, expected_output) {
+                panic!("{e}");
+            }
+        }
+
+        #[test]
+        fn max_display_depth_nested_lists() {
+            let script = "
+x = [1, [2, [3, [4]]]]
+'{x}'
+";
+            check_output_with_max_display_depth(script, 2, "[1, [2, [...]]]");
+        }
+
+        #[test]
+        fn max_display_depth_mixed_containers() {
+            let script = "
+x = {a: (1, [2]), b: 3}
+koto.to_string x
+";
+            check_output_with_max_display_depth(script, 2, "{a: (1, [...]), b: 3}");
+        }
+
+        #[test]
+        fn max_display_depth_debug() {
+            let script = "
+x = [[[[[1]]]]]
+'{x:?}'
+";
+            check_output_with_max_display_depth(script, 3, "[[[[...]]]]");
+        }
+
+        #[test]
+        fn max_display_depth_not_reached() {
+            let script = "
+x = [1, [2, [3]]]
+'{x}'
+";
+            check_output_with_max_display_depth(script, 3, "[1, [2, [3]]]");
+        }
+
+        #[test]
+        fn repeated_non_recursive_values() {
+            let script = "
+x = [1]
+y = [x, x, {a: x}]
+'{y}'
+";
+            check_script_output(script, "[[1], [1], {a: [1]}]");
+        }
+
         #[test]
         fn strings_in_tuples() {
             let script = "
@@ -3720,13 +4999,51 @@ x = ('foo', 'bar')
         #[test_case("'{51966:X}'", "CAFE"; "hex upper")]
         #[test_case("'{51966:_^10x}'", "___cafe___"; "hex with padding")]
         #[test_case("'{32:b}'", "100000"; "binary")]
+        #[test_case("'{255:x}'", "ff"; "hex lower without prefix")]
+        #[test_case("'{255:#x}'", "0xff"; "hex lower with prefix")]
+        #[test_case("'{255:#X}'", "0xFF"; "hex upper with prefix")]
+        #[test_case("'{5:08b}'", "00000101"; "zero-padded binary")]
+        #[test_case("'{5:#010b}'", "0b00000101"; "zero-padded binary with prefix")]
+        #[test_case("'{8:#o}'", "0o10"; "octal with prefix")]
+        #[test_case("'{255:_>#8x}'", "____0xff"; "hex with prefix and padding")]
+        #[test_case("'{255:+#08x}'", "+0x000ff"; "hex with sign, prefix, and padding")]
+        #[test_case("'{255.9:x}'", "ff"; "hex of float uses integer part")]
         #[test_case("'{64:o}'", "100"; "octal")]
         #[test_case("'{1_000_000:e}'", "1e6"; "exp lower")]
         #[test_case("'{123456:E}'", "1.23456E5"; "exp upper")]
+        #[test_case("'{12345.678:.2e}'", "1.23e4"; "exp with precision")]
+        #[test_case("'{12345.678:e}'", "1.2345678e4"; "exp of float")]
+        #[test_case("'{-12345.678:.3E}'", "-1.235E4"; "exp upper of negative float")]
+        #[test_case("'{0.00012:e}'", "1.2e-4"; "exp with negative exponent")]
+        #[test_case("'{0:e}'", "0e0"; "exp of zero")]
+        #[test_case("'{0.0:.2e}'", "0.00e0"; "exp of zero with precision")]
+        #[test_case("'{12345.678:.0e}'", "1e4"; "exp with zero precision")]
+        #[test_case("'{123456:.2e}'", "1.23e5"; "exp of integer with precision")]
+        #[test_case("'{3.5:?}'", "3.5"; "debug representation of float")]
         #[test_case("'{'hello':?}'", "'hello'"; "debug representation")]
+        #[test_case("'{1234567:,}'", "1,234,567"; "grouped thousands")]
+        #[test_case("'{-1234567.891:,.2}'", "-1,234,567.89"; "grouped thousands with precision")]
+        #[test_case("'{123:,}'", "123"; "grouped thousands with small number")]
+        #[test_case("'{5:+}'", "+5"; "sign on positive number")]
+        #[test_case("'{-5:+}'", "-5"; "sign on negative number")]
+        #[test_case("'{0.5:+.2}'", "+0.50"; "sign with precision")]
+        #[test_case("'{42:+06}'", "+00042"; "sign with zero padding")]
+        #[test_case("'{-42:06}'", "-00042"; "negative number with zero padding")]
+        #[test_case("'{1000:_>+10,}'", "____+1,000"; "sign and grouping with padding")]
+        #[test_case("'{'hello':+,}'", "hello"; "sign and grouping ignored for strings")]
         fn formatted_expression(input: &str, expected: &str) {
             check_script_output(input, expected);
         }
+
+        #[test]
+        fn exp_representation_round_trip() {
+            let script = "
+x = 12345.678
+y = -0.00042
+koto.run('{x:e}') == x, koto.run('{y:e}') == y, koto.run('{x:.2e}')
+";
+            check_script_output(script, tuple(&[true.into(), true.into(), 12300.0.into()]));
+        }
     }
 
     mod raw_strings {
@@ -3886,6 +5203,224 @@ finally
             check_script_output(script, 99);
         }
 
+        #[test]
+        fn finally_runs_after_return_in_try() {
+            let script = "
+log = []
+f = ||
+  try
+    return 42
+  catch _
+    log.push 'catch'
+  finally
+    log.push 'finally'
+f(), log
+";
+            check_script_output(script, tuple(&[42.into(), list(&["finally".into()])]));
+        }
+
+        #[test]
+        fn finally_runs_after_return_in_catch() {
+            let script = "
+log = []
+f = ||
+  try
+    throw 'oops'
+  catch e
+    return e
+  finally
+    log.push 'finally'
+f(), log
+";
+            check_script_output(script, tuple(&["oops".into(), list(&["finally".into()])]));
+        }
+
+        #[test]
+        fn finally_runs_when_catch_rethrows() {
+            let script = "
+log = []
+try
+  try
+    throw 'oops'
+  catch e
+    throw 'rethrown {e}'
+  finally
+    log.push 'finally'
+catch e
+  log.push e
+log
+";
+            check_script_output(script, list(&["finally".into(), "rethrown oops".into()]));
+        }
+
+        #[test]
+        fn finally_runs_after_return_in_nested_try() {
+            let script = "
+log = []
+f = ||
+  try
+    try
+      return 1
+    catch _
+      log.push 'inner catch'
+    finally
+      log.push 'inner'
+  catch _
+    log.push 'catch'
+  finally
+    log.push 'outer'
+f(), log
+";
+            check_script_output(
+                script,
+                tuple(&[1.into(), list(&["inner".into(), "outer".into()])]),
+            );
+        }
+
+        #[test]
+        fn finally_doesnt_modify_return_value() {
+            let script = "
+f = ||
+  x = 1
+  try
+    return x
+  catch _
+    x = -1
+  finally
+    x = 99
+f()
+";
+            check_script_output(script, 1);
+        }
+
+        #[test]
+        fn finally_runs_after_break_and_continue() {
+            let script = "
+log = []
+for i in 0..10
+  try
+    if i == 0 then continue
+    if i == 1 then break
+  catch _
+    log.push 'catch'
+  finally
+    log.push i
+log
+";
+            check_script_output(script, number_list(&[0, 1]));
+        }
+
+        #[test]
+        fn break_from_try_clears_catch_point() {
+            let script = "
+for i in 0..10
+  try
+    break
+  catch _
+    throw 'Unexpected catch'
+try
+  throw 'after loop'
+catch e
+  e
+";
+            check_script_output(script, "after loop");
+        }
+
+        #[test]
+        fn throw_without_value_rethrows_caught_error() {
+            let script = "
+log = []
+f = ||
+  try
+    throw {@type: 'Foo', data: 42}
+  catch error
+    log.push 'logged {koto.type error}'
+    throw
+try
+  f()
+catch error: Foo
+  log.push 'outer {error.data}'
+catch _
+  log.push 'unexpected'
+log
+";
+            check_script_output(script, list(&["logged Foo".into(), "outer 42".into()]));
+        }
+
+        #[test]
+        fn throw_without_value_rethrows_runtime_error() {
+            let script = "
+try
+  try
+    [1, 2, 3][10]
+  catch _
+    throw
+catch error
+  error
+";
+            check_script_output(script, "index out of bounds - index: 10, size: 3");
+        }
+
+        #[test]
+        fn throw_without_value_after_nested_catch() {
+            let script = "
+try
+  try
+    throw 'outer'
+  catch _
+    try
+      throw 'inner'
+    catch _
+      null
+    throw
+catch error
+  error
+";
+            check_script_output(script, "outer");
+        }
+
+        #[test]
+        fn throw_without_value_runs_finally() {
+            let script = "
+log = []
+try
+  try
+    throw 'oops'
+  catch _
+    throw
+  finally
+    log.push 'finally'
+catch error
+  log.push error
+log
+";
+            check_script_output(script, list(&["finally".into(), "oops".into()]));
+        }
+
+        #[test]
+        fn throw_koto_error_with_data() {
+            let script = "
+try
+  throw koto.error 'bad', {code: 1}
+catch error: Error
+  error.message, error.data.code
+catch _
+  throw 'Unexpected error type'
+";
+            check_script_output(script, tuple(&["bad".into(), 1.into()]));
+        }
+
+        #[test]
+        fn throw_koto_error_without_data() {
+            let script = "
+try
+  throw koto.error 'bad'
+catch error
+  koto.type(error), error.message, error.data
+";
+            check_script_output(script, tuple(&["Error".into(), "bad".into(), KValue::Null]));
+        }
+
         #[test]
         fn try_catch_type_check_bool() {
             let script = "
@@ -3922,6 +5457,49 @@ catch error
             check_script_output(script, "Foo: 99");
         }
 
+        #[test]
+        fn try_catch_type_check_dispatches_on_thrown_type() {
+            let script = "
+f = |x|
+  try
+    throw x
+  catch n: Number
+    'number {n}'
+  catch s: String
+    'string {s}'
+  catch foo: Foo
+    'foo {foo.data}'
+  catch other
+    'other {koto.type other}'
+
+f(42), f('hi'), f({@type: 'Foo', data: 99}), f({@type: 'Bar'})
+";
+            check_script_output(
+                script,
+                tuple(&[
+                    "number 42".into(),
+                    "string hi".into(),
+                    "foo 99".into(),
+                    "other Bar".into(),
+                ]),
+            );
+        }
+
+        #[test]
+        fn try_catch_type_check_on_runtime_error() {
+            let script = "
+try
+  [1, 2, 3][10]
+catch error: Number
+  throw 'Caught number'
+catch error: String
+  'String: {error}'
+catch error
+  throw 'Fallback'
+";
+            check_script_output(script, "String: index out of bounds - index: 10, size: 3");
+        }
+
         #[test]
         fn try_catch_type_check_string() {
             let script = "
@@ -4519,6 +6097,127 @@ a.x + a.y # The meta map's y entry is hidden by the data entry
         }
     }
 
+    mod computed_properties {
+        use super::*;
+
+        #[test]
+        fn getter() {
+            let script = "
+foo =
+  x: 10
+  @get double_x: || self.x * 2
+foo.double_x
+";
+            check_script_output(script, 20);
+        }
+
+        #[test]
+        fn getter_is_hidden_by_data_entry() {
+            let script = "
+foo =
+  x: 10
+  @get x: || 99
+foo.x
+";
+            check_script_output(script, 10);
+        }
+
+        #[test]
+        fn setter() {
+            let script = "
+foo =
+  x: 10
+  @set double_x: |value| self.x = value / 2
+foo.double_x = 42
+foo.x, koto.size foo
+";
+            check_script_output(script, tuple(&[21.0.into(), 1.into()]));
+        }
+
+        #[test]
+        fn getter_and_setter_in_base() {
+            let script = "
+base =
+  @get total: || self.a + self.b
+  @set total: |value|
+    self.a = value
+    self.b = 0
+foo =
+  @base: base
+  a: 1
+  b: 2
+x = foo.total
+foo.total = 10
+x, foo.total
+";
+            check_script_output(script, number_tuple(&[3, 10]));
+        }
+
+        #[test]
+        fn getter_via_with_meta_key() {
+            let script = "
+foo = koto.with_meta_key {x: 3}, '@get squared', || self.x * self.x
+foo.squared
+";
+            check_script_output(script, 9);
+        }
+    }
+
+    mod meta_introspection {
+        use super::*;
+
+        #[test]
+        fn get_meta_value() {
+            let script = "
+foo =
+  @type: 'Foo'
+  @meta bar: 42
+  x: 1
+koto.meta(foo, '@type'), koto.meta(foo, '@meta bar'), koto.meta(foo, '@display')
+";
+            check_script_output(script, tuple(&["Foo".into(), 42.into(), KValue::Null]));
+        }
+
+        #[test]
+        fn set_type() {
+            let script = "
+foo = koto.with_meta_key {x: 1}, '@type', 'Foo'
+koto.type foo
+";
+            check_script_output(script, "Foo");
+        }
+
+        #[test]
+        fn set_overloaded_operator() {
+            let script = "
+foo = koto.with_meta_key {x: 1}, '@+', |other| self.x + other
+foo + 41
+";
+            check_script_output(script, 42);
+        }
+
+        #[test]
+        fn with_meta_key_leaves_the_input_unchanged() {
+            let script = "
+foo = koto.with_meta_key {x: 1}, '@type', 'Foo'
+bar = koto.with_meta_key foo, '@type', 'Bar'
+koto.type(foo), koto.type(bar)
+";
+            check_script_output(script, tuple(&["Foo".into(), "Bar".into()]));
+        }
+
+        #[test]
+        fn with_meta_key_shares_data_with_the_input() {
+            let script = "
+foo = {x: 1}
+bar = koto.with_meta_key foo, '@type', 'Bar'
+foo.x = 99
+koto.type(foo), bar.x
+";
+            check_script_output(script, tuple(&["Map".into(), 99.into()]));
+        }
+    }
+
     mod base_access {
         use super::*;
 
@@ -4,10 +4,12 @@ mod macros;
 mod rect;
 mod vec2;
 mod vec3;
+mod vec4;
 
 pub use rect::Rect;
 pub use vec2::Vec2;
 pub use vec3::Vec3;
+pub use vec4::Vec4;
 
 use koto_runtime::{derive::koto_fn, prelude::*};
 
@@ -70,6 +72,46 @@ pub fn make_module() -> KMap {
         fn vec3(v: Vec3) -> Vec3 {
             v
         }
+
+        fn vec4() -> Vec4 {
+            (0.0, 0.0, 0.0, 0.0).into()
+        }
+
+        fn vec4(x: f64) -> Vec4 {
+            (x, 0.0, 0.0, 0.0).into()
+        }
+
+        fn vec4(x: f64, y: f64) -> Vec4 {
+            (x, y, 0.0, 0.0).into()
+        }
+
+        fn vec4(x: f64, y: f64, z: f64) -> Vec4 {
+            (x, y, z, 0.0).into()
+        }
+
+        fn vec4(x: f64, y: f64, z: f64, w: f64) -> Vec4 {
+            (x, y, z, w).into()
+        }
+
+        fn vec4(v: &Vec2) -> Vec4 {
+            (v.inner().x, v.inner().y, 0.0, 0.0).into()
+        }
+
+        fn vec4(v: &Vec2, z: f64, w: f64) -> Vec4 {
+            (v.inner().x, v.inner().y, z, w).into()
+        }
+
+        fn vec4(v: &Vec3) -> Vec4 {
+            (v.inner().x, v.inner().y, v.inner().z, 0.0).into()
+        }
+
+        fn vec4(v: &Vec3, w: f64) -> Vec4 {
+            (v.inner().x, v.inner().y, v.inner().z, w).into()
+        }
+
+        fn vec4(v: Vec4) -> Vec4 {
+            v
+        }
     }
 
     let result = KMap::with_type("geometry");
@@ -77,6 +119,7 @@ pub fn make_module() -> KMap {
     result.add_fn("rect", rect);
     result.add_fn("vec2", vec2);
     result.add_fn("vec3", vec3);
+    result.add_fn("vec4", vec4);
 
     result
 }
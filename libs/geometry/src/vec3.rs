@@ -16,6 +16,10 @@ impl Vec3 {
         Self(DVec3::new(x, y, z))
     }
 
+    pub fn inner(&self) -> DVec3 {
+        self.0
+    }
+
     #[koto_get]
     fn x(&self) -> f64 {
         self.0.x
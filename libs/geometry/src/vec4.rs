@@ -0,0 +1,263 @@
+use crate::{
+    Vec2, Vec3, geometry_arithmetic_op, geometry_arithmetic_op_rhs, geometry_comparison_op,
+    geometry_compound_assign_op,
+};
+use glam::DVec4;
+use koto_runtime::{Result, derive::*, prelude::*};
+use std::{fmt, ops};
+
+#[derive(Copy, Clone, PartialEq, KotoCopy, KotoType)]
+#[koto(runtime = koto_runtime, use_copy)]
+pub struct Vec4(DVec4);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Vec4 {
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Self(DVec4::new(x, y, z, w))
+    }
+
+    pub fn inner(&self) -> DVec4 {
+        self.0
+    }
+
+    #[koto_get]
+    fn x(&self) -> f64 {
+        self.0.x
+    }
+
+    #[koto_get]
+    fn y(&self) -> f64 {
+        self.0.y
+    }
+
+    #[koto_get]
+    fn z(&self) -> f64 {
+        self.0.z
+    }
+
+    #[koto_get]
+    fn w(&self) -> f64 {
+        self.0.w
+    }
+
+    #[koto_set]
+    fn set_x(&mut self, value: &KValue) -> Result<()> {
+        match value {
+            KValue::Number(x) => {
+                self.0.x = x.into();
+                Ok(())
+            }
+            unexpected => unexpected_type("a Number", unexpected),
+        }
+    }
+
+    #[koto_set]
+    fn set_y(&mut self, value: &KValue) -> Result<()> {
+        match value {
+            KValue::Number(y) => {
+                self.0.y = y.into();
+                Ok(())
+            }
+            unexpected => unexpected_type("a Number", unexpected),
+        }
+    }
+
+    #[koto_set]
+    fn set_z(&mut self, value: &KValue) -> Result<()> {
+        match value {
+            KValue::Number(z) => {
+                self.0.z = z.into();
+                Ok(())
+            }
+            unexpected => unexpected_type("a Number", unexpected),
+        }
+    }
+
+    #[koto_set]
+    fn set_w(&mut self, value: &KValue) -> Result<()> {
+        match value {
+            KValue::Number(w) => {
+                self.0.w = w.into();
+                Ok(())
+            }
+            unexpected => unexpected_type("a Number", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn dot(&self, other: &Vec4) -> f64 {
+        self.0.dot(other.0)
+    }
+
+    #[koto_method]
+    fn length(&self) -> f64 {
+        self.0.length()
+    }
+
+    #[koto_method]
+    fn normalize(&self) -> Vec4 {
+        Self(self.0.normalize_or_zero())
+    }
+
+    #[koto_get_fallback]
+    fn swizzle(&self, key: &KString) -> Result<Option<KValue>> {
+        if !(1..=4).contains(&key.len()) {
+            return Ok(None);
+        }
+
+        let mut components = [0.0; 4];
+        for (component, c) in components.iter_mut().zip(key.chars()) {
+            *component = match c {
+                'x' => self.0.x,
+                'y' => self.0.y,
+                'z' => self.0.z,
+                'w' => self.0.w,
+                // Keys that aren't made up entirely of components use the default lookup
+                _ => return Ok(None),
+            };
+        }
+
+        let [x, y, z, w] = components;
+        let result = match key.len() {
+            1 => x.into(),
+            2 => Vec2::new(x, y).into(),
+            3 => Vec3::new(x, y, z).into(),
+            _ => Self::new(x, y, z, w).into(),
+        };
+
+        Ok(Some(result))
+    }
+}
+
+impl KotoObject for Vec4 {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(self.to_string());
+        Ok(())
+    }
+
+    fn negate(&self) -> Result<KValue> {
+        Ok(Self(-self.0).into())
+    }
+
+    fn add(&self, other: &KValue) -> Result<KValue> {
+        geometry_arithmetic_op!(self, other, +)
+    }
+
+    fn add_rhs(&self, other: &KValue) -> Result<KValue> {
+        geometry_arithmetic_op_rhs!(self, other, +)
+    }
+
+    fn subtract(&self, other: &KValue) -> Result<KValue> {
+        geometry_arithmetic_op!(self, other, -)
+    }
+
+    fn subtract_rhs(&self, other: &KValue) -> Result<KValue> {
+        geometry_arithmetic_op_rhs!(self, other, -)
+    }
+
+    fn multiply(&self, other: &KValue) -> Result<KValue> {
+        geometry_arithmetic_op!(self, other, *)
+    }
+
+    fn multiply_rhs(&self, other: &KValue) -> Result<KValue> {
+        geometry_arithmetic_op_rhs!(self, other, *)
+    }
+
+    fn divide(&self, other: &KValue) -> Result<KValue> {
+        geometry_arithmetic_op!(self, other, /)
+    }
+
+    fn divide_rhs(&self, other: &KValue) -> Result<KValue> {
+        geometry_arithmetic_op_rhs!(self, other, /)
+    }
+
+    fn add_assign(&mut self, other: &KValue) -> Result<()> {
+        geometry_compound_assign_op!(self, other, +=)
+    }
+
+    fn subtract_assign(&mut self, other: &KValue) -> Result<()> {
+        geometry_compound_assign_op!(self, other, -=)
+    }
+
+    fn multiply_assign(&mut self, other: &KValue) -> Result<()> {
+        geometry_compound_assign_op!(self, other, *=)
+    }
+
+    fn divide_assign(&mut self, other: &KValue) -> Result<()> {
+        geometry_compound_assign_op!(self, other, /=)
+    }
+
+    fn equal(&self, other: &KValue) -> Result<bool> {
+        geometry_comparison_op!(self, other, ==)
+    }
+
+    fn index(&self, index: &KValue) -> Result<KValue> {
+        match index {
+            KValue::Number(n) => match usize::from(n) {
+                0 => Ok(self.x().into()),
+                1 => Ok(self.y().into()),
+                2 => Ok(self.z().into()),
+                3 => Ok(self.w().into()),
+                other => runtime_error!("index out of range (got {other}, should be <= 3)"),
+            },
+            unexpected => unexpected_type("Number", unexpected),
+        }
+    }
+
+    fn is_iterable(&self) -> IsIterable {
+        IsIterable::Iterable
+    }
+
+    fn make_iterator(&self, _vm: &mut KotoVm) -> Result<KIterator> {
+        let v = *self;
+
+        let iter = (0..=3).map(move |i| {
+            let result = match i {
+                0 => v.0.x,
+                1 => v.0.y,
+                2 => v.0.z,
+                3 => v.0.w,
+                _ => unreachable!(),
+            };
+            KIteratorOutput::Value(result.into())
+        });
+
+        Ok(KIterator::with_std_iter(iter))
+    }
+}
+
+impl From<DVec4> for Vec4 {
+    fn from(v: DVec4) -> Self {
+        Self(v)
+    }
+}
+
+impl From<f64> for Vec4 {
+    fn from(x: f64) -> Self {
+        Self::new(x, x, x, x)
+    }
+}
+
+impl From<(f64, f64, f64, f64)> for Vec4 {
+    fn from((x, y, z, w): (f64, f64, f64, f64)) -> Self {
+        Self::new(x, y, z, w)
+    }
+}
+
+impl From<Vec4> for KValue {
+    fn from(vec4: Vec4) -> Self {
+        KObject::from(vec4).into()
+    }
+}
+
+impl fmt::Display for Vec4 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Vec4{{x: {}, y: {}, z: {}, w: {}}}",
+            self.0.x, self.0.y, self.0.z, self.0.w
+        )
+    }
+}
+
+crate::impl_arithmetic_ops!(Vec4);